@@ -1,36 +1,58 @@
 use std::{
+    any::Any,
     fs,
+    panic::{self, AssertUnwindSafe},
     path::{Path, PathBuf},
+    process::ExitCode,
     str::FromStr,
 };
 
 use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
 use deku::ctx::Endian;
 use itertools::Itertools;
-use strum::IntoEnumIterator;
+use rayon::prelude::*;
 use xfbin_lib_rs::{
+    binary::{
+        NuccBinaryParsed, NuccBinaryParsedDeserializer, NuccBinaryParsedSerializer, NuccBinaryType,
+    },
+    game::Game,
     nucc::{nucc_binary::NuccBinary, NuccStructInfo},
-    read_xfbin, write_xfbin, NuccChunkType,
+    raw_chunks::RawChunkMetadata,
+    read_xfbin_bytes, write_xfbin_bytes,
+    xfbin::Xfbin,
+    NuccChunkType,
 };
-use xfbin_nucc_binary::{NuccBinaryParsedDeserializer, NuccBinaryParsedSerializer, NuccBinaryType};
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Parser)]
+#[derive(Parser, Clone)]
 #[clap(name = "nucc_binary_parser")]
 #[clap(author = "SutandoTsukai181")]
 #[clap(version = "0.1.0")]
 #[clap(about = "Unpacks/repacks nuccChunkBinary chunks from XFBIN files into a more usable format", long_about = None)]
+#[clap(after_help = "EXIT CODES:
+    0    All binaries were unpacked/repacked
+    1    The XFBIN, binary or metadata files could not be read or written
+    3    Some binaries were skipped or could not be parsed
+
+Binaries of unsupported types are left as is, and don't change the exit code.
+
+When the input matches multiple XFBINs, they are processed in parallel without prompting,
+and the exit code is the most severe one of all files.")]
 struct Args {
     /// Operation mode.
     #[clap(arg_enum, value_parser)]
     mode: Mode,
 
-    /// Path to XFBIN file.
-    #[clap(value_parser, value_name = "FILE")]
+    /// Path to an XFBIN file, a directory of XFBIN files, or a glob pattern (i.e. "data/**/*bin.xfbin").
+    #[clap(value_parser, value_name = "INPUT")]
     xfbin: PathBuf,
 
-    /// Path to binary file. Default is xfbin path with the new file extension.
+    /// Search directories recursively.
+    #[clap(short = 'R', long, action)]
+    recursive: bool,
+
+    /// Path to binary file. Default is xfbin path with the new file extension. Only allowed for a single XFBIN.
     #[clap(short, long, value_parser, value_name = "FILE")]
     binary: Option<PathBuf>,
 
@@ -38,6 +60,10 @@ struct Args {
     #[clap(short, long, value_parser)]
     selected_version: Option<usize>,
 
+    /// Game the XFBIN is from, used to select the binary format version (i.e. NarutoStorm4, JojoAsbr).
+    #[clap(short, long, value_parser = Game::from_str)]
+    game: Option<Game>,
+
     /// Endianness for reading the binary.
     #[clap(arg_enum, value_parser, default_value = "auto")]
     endian: Endianness,
@@ -46,6 +72,14 @@ struct Args {
     #[clap(short, long, action)]
     overwrite: bool,
 
+    /// Never prompt. Versions are detected by trying each one, and existing binary files are skipped unless --overwrite is given.
+    #[clap(short, long, action)]
+    non_interactive: bool,
+
+    /// Write a JSON report of the unpacked/repacked binaries to stdout. Progress messages are written to stderr.
+    #[clap(short, long, action)]
+    report: bool,
+
     /// Unpack binary to JSON regardless of the optimal format for the binary file.
     #[clap(short, long, action)]
     json: bool,
@@ -61,6 +95,13 @@ enum Mode {
 
     /// Repack a binary chunk into the XFBIN.
     Repack,
+
+    /// Export the data of all unknown chunks as <chunk_name>.<chunk_type>.bin files, with metadata.
+    /// The directory is given with --binary, and defaults to "<xfbin name>_raw".
+    ExportRaw,
+
+    /// Import the data of unknown chunks from a directory written by export-raw.
+    ImportRaw,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
@@ -75,7 +116,7 @@ enum Endianness {
     Big,
 }
 
-#[derive(Subcommand)]
+#[derive(Subcommand, Clone)]
 enum Commands {
     /// List all supported binary types.
     List {
@@ -94,6 +135,295 @@ struct MetaData {
     binary_type: String,
     binary_file_name: String,
     struct_info: NuccStructInfo,
+
+    // Missing in metadata written by older versions
+    /// Version index the binary was unpacked with.
+    #[serde(default)]
+    version: Option<usize>,
+
+    /// Endianness the binary was unpacked with.
+    #[serde(default)]
+    endian: Option<BinaryEndian>,
+
+    /// Hash of the chunk's data when it was unpacked.
+    #[serde(default)]
+    content_hash: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum BinaryEndian {
+    Little,
+    Big,
+}
+
+impl From<BinaryEndian> for Endian {
+    fn from(endian: BinaryEndian) -> Self {
+        match endian {
+            BinaryEndian::Little => Endian::Little,
+            BinaryEndian::Big => Endian::Big,
+        }
+    }
+}
+
+impl From<Endian> for BinaryEndian {
+    fn from(endian: Endian) -> Self {
+        match endian {
+            Endian::Little => BinaryEndian::Little,
+            Endian::Big => BinaryEndian::Big,
+        }
+    }
+}
+
+/// FNV-1a hash of the data, which is stable across builds (unlike std's DefaultHasher).
+fn content_hash(data: &[u8]) -> String {
+    let hash = data.iter().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    });
+
+    format!("fnv1a64:{hash:016x}")
+}
+
+#[derive(Serialize, Default)]
+struct Report {
+    mode: String,
+    xfbin: PathBuf,
+    results: Vec<BinaryResult>,
+    error: Option<String>,
+}
+
+#[derive(Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+enum Status {
+    Done,
+    Unsupported,
+    Skipped,
+    Failed,
+}
+
+#[derive(Serialize)]
+struct BinaryResult {
+    page_index: usize,
+    struct_info: NuccStructInfo,
+    binary_type: Option<String>,
+    version: Option<usize>,
+    file: Option<PathBuf>,
+    status: Status,
+    message: Option<String>,
+}
+
+impl BinaryResult {
+    fn new(page_index: usize, struct_info: &NuccStructInfo) -> Self {
+        Self {
+            page_index,
+            struct_info: struct_info.clone(),
+            binary_type: None,
+            version: None,
+            file: None,
+            status: Status::Done,
+            message: None,
+        }
+    }
+
+    /// Keeps the message, which might have warnings.
+    fn done(self) -> Self {
+        Self {
+            status: Status::Done,
+            ..self
+        }
+    }
+
+    fn unsupported(self) -> Self {
+        Self {
+            status: Status::Unsupported,
+            message: Some("Unsupported binary type".to_string()),
+            ..self
+        }
+    }
+
+    fn skipped(self, message: &str) -> Self {
+        Self {
+            status: Status::Skipped,
+            message: Some(message.to_string()),
+            ..self
+        }
+    }
+
+    fn failed(self, message: &str) -> Self {
+        Self {
+            status: Status::Failed,
+            message: Some(message.to_string()),
+            ..self
+        }
+    }
+}
+
+#[derive(Serialize, Default)]
+struct BatchReport {
+    mode: String,
+    input: PathBuf,
+    files: Vec<Report>,
+    summary: Summary,
+}
+
+#[derive(Serialize, Default)]
+struct Summary {
+    files: usize,
+    failed_files: usize,
+    done: usize,
+    unsupported: usize,
+    skipped: usize,
+    failed: usize,
+}
+
+impl Summary {
+    fn new(reports: &[Report]) -> Self {
+        let count = |status: Status| {
+            reports
+                .iter()
+                .flat_map(|r| r.results.iter())
+                .filter(|r| r.status == status)
+                .count()
+        };
+
+        Self {
+            files: reports.len(),
+            failed_files: reports.iter().filter(|r| r.error.is_some()).count(),
+            done: count(Status::Done),
+            unsupported: count(Status::Unsupported),
+            skipped: count(Status::Skipped),
+            failed: count(Status::Failed),
+        }
+    }
+}
+
+const EXIT_ERROR: u8 = 1;
+const EXIT_PARTIAL: u8 = 3;
+
+fn is_xfbin(path: &Path) -> bool {
+    path.is_file()
+        && path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map_or(false, |e| e.eq_ignore_ascii_case("xfbin"))
+}
+
+fn collect_directory(
+    directory: &Path,
+    recursive: bool,
+    paths: &mut Vec<PathBuf>,
+) -> Result<(), String> {
+    let entries = fs::read_dir(directory).map_err(|err| {
+        format!(
+            "Could not read directory \"{}\": {err}",
+            directory.display()
+        )
+    })?;
+
+    for entry in entries {
+        let path = entry
+            .map_err(|err| {
+                format!(
+                    "Could not read directory \"{}\": {err}",
+                    directory.display()
+                )
+            })?
+            .path();
+
+        if path.is_dir() {
+            if recursive {
+                collect_directory(&path, recursive, paths)?;
+            }
+        } else if is_xfbin(&path) {
+            paths.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the XFBIN files that the input points to, sorted by path.
+fn collect_inputs(input: &Path, recursive: bool) -> Result<Vec<PathBuf>, String> {
+    let mut paths = vec![];
+
+    if input.is_file() {
+        paths.push(input.to_path_buf());
+    } else if input.is_dir() {
+        collect_directory(input, recursive, &mut paths)?;
+    } else {
+        let pattern = input.to_str().unwrap_or_default();
+        for entry in glob::glob(pattern).map_err(|err| format!("Invalid glob pattern: {err}"))? {
+            let path = entry.map_err(|err| format!("Could not read path: {err}"))?;
+
+            if path.is_dir() {
+                collect_directory(&path, recursive, &mut paths)?;
+            } else if path.is_file() {
+                paths.push(path);
+            }
+        }
+    }
+
+    paths.sort();
+    paths.dedup();
+
+    if paths.is_empty() {
+        return Err(format!("No XFBIN files found in \"{}\"", input.display()));
+    }
+
+    Ok(paths)
+}
+
+fn process(args: &Args) -> Report {
+    let mut report = Report {
+        mode: match args.mode {
+            Mode::Unpack => "unpack",
+            Mode::Repack => "repack",
+            Mode::ExportRaw => "export-raw",
+            Mode::ImportRaw => "import-raw",
+        }
+        .to_string(),
+        xfbin: args.xfbin.clone(),
+        ..Default::default()
+    };
+
+    // A panic while reading or converting one file is recorded as its error, instead of aborting the batch
+    let result = panic::catch_unwind(AssertUnwindSafe(|| match args.mode {
+        Mode::Unpack => unpack(args, &mut report),
+        Mode::Repack => repack(args, &mut report),
+        Mode::ExportRaw => export_raw(args, &mut report),
+        Mode::ImportRaw => import_raw(args, &mut report),
+    }))
+    .unwrap_or_else(|payload| Err(format!("Unexpected error: {}", panic_message(&*payload))));
+
+    if let Err(err) = result {
+        eprintln!("Error in \"{}\": {err}", args.xfbin.display());
+        report.error = Some(err);
+    }
+
+    report
+}
+
+/// Returns the message of a caught panic, which is either a &str or a String.
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown panic")
+}
+
+fn exit_code(reports: &[Report]) -> ExitCode {
+    if reports.iter().any(|r| r.error.is_some()) {
+        ExitCode::from(EXIT_ERROR)
+    } else if reports
+        .iter()
+        .flat_map(|r| r.results.iter())
+        .any(|r| matches!(r.status, Status::Skipped | Status::Failed))
+    {
+        ExitCode::from(EXIT_PARTIAL)
+    } else {
+        ExitCode::SUCCESS
+    }
 }
 
 fn binary_path_from_xfbin(
@@ -127,204 +457,618 @@ fn binary_path_from_xfbin(
     output_path
 }
 
-fn unpack(args: Args) {
-    println!(
-        "Reading XFBIN: \"{}\"...",
-        args.xfbin.file_name().unwrap().to_str().unwrap()
-    );
+/// Prints progress messages. They are written to stderr when the JSON report is written to stdout.
+macro_rules! log {
+    ($args:expr) => {
+        if $args.report {
+            eprintln!();
+        } else {
+            println!();
+        }
+    };
+    ($args:expr, $($arg:tt)*) => {
+        if $args.report {
+            eprintln!($($arg)*);
+        } else {
+            println!($($arg)*);
+        }
+    };
+}
+
+fn file_name(path: &Path) -> &str {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default()
+}
+
+/// Asks before overwriting, unless running non-interactively (where files are only overwritten with --overwrite).
+fn confirm_overwrite(args: &Args, path: &Path) -> bool {
+    if args.overwrite || !path.is_file() {
+        return true;
+    }
+
+    !args.non_interactive
+        && dialoguer::Confirm::new()
+            .with_prompt("File already exists. Overwrite?")
+            .interact()
+            .unwrap_or(false)
+}
+
+/// Asks before writing the repacked XFBIN over the input. Without prompts, the input is always replaced,
+/// since that is what repacking is for.
+fn confirm_in_place(args: &Args) -> bool {
+    args.non_interactive || confirm_overwrite(args, &args.xfbin)
+}
+
+fn read_xfbin_file(args: &Args) -> Result<Xfbin, String> {
+    log!(args, "Reading XFBIN: \"{}\"...", file_name(&args.xfbin));
 
-    let xfbin = read_xfbin(&args.xfbin).expect("Could not read XFBIN");
+    fs::read(&args.xfbin)
+        .map_err(|err| format!("Could not read XFBIN: {err}"))
+        .and_then(|bytes| {
+            read_xfbin_bytes(bytes).map_err(|err| format!("Could not parse XFBIN: {err}"))
+        })
+}
+
+fn select_version(
+    args: &Args,
+    binary: &NuccBinary,
+    binary_type: NuccBinaryType,
+    endian: Endian,
+) -> Option<usize> {
+    if let Some(selected_version) = args.selected_version {
+        return Some(selected_version);
+    }
+
+    if let Some(game) = args.game {
+        return Some(game.profile().binary_version(&binary_type));
+    }
+
+    let versions = binary_type.version_options();
+    if versions.len() == 1 {
+        return Some(0);
+    }
 
-    let endianness = match args.endian {
+    // Types without version options have nothing to select from, but can still fail to parse
+    let detected = binary.detect_version(binary_type, endian);
+    if args.non_interactive || versions.is_empty() {
+        return detected;
+    }
+
+    log!(args);
+    log!(args, "Select version:");
+
+    Some(
+        dialoguer::Select::new()
+            .items(&versions)
+            .default(detected.unwrap_or_default())
+            .clear(false)
+            .interact()
+            .unwrap_or(0),
+    )
+}
+
+/// Returns the byte order given with --endian, or None if it should be detected.
+fn selected_endian(args: &Args) -> Option<Endian> {
+    match args.endian {
         Endianness::Auto => None,
         Endianness::Little => Some(Endian::Little),
         Endianness::Big => Some(Endian::Big),
-    };
+    }
+}
+
+fn unpack(args: &Args, report: &mut Report) -> Result<(), String> {
+    let xfbin = read_xfbin_file(args)?;
+
+    let endianness = selected_endian(args);
 
     let mut counter = 0;
+    let mut meta_files = vec![];
     for (page_index, page) in xfbin.pages.iter().enumerate() {
         for nucc_struct in page.structs.iter() {
             if let NuccChunkType::NuccChunkBinary = nucc_struct.chunk_type() {
                 let binary = nucc_struct.downcast_ref::<NuccBinary>().unwrap();
+                let mut result = BinaryResult::new(page_index, &binary.struct_info);
 
-                if let Some((binary_type, endian)) = binary.get_binary_type() {
-                    println!("Found NuccBinaryType: {}", binary_type);
-
-                    let version = if let Some(selected_version) = args.selected_version {
-                        selected_version
-                    } else {
-                        let versions = binary_type.version_options();
-                        if versions.is_empty() {
-                            0
-                        } else {
-                            println!();
-                            println!("Select version:");
-
-                            dialoguer::Select::new()
-                                .items(&binary_type.version_options())
-                                .default(0)
-                                .clear(false)
-                                .interact()
-                                .unwrap_or(0)
-                        }
-                    };
-
-                    let parsed = binary
-                        .parse_data(Some((binary_type, endian)), endianness, version)
-                        .unwrap();
-
-                    let mut extension = parsed.extension(args.json);
-                    extension = if counter != 0 {
-                        format!(".{}", counter)
-                    } else {
-                        String::from("")
-                    } + &extension;
-
-                    let output_path =
-                        binary_path_from_xfbin(&args.xfbin, args.binary.clone(), extension);
-
-                    println!(
-                        "Writing binary file: \"{}\"...",
-                        output_path.file_name().unwrap().to_str().unwrap()
-                    );
-                    if !args.overwrite
-                        && output_path.is_file()
-                        && !dialoguer::Confirm::new()
-                            .with_prompt("File already exists. Overwrite?")
-                            .interact()
-                            .unwrap_or(false)
-                    {
-                        println!("Skipping file.");
-                        println!();
+                let (binary_type, detected_endian) = match binary.get_binary_type() {
+                    Some(binary_type) => binary_type,
+                    None => {
+                        log!(
+                            args,
+                            "Skipping unsupported NuccBinary: {}",
+                            binary.struct_info
+                        );
+                        report.results.push(result.unsupported());
                         continue;
                     }
+                };
 
-                    fs::write(
-                        output_path.clone(),
-                        Vec::<u8>::from(NuccBinaryParsedSerializer(parsed, args.json)),
-                    )
-                    .expect("Could not write binary file");
-
-                    let meta_data = MetaData {
-                        page_index,
-                        binary_type: binary_type.to_string(),
-                        binary_file_name: output_path
-                            .clone()
-                            .file_name()
-                            .unwrap()
-                            .to_str()
-                            .unwrap()
-                            .to_string(),
-                        struct_info: binary.struct_info.clone(),
-                    };
-
-                    let meta_data = serde_json::to_string_pretty(&meta_data).unwrap();
-
-                    let mut meta_path = output_path.clone();
-                    meta_path.set_extension("meta.json");
-
-                    // Don't prompt for overwrite here
-                    println!(
-                        "Writing metadata: \"{}\"...",
-                        meta_path.file_name().unwrap().to_str().unwrap()
-                    );
-                    fs::write(meta_path, meta_data).expect("Could not write output metadata file");
-
-                    counter += 1;
+                log!(args, "Found NuccBinaryType: {}", binary_type);
+                let endian = endianness.unwrap_or(detected_endian);
+                result.binary_type = Some(binary_type.to_string());
+
+                let version = match select_version(args, binary, binary_type, endian) {
+                    Some(version) => version,
+                    None => {
+                        log!(
+                            args,
+                            "Could not detect the version of {}",
+                            binary.struct_info
+                        );
+                        report
+                            .results
+                            .push(result.failed("No version could parse the binary"));
+                        continue;
+                    }
+                };
+                result.version = Some(version);
+
+                let parsed = match binary.parse_data(Some((binary_type, endian)), None, version) {
+                    Ok(parsed) => parsed,
+                    Err(err) => {
+                        log!(args, "Could not parse {}: {err}", binary.struct_info);
+                        report
+                            .results
+                            .push(result.failed(&format!("Could not parse binary: {err}")));
+                        continue;
+                    }
+                };
+
+                let mut extension = parsed.extension(args.json);
+                extension = if counter != 0 {
+                    format!(".{}", counter)
                 } else {
-                    println!("Skipping unsupported NuccBinary: {}", binary.struct_info);
+                    String::from("")
+                } + &extension;
+
+                let output_path =
+                    binary_path_from_xfbin(&args.xfbin, args.binary.clone(), extension);
+                result.file = Some(output_path.clone());
+
+                log!(
+                    args,
+                    "Writing binary file: \"{}\"...",
+                    file_name(&output_path)
+                );
+                if !confirm_overwrite(args, &output_path) {
+                    log!(args, "Skipping file.");
+                    log!(args);
+                    report.results.push(result.skipped("File already exists"));
+                    continue;
                 }
+
+                fs::write(
+                    output_path.clone(),
+                    Vec::<u8>::from(NuccBinaryParsedSerializer(parsed, args.json)),
+                )
+                .map_err(|err| format!("Could not write binary file: {err}"))?;
+
+                let meta_data = MetaData {
+                    page_index,
+                    binary_type: binary_type.to_string(),
+                    binary_file_name: file_name(&output_path).to_string(),
+                    struct_info: binary.struct_info.clone(),
+                    version: Some(version),
+                    endian: Some(endian.into()),
+                    content_hash: Some(content_hash(&binary.data)),
+                };
+
+                let meta_data = serde_json::to_string_pretty(&meta_data).unwrap();
+
+                let mut meta_path = output_path.clone();
+                meta_path.set_extension("meta.json");
+
+                // Don't prompt for overwrite here
+                log!(args, "Writing metadata: \"{}\"...", file_name(&meta_path));
+                fs::write(&meta_path, meta_data)
+                    .map_err(|err| format!("Could not write output metadata file: {err}"))?;
+
+                // Binaries written next to the XFBIN are listed by name, so the files can be moved together
+                meta_files.push(if args.binary.is_some() {
+                    fs::canonicalize(&meta_path)
+                        .unwrap_or(meta_path)
+                        .to_string_lossy()
+                        .to_string()
+                } else {
+                    file_name(&meta_path).to_string()
+                });
+
+                report.results.push(result.done());
+                counter += 1;
             }
         }
     }
 
-    println!();
-    println!("Unpacking done.");
+    if !meta_files.is_empty() {
+        let manifest_path = manifest_path(&args.xfbin);
+        log!(
+            args,
+            "Writing manifest: \"{}\"...",
+            file_name(&manifest_path)
+        );
+
+        let manifest = serde_json::to_string_pretty(&Manifest { meta_files }).unwrap();
+        fs::write(manifest_path, manifest)
+            .map_err(|err| format!("Could not write manifest file: {err}"))?;
+    }
+
+    log!(args);
+    log!(args, "Unpacking done.");
+
+    Ok(())
 }
 
-fn repack(args: Args) {
-    println!(
-        "Reading XFBIN: \"{}\"...",
-        args.xfbin.file_name().unwrap().to_str().unwrap()
-    );
-    let mut xfbin = read_xfbin(&args.xfbin).expect("Could not read XFBIN");
+/// Lists the metadata files written by unpacking an XFBIN, in the manifest next to it.
+#[derive(Serialize, Deserialize, Default)]
+struct Manifest {
+    meta_files: Vec<String>,
+}
+
+fn manifest_path(xfbin_path: &Path) -> PathBuf {
+    xfbin_path.with_extension("manifest.json")
+}
+
+/// Finds the metadata files to repack: the one given with --binary, the ones listed in the XFBIN's manifest,
+/// or the ones in the XFBIN's directory that were named by unpacking it (`<name>.meta.json`, `<name>.<n>.meta.json`).
+fn find_meta_files(args: &Args) -> Result<Vec<PathBuf>, String> {
+    if let Some(binary_path) = &args.binary {
+        return Ok(vec![binary_path.with_extension("meta.json")]);
+    }
+
+    let manifest_path = manifest_path(&args.xfbin);
+    if manifest_path.is_file() {
+        log!(
+            args,
+            "Reading manifest: \"{}\"...",
+            file_name(&manifest_path)
+        );
+
+        let manifest = fs::read(&manifest_path)
+            .map_err(|err| format!("Could not read manifest file: {err}"))?;
+        let manifest: Manifest = serde_json::from_slice(&manifest)
+            .map_err(|err| format!("Could not parse manifest file: {err}"))?;
+
+        return Ok(manifest
+            .meta_files
+            .into_iter()
+            .map(|path| {
+                manifest_path
+                    .parent()
+                    .unwrap_or_else(|| Path::new(""))
+                    .join(path)
+            })
+            .collect());
+    }
 
-    let mut meta_path = if let Some(binary_path) = args.binary {
-        binary_path
+    let stem = args
+        .xfbin
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or_default()
+        .to_string();
+    let directory = args.xfbin.parent().unwrap_or_else(|| Path::new("."));
+    let directory = if directory.as_os_str().is_empty() {
+        Path::new(".")
     } else {
-        args.xfbin.clone()
+        directory
     };
 
-    meta_path.set_extension("meta.json");
+    let mut meta_files = fs::read_dir(directory)
+        .map_err(|err| format!("Could not read directory: {err}"))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            let name = file_name(path);
+            name.strip_prefix(&stem)
+                .and_then(|rest| rest.strip_suffix(".meta.json"))
+                .map_or(false, |counter| {
+                    counter.is_empty()
+                        || counter.strip_prefix('.').map_or(false, |n| {
+                            !n.is_empty() && n.chars().all(|c| c.is_ascii_digit())
+                        })
+                })
+        })
+        .collect_vec();
 
-    println!(
-        "Reading metadata: \"{}\"...",
-        meta_path.file_name().unwrap().to_str().unwrap()
-    );
-    let meta_data = fs::read(meta_path.clone()).expect("Could not read metadata file");
-    let meta_data: MetaData =
-        serde_json::from_slice(&meta_data).expect("Could not parse metadata file");
+    // Sort by counter, so binaries are applied in the order they were unpacked
+    meta_files.sort_by_key(|path| {
+        let name = file_name(path);
+        name[stem.len()..name.len() - ".meta.json".len()]
+            .trim_start_matches('.')
+            .parse::<usize>()
+            .unwrap_or_default()
+    });
+
+    if meta_files.is_empty() {
+        return Err(format!(
+            "Could not find any metadata files for \"{}\"",
+            file_name(&args.xfbin)
+        ));
+    }
 
-    let binary_type =
-        NuccBinaryType::from_str(&meta_data.binary_type).expect("Unexpected NuccBinaryType");
-    println!("Found NuccBinaryType: {}", binary_type);
+    Ok(meta_files)
+}
+
+fn repack_binary(
+    args: &Args,
+    xfbin: &mut Xfbin,
+    meta_path: &Path,
+    repacked: &mut Vec<(usize, NuccStructInfo)>,
+) -> Result<BinaryResult, (Option<BinaryResult>, String)> {
+    log!(args, "Reading metadata: \"{}\"...", file_name(meta_path));
+    let meta_data = fs::read(meta_path)
+        .map_err(|err| (None, format!("Could not read metadata file: {err}")))?;
+    let meta_data: MetaData = serde_json::from_slice(&meta_data)
+        .map_err(|err| (None, format!("Could not parse metadata file: {err}")))?;
+
+    let result = BinaryResult::new(meta_data.page_index, &meta_data.struct_info);
+    let failed = |result: &BinaryResult, message: String| {
+        let result = BinaryResult {
+            file: Some(meta_path.to_path_buf()),
+            ..BinaryResult::new(result.page_index, &result.struct_info)
+        };
+
+        (Some(result), message)
+    };
+
+    let binary_type = NuccBinaryType::from_str(&meta_data.binary_type).map_err(|_| {
+        failed(
+            &result,
+            format!("Unexpected NuccBinaryType: {}", meta_data.binary_type),
+        )
+    })?;
+    log!(args, "Found NuccBinaryType: {}", binary_type);
+
+    let key = (meta_data.page_index, meta_data.struct_info.clone());
+    if repacked.contains(&key) {
+        return Err(failed(
+            &result,
+            format!(
+                "{} was already repacked from another file",
+                meta_data.struct_info
+            ),
+        ));
+    }
 
     let page = xfbin
         .pages
         .get_mut(meta_data.page_index)
-        .unwrap_or_else(|| panic!("Could not find page"));
+        .ok_or_else(|| failed(&result, "Could not find page".to_string()))?;
 
     let nucc_struct = page
         .structs
         .iter_mut()
         .filter(|s| *s.struct_info() == meta_data.struct_info)
         .exactly_one()
-        .map_err(|_| {})
-        .expect("Could not find a unique nucc struct");
+        .map_err(|_| failed(&result, "Could not find a unique nucc struct".to_string()))?;
+
+    let binary = nucc_struct
+        .downcast_mut::<NuccBinary>()
+        .ok_or_else(|| failed(&result, "Nucc struct is not a binary".to_string()))?;
+
+    let mut result = BinaryResult {
+        binary_type: Some(binary_type.to_string()),
+        ..result
+    };
 
-    let mut binary_path = meta_path.clone();
-    binary_path.set_file_name(meta_data.binary_file_name);
+    let mut warnings = vec![];
+    if let Some(hash) = &meta_data.content_hash {
+        if *hash != content_hash(&binary.data) {
+            warnings.push("The chunk changed since it was unpacked".to_string());
+        }
+    }
+
+    // The binary is written back in the byte order it was unpacked with, unless another one was selected
+    let endian = selected_endian(args)
+        .or(meta_data.endian.map(Endian::from))
+        .or(binary.get_binary_type().map(|(_, endian)| endian))
+        .unwrap_or(Endian::Big);
+
+    // The version from the metadata reproduces the unpacked format, unless another one was selected.
+    // Otherwise, the binary that is being replaced is used to detect the version.
+    let version = match (args.selected_version, args.game, meta_data.version) {
+        (None, None, Some(version)) => version,
+        _ => select_version(args, binary, binary_type, endian).unwrap_or_default(),
+    };
+    result.version = Some(version);
+
+    for warning in warnings.iter() {
+        log!(args, "Warning: {warning}");
+    }
+
+    if !warnings.is_empty() {
+        result.message = Some(warnings.join(". "));
+    }
 
-    println!(
+    let binary_path = meta_path.with_file_name(&meta_data.binary_file_name);
+    result.file = Some(binary_path.clone());
+
+    log!(
+        args,
         "Reading binary file: \"{}\"...",
-        binary_path.file_name().unwrap().to_str().unwrap()
+        file_name(&binary_path)
+    );
+    let binary_data = match fs::read(&binary_path) {
+        Ok(data) => data,
+        Err(err) => return Err((Some(result), format!("Could not read binary file: {err}"))),
+    };
+
+    let converted = Box::<dyn NuccBinaryParsed>::try_from(NuccBinaryParsedDeserializer(
+        binary_type,
+        args.json,
+        binary_data,
+    ))
+    .and_then(|parsed| binary.update_data(parsed, endian, version));
+
+    if let Err(err) = converted {
+        return Err((
+            Some(result),
+            format!("Could not convert binary file: {err}"),
+        ));
+    }
+
+    repacked.push(key);
+    Ok(result)
+}
+
+fn repack(args: &Args, report: &mut Report) -> Result<(), String> {
+    let mut xfbin = read_xfbin_file(args)?;
+    let meta_files = find_meta_files(args)?;
+
+    let mut repacked = vec![];
+    for meta_path in meta_files.iter() {
+        match repack_binary(args, &mut xfbin, meta_path, &mut repacked) {
+            Ok(result) => report.results.push(result),
+            Err((Some(result), err)) => {
+                log!(args, "Could not repack \"{}\": {err}", file_name(meta_path));
+                report.results.push(result.failed(&err));
+            }
+            Err((None, err)) => return Err(err),
+        }
+    }
+
+    if repacked.is_empty() {
+        return Err("No binaries could be repacked".to_string());
+    }
+
+    log!(args, "Writing XFBIN: \"{}\"...", file_name(&args.xfbin));
+    if !confirm_in_place(args) {
+        log!(args, "Aborting.");
+        log!(args);
+
+        for result in report.results.iter_mut() {
+            if result.status == Status::Done {
+                result.status = Status::Skipped;
+                result.message = Some("File already exists".to_string());
+            }
+        }
+
+        return Ok(());
+    }
+
+    write_xfbin_bytes(xfbin)
+        .map_err(|err| format!("Could not write XFBIN: {err}"))
+        .and_then(|bytes| {
+            fs::write(&args.xfbin, bytes).map_err(|err| format!("Could not write XFBIN: {err}"))
+        })?;
+
+    log!(args);
+    log!(args, "Repacking done.");
+
+    Ok(())
+}
+
+fn raw_directory(args: &Args) -> PathBuf {
+    args.binary.clone().unwrap_or_else(|| {
+        let stem = args
+            .xfbin
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default();
+
+        args.xfbin.with_file_name(format!("{stem}_raw"))
+    })
+}
+
+fn raw_chunk_result(metadata_path: &Path) -> BinaryResult {
+    let metadata = fs::read(metadata_path)
+        .ok()
+        .and_then(|m| serde_json::from_slice::<RawChunkMetadata>(&m).ok());
+
+    let (page_index, struct_info) = metadata
+        .map(|m| (m.page_index, m.struct_info))
+        .unwrap_or_default();
+
+    BinaryResult {
+        file: Some(metadata_path.to_path_buf()),
+        ..BinaryResult::new(page_index, &struct_info)
+    }
+}
+
+fn export_raw(args: &Args, report: &mut Report) -> Result<(), String> {
+    let xfbin = read_xfbin_file(args)?;
+    let directory = raw_directory(args);
+
+    log!(
+        args,
+        "Exporting unknown chunks to: \"{}\"...",
+        directory.display()
     );
-    let binary_data = fs::read(binary_path).expect("Could not read binary file");
-    let converter = NuccBinaryParsedDeserializer(binary_type, args.json, binary_data);
+    let metadata_paths = xfbin
+        .export_unknown_chunks(&directory)
+        .map_err(|err| format!("Could not export unknown chunks: {err}"))?;
+
+    report
+        .results
+        .extend(metadata_paths.iter().map(|path| raw_chunk_result(path)));
+
+    log!(args);
+    log!(args, "Exported {} chunks.", metadata_paths.len());
 
-    let binary = nucc_struct.downcast_mut::<NuccBinary>().unwrap();
-    binary.update_data(converter.into(), args.selected_version.unwrap_or_default());
+    Ok(())
+}
+
+fn import_raw(args: &Args, report: &mut Report) -> Result<(), String> {
+    let mut xfbin = read_xfbin_file(args)?;
+    let directory = raw_directory(args);
 
-    println!(
-        "Writing XFBIN: \"{}\"...",
-        args.xfbin.file_name().unwrap().to_str().unwrap()
+    log!(
+        args,
+        "Importing unknown chunks from: \"{}\"...",
+        directory.display()
     );
-    if !args.overwrite
-        && args.xfbin.is_file()
-        && !dialoguer::Confirm::new()
-            .with_prompt("File already exists. Overwrite?")
-            .interact()
-            .unwrap_or(false)
-    {
-        println!("Aborting.");
-        println!();
-        return;
+    let metadata_paths = xfbin
+        .import_unknown_chunks(&directory)
+        .map_err(|err| format!("Could not import unknown chunks: {err}"))?;
+
+    log!(args, "Writing XFBIN: \"{}\"...", file_name(&args.xfbin));
+    if !confirm_in_place(args) {
+        log!(args, "Aborting.");
+        return Ok(());
     }
 
-    write_xfbin(xfbin, &args.xfbin).expect("Could not write XFBIN");
+    write_xfbin_bytes(xfbin)
+        .map_err(|err| format!("Could not write XFBIN: {err}"))
+        .and_then(|bytes| {
+            fs::write(&args.xfbin, bytes).map_err(|err| format!("Could not write XFBIN: {err}"))
+        })?;
 
-    println!();
-    println!("Repacking done.");
+    report
+        .results
+        .extend(metadata_paths.iter().map(|path| raw_chunk_result(path)));
+
+    log!(args);
+    log!(args, "Imported {} chunks.", metadata_paths.len());
+
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct BinaryTypeInfo {
+    name: String,
+    versions: Option<Vec<String>>,
+    examples: Option<Vec<String>>,
 }
 
-fn list(print_versions: bool, print_paths: bool) {
+fn list(args: &Args, print_versions: bool, print_paths: bool) {
+    let binary_types = NuccBinaryType::iter()
+        .map(|binary_type| BinaryTypeInfo {
+            name: binary_type.to_string(),
+            versions: print_versions.then(|| binary_type.version_options()),
+            examples: print_paths.then(|| binary_type.examples()),
+        })
+        .collect::<Vec<_>>();
+
+    if args.report {
+        println!("{}", serde_json::to_string_pretty(&binary_types).unwrap());
+        return;
+    }
+
     println!("Supported binary types:");
 
-    for binary_type in NuccBinaryType::iter() {
-        println!("  {}", binary_type);
+    for binary_type in binary_types {
+        println!("  {}", binary_type.name);
 
-        let versions = binary_type.version_options();
-        if print_versions {
+        if let Some(versions) = binary_type.versions {
             if !versions.is_empty() {
                 println!("  versions:");
                 for (i, version) in versions.iter().enumerate() {
@@ -337,9 +1081,9 @@ fn list(print_versions: bool, print_paths: bool) {
             println!();
         }
 
-        if print_paths {
+        if let Some(examples) = binary_type.examples {
             println!("  examples:");
-            for file_path in binary_type.examples() {
+            for file_path in examples {
                 println!("      {}", file_path);
             }
 
@@ -350,26 +1094,86 @@ fn list(print_versions: bool, print_paths: bool) {
     println!();
 }
 
-fn main() {
+fn main() -> ExitCode {
     let args = Args::parse();
 
     // Print header
-    print!("{}", Args::command().render_version());
-    println!("{}", Args::command().get_author().unwrap());
-    println!();
+    log!(args, "{}", Args::command().render_version().trim_end());
+    log!(args, "{}", Args::command().get_author().unwrap());
+    log!(args);
 
-    if let Some(commands) = args.commands {
-        match commands {
-            Commands::List { versions, paths } => {
-                list(versions, paths);
-            }
+    if let Some(Commands::List { versions, paths }) = &args.commands {
+        list(&args, *versions, *paths);
+        log!(args, "Program finished.");
+        return ExitCode::SUCCESS;
+    }
+
+    let inputs = match collect_inputs(&args.xfbin, args.recursive) {
+        Ok(inputs) => inputs,
+        Err(err) => {
+            eprintln!("Error: {err}");
+            return ExitCode::from(EXIT_ERROR);
         }
-    } else {
-        match args.mode {
-            Mode::Unpack => unpack(args),
-            Mode::Repack => repack(args),
-        };
+    };
+
+    // Single files keep the original behavior, including prompts
+    if let [xfbin] = &inputs[..] {
+        let report = process(&Args {
+            xfbin: xfbin.clone(),
+            ..args.clone()
+        });
+
+        if args.report {
+            println!("{}", serde_json::to_string_pretty(&report).unwrap());
+        }
+
+        log!(args, "Program finished.");
+        return exit_code(&[report]);
+    }
+
+    if args.binary.is_some() {
+        eprintln!("Error: --binary can only be used with a single XFBIN");
+        return ExitCode::from(EXIT_ERROR);
+    }
+
+    log!(args, "Processing {} XFBIN files...", inputs.len());
+
+    let reports = inputs
+        .par_iter()
+        .map(|xfbin| {
+            process(&Args {
+                xfbin: xfbin.clone(),
+                non_interactive: true,
+                ..args.clone()
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let batch_report = BatchReport {
+        mode: reports[0].mode.clone(),
+        input: args.xfbin.clone(),
+        summary: Summary::new(&reports),
+        files: reports,
+    };
+
+    let summary = &batch_report.summary;
+    log!(args);
+    log!(
+        args,
+        "Processed {} files ({} failed): {} binaries done, {} unsupported, {} skipped, {} failed.",
+        summary.files,
+        summary.failed_files,
+        summary.done,
+        summary.unsupported,
+        summary.skipped,
+        summary.failed
+    );
+
+    let exit_code = exit_code(&batch_report.files);
+    if args.report {
+        println!("{}", serde_json::to_string_pretty(&batch_report).unwrap());
     }
 
-    println!("Program finished.");
+    log!(args, "Program finished.");
+    exit_code
 }
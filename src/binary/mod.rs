@@ -0,0 +1,208 @@
+mod raw;
+pub mod registry;
+
+use std::{fmt, str::FromStr, sync::OnceLock};
+
+use deku::ctx::Endian;
+use downcast_rs::{impl_downcast, Downcast};
+use regex::Regex;
+
+use crate::error::NuccError;
+
+pub use raw::RawBinary;
+
+/// A file stored in a nuccChunkBinary, parsed into a more usable format.
+pub trait NuccBinaryParsed: Downcast {
+    fn binary_type(&self) -> NuccBinaryType;
+
+    /// Extension of the unpacked file, including the dot.
+    fn extension(&self, use_json: bool) -> String;
+
+    /// Returns the contents of the unpacked file.
+    fn serialize(&self, use_json: bool) -> Vec<u8>;
+
+    /// Returns the binary's data, written with the given byte order and version index of the type's version options.
+    fn write(&self, endian: Endian, version: usize) -> Result<Vec<u8>, NuccError>;
+}
+
+impl_downcast!(NuccBinaryParsed);
+
+/// Type of the file stored in a nuccChunkBinary, which is found by matching the chunk's file path against the type's patterns.
+/// Built-in binary types are stored as-is. Types registered at runtime are `Custom`, with their index in the registry.
+///
+/// Game-specific formats (i.e. character parameter tables) are not built in, so they have to be registered.
+/// Only registered types have version options, which are selected by game with `GameProfile::binary_version`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NuccBinaryType {
+    Dds,
+    Lua,
+    Png,
+    Xml,
+    Custom(usize),
+}
+
+/// Built-in types with the pattern of their file paths.
+const BUILT_IN_TYPES: [(NuccBinaryType, &str); 4] = [
+    (NuccBinaryType::Dds, r"\.dds$"),
+    (NuccBinaryType::Lua, r"\.lua$"),
+    (NuccBinaryType::Png, r"\.png$"),
+    (NuccBinaryType::Xml, r"\.xml$"),
+];
+
+impl NuccBinaryType {
+    /// Returns the registered types from the most recent one, followed by the built-in types.
+    /// Registered types come first so they can override the patterns of the types after them.
+    pub fn iter() -> impl Iterator<Item = NuccBinaryType> {
+        (0..registry::handler_count())
+            .rev()
+            .map(NuccBinaryType::Custom)
+            .chain(BUILT_IN_TYPES.map(|(binary_type, _)| binary_type))
+    }
+
+    /// Regexes that match the chunk file paths of the type, with the byte order of the matched binaries.
+    pub fn patterns(&self) -> Vec<(Regex, Endian)> {
+        // Compiled once, since every binary chunk's path is matched against all types
+        static BUILT_IN_PATTERNS: OnceLock<Vec<(NuccBinaryType, Regex)>> = OnceLock::new();
+
+        if let NuccBinaryType::Custom(index) = self {
+            return registry::get_handler(*index)
+                .map(|handler| handler.patterns)
+                .unwrap_or_default();
+        }
+
+        BUILT_IN_PATTERNS
+            .get_or_init(|| {
+                BUILT_IN_TYPES
+                    .iter()
+                    .map(|(binary_type, pattern)| {
+                        (*binary_type, Regex::new(&format!("(?i){pattern}")).unwrap())
+                    })
+                    .collect()
+            })
+            .iter()
+            .filter(|(binary_type, _)| binary_type == self)
+            .map(|(_, regex)| (regex.clone(), Endian::Big))
+            .collect()
+    }
+
+    /// Names of the versions of the type's layout, usually the games that use each one.
+    /// Empty if the type has a single layout.
+    pub fn version_options(&self) -> Vec<String> {
+        match self {
+            NuccBinaryType::Custom(index) => registry::get_handler(*index)
+                .map(|handler| handler.version_options)
+                .unwrap_or_default(),
+            _ => vec![],
+        }
+    }
+
+    /// Example chunk file paths that are matched by the type's patterns.
+    pub fn examples(&self) -> Vec<String> {
+        match self {
+            NuccBinaryType::Dds => vec!["data/ui/texture.dds".to_string()],
+            NuccBinaryType::Lua => vec!["data/script/script.lua".to_string()],
+            NuccBinaryType::Png => vec!["data/ui/texture.png".to_string()],
+            NuccBinaryType::Xml => vec!["data/param/param.xml".to_string()],
+            NuccBinaryType::Custom(index) => registry::get_handler(*index)
+                .map(|handler| handler.examples)
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl fmt::Display for NuccBinaryType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NuccBinaryType::Dds => write!(f, "DDS"),
+            NuccBinaryType::Lua => write!(f, "LUA"),
+            NuccBinaryType::Png => write!(f, "PNG"),
+            NuccBinaryType::Xml => write!(f, "XML"),
+            NuccBinaryType::Custom(index) => match registry::get_handler(*index) {
+                Some(handler) => write!(f, "{}", handler.name),
+                None => write!(f, "Custom{index}"),
+            },
+        }
+    }
+}
+
+impl FromStr for NuccBinaryType {
+    type Err = ();
+
+    /// Matches the names returned by `Display`, ignoring case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        NuccBinaryType::iter()
+            .find(|binary_type| binary_type.to_string().eq_ignore_ascii_case(s))
+            .ok_or(())
+    }
+}
+
+/// Reads the data of a nuccChunkBinary as the type, with the given byte order and version index.
+pub struct NuccBinaryParsedReader<'a>(pub NuccBinaryType, pub &'a [u8], pub Endian, pub usize);
+
+/// Writes a parsed binary back to nuccChunkBinary data with the given byte order and version index.
+pub struct NuccBinaryParsedWriter(pub Box<dyn NuccBinaryParsed>, pub Endian, pub usize);
+
+/// Converts a parsed binary to the contents of its unpacked file, as JSON if the flag is set.
+pub struct NuccBinaryParsedSerializer(pub Box<dyn NuccBinaryParsed>, pub bool);
+
+/// Reads an unpacked file as the type, from JSON if the flag is set.
+pub struct NuccBinaryParsedDeserializer(pub NuccBinaryType, pub bool, pub Vec<u8>);
+
+impl<'a> TryFrom<NuccBinaryParsedReader<'a>> for Box<dyn NuccBinaryParsed> {
+    type Error = NuccError;
+
+    fn try_from(reader: NuccBinaryParsedReader<'a>) -> Result<Self, Self::Error> {
+        let NuccBinaryParsedReader(binary_type, data, endian, version) = reader;
+
+        match binary_type {
+            NuccBinaryType::Custom(index) => {
+                let handler =
+                    registry::get_handler(index).ok_or(NuccError::BinaryHandlerNotFound)?;
+
+                (handler.reader)(data, endian, version)
+            }
+            _ => Ok(Box::new(RawBinary {
+                binary_type,
+                data: data.to_vec(),
+            })),
+        }
+    }
+}
+
+impl TryFrom<NuccBinaryParsedWriter> for Vec<u8> {
+    type Error = NuccError;
+
+    fn try_from(writer: NuccBinaryParsedWriter) -> Result<Self, Self::Error> {
+        let NuccBinaryParsedWriter(parsed, endian, version) = writer;
+        parsed.write(endian, version)
+    }
+}
+
+impl From<NuccBinaryParsedSerializer> for Vec<u8> {
+    fn from(serializer: NuccBinaryParsedSerializer) -> Self {
+        let NuccBinaryParsedSerializer(parsed, use_json) = serializer;
+        parsed.serialize(use_json)
+    }
+}
+
+impl TryFrom<NuccBinaryParsedDeserializer> for Box<dyn NuccBinaryParsed> {
+    type Error = NuccError;
+
+    fn try_from(deserializer: NuccBinaryParsedDeserializer) -> Result<Self, Self::Error> {
+        let NuccBinaryParsedDeserializer(binary_type, use_json, data) = deserializer;
+
+        match binary_type {
+            NuccBinaryType::Custom(index) => {
+                let handler =
+                    registry::get_handler(index).ok_or(NuccError::BinaryHandlerNotFound)?;
+
+                (handler.deserializer)(data, use_json)
+            }
+            _ => Ok(Box::new(RawBinary::deserialize(
+                binary_type,
+                data,
+                use_json,
+            )?)),
+        }
+    }
+}
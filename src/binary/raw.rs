@@ -0,0 +1,72 @@
+use deku::ctx::Endian;
+use serde::{Deserialize, Serialize};
+
+use super::{NuccBinaryParsed, NuccBinaryType};
+use crate::error::NuccError;
+
+/// A binary that is a complete file on its own, which is unpacked without any changes.
+pub struct RawBinary {
+    pub binary_type: NuccBinaryType,
+    pub data: Vec<u8>,
+}
+
+/// JSON representation of a raw binary, for consistency with the other types when unpacking to JSON.
+#[derive(Serialize, Deserialize)]
+struct RawBinaryJson {
+    data: Vec<u8>,
+}
+
+impl RawBinary {
+    pub fn deserialize(
+        binary_type: NuccBinaryType,
+        data: Vec<u8>,
+        use_json: bool,
+    ) -> Result<Self, NuccError> {
+        let data = if use_json {
+            serde_json::from_slice::<RawBinaryJson>(&data)
+                .map_err(|_| NuccError::InvalidBinary)?
+                .data
+        } else {
+            data
+        };
+
+        Ok(Self { binary_type, data })
+    }
+}
+
+impl NuccBinaryParsed for RawBinary {
+    fn binary_type(&self) -> NuccBinaryType {
+        self.binary_type
+    }
+
+    fn extension(&self, use_json: bool) -> String {
+        if use_json {
+            return String::from(".json");
+        }
+
+        match self.binary_type {
+            NuccBinaryType::Dds => ".dds",
+            NuccBinaryType::Lua => ".lua",
+            NuccBinaryType::Png => ".png",
+            NuccBinaryType::Xml => ".xml",
+            NuccBinaryType::Custom(_) => ".bin",
+        }
+        .to_string()
+    }
+
+    fn serialize(&self, use_json: bool) -> Vec<u8> {
+        if use_json {
+            serde_json::to_vec(&RawBinaryJson {
+                data: self.data.clone(),
+            })
+            .unwrap()
+        } else {
+            self.data.clone()
+        }
+    }
+
+    /// The file is stored as is, so it has no byte order or versions.
+    fn write(&self, _endian: Endian, _version: usize) -> Result<Vec<u8>, NuccError> {
+        Ok(self.data.clone())
+    }
+}
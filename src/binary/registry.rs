@@ -0,0 +1,61 @@
+use std::sync::{OnceLock, RwLock};
+
+use deku::ctx::Endian;
+use regex::Regex;
+
+use super::{NuccBinaryParsed, NuccBinaryType};
+use crate::error::NuccError;
+
+/// Parses the data of a nuccChunkBinary with the given byte order and version index.
+/// Should return an error if the data doesn't match the version, since versions are detected by trying each one.
+pub type NuccBinaryReader =
+    fn(data: &[u8], endian: Endian, version: usize) -> Result<Box<dyn NuccBinaryParsed>, NuccError>;
+
+/// Parses an unpacked file, which is JSON if the flag is set.
+pub type NuccBinaryDeserializer =
+    fn(data: Vec<u8>, use_json: bool) -> Result<Box<dyn NuccBinaryParsed>, NuccError>;
+
+/// A binary type defined outside of the crate. Parsed binaries are written and serialized through
+/// their `NuccBinaryParsed` implementation, which should return the registered type.
+#[derive(Clone)]
+pub struct NuccBinaryHandler {
+    /// Returned by the type's `Display`, and used to find the type in unpacked metadata.
+    pub name: String,
+
+    pub patterns: Vec<(Regex, Endian)>,
+    pub version_options: Vec<String>,
+    pub examples: Vec<String>,
+
+    pub reader: NuccBinaryReader,
+    pub deserializer: NuccBinaryDeserializer,
+}
+
+fn registry() -> &'static RwLock<Vec<NuccBinaryHandler>> {
+    static REGISTRY: OnceLock<RwLock<Vec<NuccBinaryHandler>>> = OnceLock::new();
+    REGISTRY.get_or_init(Default::default)
+}
+
+/// Registers a binary type, which takes priority over the built-in types and the previously registered ones.
+/// Registering a name again replaces its handler and keeps its type.
+pub fn register_binary_type(handler: NuccBinaryHandler) -> NuccBinaryType {
+    let mut handlers = registry().write().unwrap();
+
+    match handlers.iter().position(|h| h.name == handler.name) {
+        Some(index) => {
+            handlers[index] = handler;
+            NuccBinaryType::Custom(index)
+        }
+        None => {
+            handlers.push(handler);
+            NuccBinaryType::Custom(handlers.len() - 1)
+        }
+    }
+}
+
+pub fn get_handler(index: usize) -> Option<NuccBinaryHandler> {
+    registry().read().unwrap().get(index).cloned()
+}
+
+pub(crate) fn handler_count() -> usize {
+    registry().read().unwrap().len()
+}
@@ -0,0 +1,347 @@
+use hashbrown::HashMap;
+
+use crate::error::NuccError;
+
+const CRILAYLA_MAGIC: &[u8] = b"CRILAYLA";
+
+// The first 0x100 bytes of the data are stored uncompressed after the compressed data
+const RAW_HEADER_SIZE: usize = 0x100;
+
+const VLE_LENGTHS: [u32; 4] = [2, 3, 5, 8];
+const MIN_MATCH: usize = 3;
+const MAX_DISTANCE: usize = (1 << 13) + MIN_MATCH - 1;
+const MAX_CANDIDATES: usize = 64;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    #[default]
+    None,
+    Crilayla,
+}
+
+impl Compression {
+    pub fn detect(bytes: &[u8]) -> Compression {
+        if bytes.len() >= 0x10 && bytes.starts_with(CRILAYLA_MAGIC) {
+            Compression::Crilayla
+        } else {
+            Compression::None
+        }
+    }
+
+    pub fn compress(&self, bytes: Vec<u8>) -> Vec<u8> {
+        match self {
+            Compression::None => bytes,
+            Compression::Crilayla => crilayla_compress(&bytes),
+        }
+    }
+}
+
+/// Removes all known compression wrappers from the data.
+pub fn decompress(mut bytes: Vec<u8>) -> Result<Vec<u8>, NuccError> {
+    loop {
+        bytes = match Compression::detect(&bytes) {
+            Compression::None => return Ok(bytes),
+            Compression::Crilayla => crilayla_decompress(&bytes)?,
+        };
+    }
+}
+
+fn read_u32_le(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+/// CRILAYLA streams are read backwards, starting from the last byte of the compressed data.
+struct BackwardBitReader<'a> {
+    data: &'a [u8],
+    position: usize,
+    bit_pool: u8,
+    bits_left: u32,
+}
+
+impl<'a> BackwardBitReader<'a> {
+    fn read(&mut self, bit_count: u32) -> Option<u32> {
+        let mut value = 0u32;
+        let mut bits_read = 0;
+
+        while bits_read < bit_count {
+            if self.bits_left == 0 {
+                self.position = self.position.checked_sub(1)?;
+                self.bit_pool = self.data[self.position];
+                self.bits_left = 8;
+            }
+
+            let bits = self.bits_left.min(bit_count - bits_read);
+            let mask = (1u32 << bits) - 1;
+
+            value = (value << bits) | ((self.bit_pool as u32 >> (self.bits_left - bits)) & mask);
+
+            self.bits_left -= bits;
+            bits_read += bits;
+        }
+
+        Some(value)
+    }
+}
+
+pub fn crilayla_decompress(bytes: &[u8]) -> Result<Vec<u8>, NuccError> {
+    if Compression::detect(bytes) != Compression::Crilayla {
+        return Err(NuccError::InvalidCompressedData);
+    }
+
+    let uncompressed_size = read_u32_le(bytes, 0x08) as usize;
+    let compressed_size = read_u32_le(bytes, 0x0C) as usize;
+
+    let data_end = 0x10 + compressed_size;
+    if bytes.len() < data_end + RAW_HEADER_SIZE {
+        return Err(NuccError::InvalidCompressedData);
+    }
+
+    // Every bit of compressed data decodes to at most 32 bytes, so the size is checked before it's allocated
+    if uncompressed_size > compressed_size.saturating_mul(0x100) {
+        return Err(NuccError::InvalidCompressedData);
+    }
+
+    let mut output = vec![0u8; RAW_HEADER_SIZE + uncompressed_size];
+    output[..RAW_HEADER_SIZE].copy_from_slice(&bytes[data_end..data_end + RAW_HEADER_SIZE]);
+
+    let mut reader = BackwardBitReader {
+        data: &bytes[0x10..data_end],
+        position: compressed_size,
+        bit_pool: 0,
+        bits_left: 0,
+    };
+
+    // The data is decompressed from the end to the start
+    let output_end = output.len() - 1;
+    let mut written = 0;
+
+    while written < uncompressed_size {
+        if reader.read(1).ok_or(NuccError::InvalidCompressedData)? == 0 {
+            output[output_end - written] =
+                reader.read(8).ok_or(NuccError::InvalidCompressedData)? as u8;
+            written += 1;
+            continue;
+        }
+
+        let mut source = output_end - written
+            + reader.read(13).ok_or(NuccError::InvalidCompressedData)? as usize
+            + MIN_MATCH;
+        let mut length = MIN_MATCH;
+
+        let mut all_max = true;
+        for bits in VLE_LENGTHS {
+            let value = reader.read(bits).ok_or(NuccError::InvalidCompressedData)?;
+            length += value as usize;
+
+            if value != (1 << bits) - 1 {
+                all_max = false;
+                break;
+            }
+        }
+
+        if all_max {
+            loop {
+                let value = reader.read(8).ok_or(NuccError::InvalidCompressedData)?;
+                length += value as usize;
+
+                if value != 0xFF {
+                    break;
+                }
+            }
+        }
+
+        if source > output_end || length > uncompressed_size - written {
+            return Err(NuccError::InvalidCompressedData);
+        }
+
+        for _ in 0..length {
+            output[output_end - written] = output[source];
+            source -= 1;
+            written += 1;
+        }
+    }
+
+    Ok(output)
+}
+
+struct BitWriter {
+    bytes: Vec<u8>,
+    bits_used: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u32, bit_count: u32) {
+        for i in (0..bit_count).rev() {
+            if self.bits_used % 8 == 0 {
+                self.bytes.push(0);
+            }
+
+            let bit = ((value >> i) & 1) as u8;
+            *self.bytes.last_mut().unwrap() |= bit << (7 - self.bits_used % 8);
+            self.bits_used += 1;
+        }
+    }
+}
+
+/// Compresses the data with a greedy LZ matcher. Data that is too small to have the uncompressed header is returned as is.
+pub fn crilayla_compress(bytes: &[u8]) -> Vec<u8> {
+    if bytes.len() < RAW_HEADER_SIZE {
+        return bytes.to_vec();
+    }
+
+    // The decompressor writes the data from the end, so the data is compressed in reverse
+    let data: Vec<u8> = bytes[RAW_HEADER_SIZE..].iter().rev().copied().collect();
+    let mut writer = BitWriter {
+        bytes: vec![],
+        bits_used: 0,
+    };
+
+    let mut positions: HashMap<[u8; 3], Vec<usize>> = HashMap::new();
+    let mut position = 0;
+
+    while position < data.len() {
+        let mut best = (0, 0);
+
+        if position + MIN_MATCH <= data.len() {
+            let key = [data[position], data[position + 1], data[position + 2]];
+
+            for &candidate in positions
+                .get(&key)
+                .into_iter()
+                .flatten()
+                .rev()
+                .take(MAX_CANDIDATES)
+            {
+                let distance = position - candidate;
+                if distance > MAX_DISTANCE {
+                    break;
+                }
+
+                if distance < MIN_MATCH {
+                    continue;
+                }
+
+                let length = data[position..]
+                    .iter()
+                    .zip(data[candidate..].iter())
+                    .take_while(|(a, b)| a == b)
+                    .count();
+
+                if length > best.0 {
+                    best = (length, distance);
+                }
+            }
+        }
+
+        let (length, distance) = best;
+        let step = if length >= MIN_MATCH {
+            writer.write(1, 1);
+            writer.write((distance - MIN_MATCH) as u32, 13);
+
+            let mut remaining = (length - MIN_MATCH) as u32;
+            let mut all_max = true;
+
+            for bits in VLE_LENGTHS {
+                let max = (1 << bits) - 1;
+                let value = remaining.min(max);
+
+                writer.write(value, bits);
+                remaining -= value;
+
+                if value != max {
+                    all_max = false;
+                    break;
+                }
+            }
+
+            if all_max {
+                loop {
+                    let value = remaining.min(0xFF);
+                    writer.write(value, 8);
+                    remaining -= value;
+
+                    if value != 0xFF {
+                        break;
+                    }
+                }
+            }
+
+            length
+        } else {
+            writer.write(0, 1);
+            writer.write(data[position] as u32, 8);
+
+            1
+        };
+
+        for i in position..position + step {
+            if i + MIN_MATCH <= data.len() {
+                positions
+                    .entry([data[i], data[i + 1], data[i + 2]])
+                    .or_default()
+                    .push(i);
+            }
+        }
+
+        position += step;
+    }
+
+    // The compressed data is also read backwards
+    let compressed: Vec<u8> = writer.bytes.into_iter().rev().collect();
+
+    let mut output = Vec::with_capacity(0x10 + compressed.len() + RAW_HEADER_SIZE);
+    output.extend_from_slice(CRILAYLA_MAGIC);
+    output.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    output.extend_from_slice(&(compressed.len() as u32).to_le_bytes());
+    output.extend(compressed);
+    output.extend_from_slice(&bytes[..RAW_HEADER_SIZE]);
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_data() -> Vec<u8> {
+        (0..0x1000u32)
+            .map(|i| {
+                if i % 0x300 < 0x100 {
+                    (i % 7) as u8
+                } else {
+                    (i * 31 % 251) as u8
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn crilayla_round_trip() {
+        let data = sample_data();
+        let compressed = crilayla_compress(&data);
+
+        assert_eq!(Compression::detect(&compressed), Compression::Crilayla);
+        assert!(compressed.len() < data.len());
+        assert_eq!(decompress(compressed).unwrap(), data);
+    }
+
+    #[test]
+    fn crilayla_small_data() {
+        let data = vec![1, 2, 3];
+        assert_eq!(crilayla_compress(&data), data);
+    }
+
+    #[test]
+    fn crilayla_invalid_size() {
+        let mut compressed = crilayla_compress(&sample_data());
+        compressed[0x08..0x0C].copy_from_slice(&u32::MAX.to_le_bytes());
+
+        assert!(crilayla_decompress(&compressed).is_err());
+    }
+
+    #[test]
+    fn crilayla_truncated() {
+        let compressed = crilayla_compress(&sample_data());
+        assert!(crilayla_decompress(&compressed[..compressed.len() - 1]).is_err());
+    }
+}
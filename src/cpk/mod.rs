@@ -0,0 +1,748 @@
+pub mod utf;
+
+use std::{
+    fs::File,
+    io::{BufReader, Cursor, Read, Seek, SeekFrom, Write},
+    path::Path,
+};
+
+use hashbrown::HashMap;
+
+use crate::compression::{self, Compression};
+use crate::error::NuccError;
+use utf::{align, UtfColumn, UtfStorage, UtfTable, UtfValue};
+
+// The CPK header packet is padded to this size, and ends with the copyright string
+const HEADER_SIZE: u64 = 0x800;
+const COPYRIGHT: &[u8] = b"(c)CRI";
+const DEFAULT_ALIGN: u64 = 0x800;
+
+#[derive(Debug, Clone)]
+pub struct CpkFile {
+    /// Directory and file name separated by '/'. Files that are only listed in the ITOC use their ID as the path.
+    pub path: String,
+    pub id: u32,
+
+    pub offset: u64,
+
+    /// Size of the stored data. Smaller than extract_size if the file is compressed with CRILAYLA.
+    pub size: u64,
+    pub extract_size: u64,
+
+    /// Modification time from the ETOC, packed the way CRI's tools write it. Only files listed in the TOC have one.
+    pub update_date_time: Option<u64>,
+
+    toc_row: Option<usize>,
+}
+
+impl CpkFile {
+    pub fn is_compressed(&self) -> bool {
+        self.size < self.extract_size
+    }
+}
+
+/// A CRI CPK archive. Files are read from the reader on demand, and replaced files are kept in memory
+/// until the archive is written.
+///
+/// Files are listed from the TOC, or from the ITOC if the archive has no TOC, and the ETOC's modification
+/// times are read with them. The GTOC (file groups and attributes) is not interpreted; it is written back
+/// unchanged, so groups are kept as long as files are only replaced and not added or removed.
+pub struct Cpk<R: Read + Seek> {
+    reader: R,
+
+    pub header: UtfTable,
+    pub toc: Option<UtfTable>,
+    pub itoc: Option<UtfTable>,
+    pub etoc: Option<UtfTable>,
+    pub gtoc: Option<UtfTable>,
+
+    // Contents of the DataL and DataH columns of the ITOC
+    itoc_low: Option<UtfTable>,
+    itoc_high: Option<UtfTable>,
+
+    files: Vec<CpkFile>,
+    replacements: HashMap<usize, Vec<u8>>,
+}
+
+/// Checks for the magic of the CPK header packet.
+pub fn is_cpk(bytes: &[u8]) -> bool {
+    bytes.starts_with(b"CPK ")
+}
+
+fn io_err<T>(_: T) -> NuccError {
+    NuccError::InvalidCpk
+}
+
+/// Returns the size of the whole stream, leaving the position unchanged.
+fn stream_len<R: Seek>(reader: &mut R) -> Result<u64, NuccError> {
+    let position = reader.stream_position().map_err(io_err)?;
+    let len = reader.seek(SeekFrom::End(0)).map_err(io_err)?;
+    reader.seek(SeekFrom::Start(position)).map_err(io_err)?;
+
+    Ok(len)
+}
+
+fn read_packet<R: Read + Seek>(
+    reader: &mut R,
+    offset: u64,
+    magic: &[u8],
+) -> Result<UtfTable, NuccError> {
+    let mut header = [0u8; 0x10];
+    reader.seek(SeekFrom::Start(offset)).map_err(io_err)?;
+    reader.read_exact(&mut header).map_err(io_err)?;
+
+    if &header[..4] != magic {
+        return Err(NuccError::InvalidCpk);
+    }
+
+    // Sizes are checked against the file before they are allocated
+    let size = u64::from_le_bytes(header[0x08..0x10].try_into().unwrap());
+    if size > stream_len(reader)?.saturating_sub(offset + 0x10) {
+        return Err(NuccError::InvalidCpk);
+    }
+
+    let mut bytes = vec![0u8; size as usize];
+    reader.read_exact(&mut bytes).map_err(io_err)?;
+
+    UtfTable::read(&bytes)
+}
+
+fn packet(magic: &[u8], table: &UtfTable) -> Vec<u8> {
+    let utf = table.write();
+
+    let mut output = Vec::with_capacity(0x10 + utf.len());
+    output.extend_from_slice(magic);
+    output.extend_from_slice(&0xFFu32.to_le_bytes());
+    output.extend_from_slice(&(utf.len() as u64).to_le_bytes());
+    output.extend(utf);
+
+    output
+}
+
+/// Returns the offset and size of a table, if the CPK has it.
+fn table_location(header: &UtfTable, name: &str) -> Option<(u64, u64)> {
+    let offset = header.get_u64(0, &format!("{name}Offset"))?;
+    let size = header.get_u64(0, &format!("{name}Size")).unwrap_or(0);
+
+    match offset {
+        0 => None,
+        offset => Some((offset, size)),
+    }
+}
+
+fn set_if_present(table: &mut UtfTable, name: &str, value: u64) -> Result<(), NuccError> {
+    match table.column_index(name) {
+        Some(_) => table.set_u64(0, name, value),
+        None => Ok(()),
+    }
+}
+
+/// Sizes are written for every row, so that the size of the table doesn't depend on their values.
+fn force_per_row(table: &mut UtfTable, names: &[&str]) {
+    for column in table.columns.iter_mut() {
+        if names.contains(&column.name.as_str()) {
+            column.storage = UtfStorage::PerRow;
+        }
+    }
+}
+
+fn normalize_path(path: &str) -> String {
+    path.replace('\\', "/").trim_start_matches('/').to_string()
+}
+
+fn write_bytes<W: Write>(writer: &mut W, written: &mut u64, bytes: &[u8]) -> Result<(), NuccError> {
+    writer.write_all(bytes).map_err(io_err)?;
+    *written += bytes.len() as u64;
+
+    Ok(())
+}
+
+fn write_padding<W: Write>(
+    writer: &mut W,
+    written: &mut u64,
+    target: u64,
+) -> Result<(), NuccError> {
+    if target > *written {
+        writer
+            .write_all(&vec![0u8; (target - *written) as usize])
+            .map_err(io_err)?;
+        *written = target;
+    }
+
+    Ok(())
+}
+
+impl Cpk<BufReader<File>> {
+    pub fn open(file_path: &dyn AsRef<Path>) -> Result<Self, NuccError> {
+        Cpk::from_reader(BufReader::new(File::open(file_path).map_err(io_err)?))
+    }
+}
+
+impl Cpk<Cursor<Vec<u8>>> {
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self, NuccError> {
+        Cpk::from_reader(Cursor::new(bytes))
+    }
+}
+
+impl<R: Read + Seek> Cpk<R> {
+    pub fn from_reader(mut reader: R) -> Result<Self, NuccError> {
+        let header = read_packet(&mut reader, 0, b"CPK ")?;
+
+        let mut read_table = |name: &str, magic: &[u8]| match table_location(&header, name) {
+            Some((offset, _)) => read_packet(&mut reader, offset, magic).map(Some),
+            None => Ok(None),
+        };
+
+        let toc = read_table("Toc", b"TOC ")?;
+        let itoc = read_table("Itoc", b"ITOC")?;
+        let etoc = read_table("Etoc", b"ETOC")?;
+        let gtoc = read_table("Gtoc", b"GTOC")?;
+
+        let read_itoc_data = |name: &str| match itoc.as_ref().and_then(|t| t.get(0, name)) {
+            Some(UtfValue::Data(data)) if !data.is_empty() => UtfTable::read(data).map(Some),
+            _ => Ok(None),
+        };
+
+        let itoc_low = read_itoc_data("DataL")?;
+        let itoc_high = read_itoc_data("DataH")?;
+
+        let mut cpk = Self {
+            reader,
+            header,
+            toc,
+            itoc,
+            etoc,
+            gtoc,
+            itoc_low,
+            itoc_high,
+            files: vec![],
+            replacements: HashMap::new(),
+        };
+
+        cpk.files = match cpk.toc {
+            Some(_) => cpk.toc_files()?,
+            None => cpk.itoc_files()?,
+        };
+
+        Ok(cpk)
+    }
+
+    fn content_offset(&self) -> u64 {
+        table_location(&self.header, "Content")
+            .map(|(offset, _)| offset)
+            .unwrap_or(0)
+    }
+
+    fn alignment(&self) -> u64 {
+        match self.header.get_u64(0, "Align") {
+            Some(0) | None => DEFAULT_ALIGN,
+            Some(alignment) => alignment,
+        }
+    }
+
+    fn toc_files(&self) -> Result<Vec<CpkFile>, NuccError> {
+        let toc = self.toc.as_ref().unwrap();
+
+        // File offsets are relative to whichever comes first
+        let add_offset = match (
+            table_location(&self.header, "Content"),
+            table_location(&self.header, "Toc"),
+        ) {
+            (Some((content, _)), Some((toc, _))) => content.min(toc),
+            (Some((content, _)), None) => content,
+            (None, Some((toc, _))) => toc,
+            (None, None) => 0,
+        };
+
+        let mut files = vec![];
+        for row in 0..toc.rows.len() {
+            let dir_name = toc
+                .get(row, "DirName")
+                .and_then(|v| v.as_str())
+                .unwrap_or("");
+            let file_name = toc
+                .get(row, "FileName")
+                .and_then(|v| v.as_str())
+                .ok_or(NuccError::InvalidCpk)?;
+
+            let size = toc.get_u64(row, "FileSize").ok_or(NuccError::InvalidCpk)?;
+
+            // ETOC rows are in the same order as the TOC's
+            let update_date_time = self
+                .etoc
+                .as_ref()
+                .and_then(|etoc| etoc.get_u64(row, "UpdateDateTime"));
+
+            files.push(CpkFile {
+                path: match dir_name {
+                    "" => file_name.to_string(),
+                    dir_name => format!("{dir_name}/{file_name}"),
+                },
+                id: toc.get_u64(row, "ID").unwrap_or(row as u64) as u32,
+                offset: toc
+                    .get_u64(row, "FileOffset")
+                    .ok_or(NuccError::InvalidCpk)?
+                    + add_offset,
+                size,
+                extract_size: toc.get_u64(row, "ExtractSize").unwrap_or(size),
+                update_date_time,
+                toc_row: Some(row),
+            });
+        }
+
+        Ok(files)
+    }
+
+    fn itoc_files(&self) -> Result<Vec<CpkFile>, NuccError> {
+        let mut files = vec![];
+
+        for table in [&self.itoc_low, &self.itoc_high].into_iter().flatten() {
+            for row in 0..table.rows.len() {
+                let id = table.get_u64(row, "ID").ok_or(NuccError::InvalidCpk)? as u32;
+                let size = table
+                    .get_u64(row, "FileSize")
+                    .ok_or(NuccError::InvalidCpk)?;
+
+                files.push(CpkFile {
+                    path: id.to_string(),
+                    id,
+                    offset: 0,
+                    size,
+                    extract_size: table.get_u64(row, "ExtractSize").unwrap_or(size),
+                    update_date_time: None,
+                    toc_row: None,
+                });
+            }
+        }
+
+        // Files without a TOC are stored one after the other, sorted by their ID
+        files.sort_by_key(|f| f.id);
+
+        let alignment = self.alignment();
+        let mut offset = self.content_offset();
+
+        for file in files.iter_mut() {
+            file.offset = offset;
+            offset = align((offset + file.size) as usize, alignment as usize) as u64;
+        }
+
+        Ok(files)
+    }
+
+    pub fn files(&self) -> &[CpkFile] {
+        &self.files
+    }
+
+    fn file_index(&self, path: &str) -> Result<usize, NuccError> {
+        let path = normalize_path(path);
+
+        self.files
+            .iter()
+            .position(|f| f.path == path)
+            .ok_or(NuccError::CpkFileNotFound)
+    }
+
+    pub fn find(&self, path: &str) -> Option<&CpkFile> {
+        self.file_index(path).ok().map(|i| &self.files[i])
+    }
+
+    fn stored_data(&mut self, index: usize) -> Result<Vec<u8>, NuccError> {
+        if let Some(data) = self.replacements.get(&index) {
+            return Ok(data.clone());
+        }
+
+        let file = &self.files[index];
+        if file.offset.saturating_add(file.size) > stream_len(&mut self.reader)? {
+            return Err(NuccError::InvalidCpk);
+        }
+
+        let mut data = vec![0u8; file.size as usize];
+
+        self.reader
+            .seek(SeekFrom::Start(file.offset))
+            .map_err(io_err)?;
+        self.reader.read_exact(&mut data).map_err(io_err)?;
+
+        Ok(data)
+    }
+
+    /// Returns the file's data, decompressed if needed.
+    pub fn extract(&mut self, path: &str) -> Result<Vec<u8>, NuccError> {
+        let index = self.file_index(path)?;
+        let data = self.stored_data(index)?;
+
+        match self.files[index].is_compressed() {
+            true => compression::crilayla_decompress(&data),
+            false => Ok(data),
+        }
+    }
+
+    /// Replaces the file's data. The archive has to be written for the change to be saved.
+    pub fn replace(
+        &mut self,
+        path: &str,
+        data: Vec<u8>,
+        compression: Compression,
+    ) -> Result<(), NuccError> {
+        let index = self.file_index(path)?;
+
+        let extract_size = data.len() as u64;
+        let stored = compression.compress(data);
+        let size = stored.len() as u64;
+
+        let file = &mut self.files[index];
+        file.size = size;
+        file.extract_size = extract_size;
+
+        let (id, toc_row) = (file.id, file.toc_row);
+
+        if let (Some(toc), Some(row)) = (self.toc.as_mut(), toc_row) {
+            toc.set_u64(row, "FileSize", size)?;
+            toc.set_u64(row, "ExtractSize", extract_size)?;
+        }
+
+        self.update_itoc(id, size, extract_size)?;
+
+        self.replacements.insert(index, stored);
+        Ok(())
+    }
+
+    fn update_itoc(&mut self, id: u32, size: u64, extract_size: u64) -> Result<(), NuccError> {
+        let find_row = |table: &Option<UtfTable>| {
+            table
+                .as_ref()
+                .and_then(|t| (0..t.rows.len()).find(|r| t.get_u64(*r, "ID") == Some(id as u64)))
+        };
+
+        if let Some(row) = find_row(&self.itoc_high) {
+            let table = self.itoc_high.as_mut().unwrap();
+            table.set_u64(row, "FileSize", size)?;
+            return table.set_u64(row, "ExtractSize", extract_size);
+        }
+
+        let row = match find_row(&self.itoc_low) {
+            Some(row) => row,
+            None => return Ok(()),
+        };
+
+        let table = self.itoc_low.as_mut().unwrap();
+        if size <= u16::MAX as u64 && extract_size <= u16::MAX as u64 {
+            table.set_u64(row, "FileSize", size)?;
+            return table.set_u64(row, "ExtractSize", extract_size);
+        }
+
+        // Sizes that don't fit in the low table have to be moved to the high table
+        table.rows.remove(row);
+
+        let high = self.itoc_high.get_or_insert_with(|| UtfTable {
+            name: "CpkItocH".to_string(),
+            columns: ["ID", "FileSize", "ExtractSize"]
+                .iter()
+                .zip([UtfValue::U16(0), UtfValue::U32(0), UtfValue::U32(0)])
+                .map(|(name, value)| UtfColumn {
+                    name: name.to_string(),
+                    storage: UtfStorage::PerRow,
+                    value,
+                })
+                .collect(),
+            rows: vec![],
+        });
+
+        // Rows are sorted by ID
+        let id_column = high.column_index("ID").ok_or(NuccError::InvalidCpk)?;
+        let index = high
+            .rows
+            .iter()
+            .position(|r| {
+                r[id_column]
+                    .as_u64()
+                    .map_or(false, |row_id| row_id > id as u64)
+            })
+            .unwrap_or(high.rows.len());
+
+        let new_row = high.columns.iter().map(|c| c.value.clone()).collect();
+        high.rows.insert(index, new_row);
+        high.set_u64(index, "ID", id as u64)?;
+        high.set_u64(index, "FileSize", size)?;
+        high.set_u64(index, "ExtractSize", extract_size)
+    }
+
+    /// Writes the archive with all replaced files. Files are read from the original archive as needed,
+    /// so the writer must not be the same file as the reader. The file list keeps pointing to the original archive.
+    pub fn write<W: Write>(&mut self, writer: &mut W) -> Result<(), NuccError> {
+        let alignment = self.alignment();
+        let align_to = |value: u64| align(value as usize, alignment as usize) as u64;
+
+        let size_columns = ["FileOffset", "FileSize", "ExtractSize"];
+
+        if let Some(toc) = self.toc.as_mut() {
+            force_per_row(toc, &size_columns);
+        }
+
+        // Nested ITOC tables
+        let low = self.itoc_low.as_mut().map(|t| {
+            force_per_row(t, &size_columns);
+            (t.rows.len() as u64, t.write())
+        });
+        let high = self.itoc_high.as_mut().map(|t| {
+            force_per_row(t, &size_columns);
+            (t.rows.len() as u64, t.write())
+        });
+
+        if let Some(itoc) = self.itoc.as_mut() {
+            for (name, files, table) in [("DataL", "FilesL", low), ("DataH", "FilesH", high)] {
+                let (count, data) = table.unwrap_or_default();
+                if let Some(column) = itoc.column_index(name) {
+                    itoc.rows[0][column] = UtfValue::Data(data);
+                }
+
+                set_if_present(itoc, files, count)?;
+            }
+        }
+
+        // Tables are written before the content, except for the ETOC
+        let mut position = HEADER_SIZE;
+        let mut place_table = |table: &Option<UtfTable>, magic: &[u8]| {
+            table.as_ref().map(|table| {
+                let offset = position;
+                let packet = packet(magic, table);
+
+                position = align_to(position + packet.len() as u64);
+                (offset, packet)
+            })
+        };
+
+        let toc_packet = place_table(&self.toc, b"TOC ");
+        let itoc_packet = place_table(&self.itoc, b"ITOC");
+        let gtoc_packet = place_table(&self.gtoc, b"GTOC");
+
+        let content_offset = position;
+
+        // ITOC files are stored in the order of their IDs
+        let mut order: Vec<usize> = (0..self.files.len()).collect();
+        if self.toc.is_none() {
+            order.sort_by_key(|i| self.files[*i].id);
+        }
+
+        let mut offsets = vec![0u64; self.files.len()];
+        for index in order.iter() {
+            offsets[*index] = position;
+            position = align_to(position + self.files[*index].size);
+        }
+
+        let content_size = position - content_offset;
+        let etoc_offset = position;
+
+        // File offsets are relative to the TOC, since it comes before the content
+        let toc_packet = match (self.toc.as_mut(), toc_packet) {
+            (Some(toc), Some((toc_offset, _))) => {
+                for (file, offset) in self.files.iter().zip(offsets.iter()) {
+                    if let Some(row) = file.toc_row {
+                        toc.set_u64(row, "FileOffset", offset - toc_offset)?;
+                    }
+                }
+
+                Some((toc_offset, packet(b"TOC ", toc)))
+            }
+            _ => None,
+        };
+
+        let etoc_packet = self
+            .etoc
+            .as_ref()
+            .map(|etoc| (etoc_offset, packet(b"ETOC", etoc)));
+
+        let header = &mut self.header;
+        set_if_present(header, "ContentOffset", content_offset)?;
+        set_if_present(header, "ContentSize", content_size)?;
+
+        for (name, table) in [
+            ("Toc", &toc_packet),
+            ("Itoc", &itoc_packet),
+            ("Gtoc", &gtoc_packet),
+            ("Etoc", &etoc_packet),
+        ] {
+            let (offset, size) = table
+                .as_ref()
+                .map_or((0, 0), |(offset, packet)| (*offset, packet.len() as u64));
+
+            set_if_present(header, &format!("{name}Offset"), offset)?;
+            set_if_present(header, &format!("{name}Size"), size)?;
+        }
+
+        set_if_present(
+            header,
+            "EnabledPackedSize",
+            self.files.iter().map(|f| f.size).sum(),
+        )?;
+        set_if_present(
+            header,
+            "EnabledDataSize",
+            self.files.iter().map(|f| f.extract_size).sum(),
+        )?;
+
+        let header_packet = packet(b"CPK ", header);
+        if header_packet.len() as u64 + COPYRIGHT.len() as u64 > HEADER_SIZE {
+            return Err(NuccError::InvalidCpk);
+        }
+
+        let mut written = 0u64;
+        write_bytes(writer, &mut written, &header_packet)?;
+        write_padding(writer, &mut written, HEADER_SIZE - COPYRIGHT.len() as u64)?;
+        write_bytes(writer, &mut written, COPYRIGHT)?;
+
+        for (offset, packet) in [toc_packet, itoc_packet, gtoc_packet].into_iter().flatten() {
+            write_padding(writer, &mut written, offset)?;
+            write_bytes(writer, &mut written, &packet)?;
+        }
+
+        for index in order {
+            let data = self.stored_data(index)?;
+
+            write_padding(writer, &mut written, offsets[index])?;
+            write_bytes(writer, &mut written, &data)?;
+        }
+
+        write_padding(writer, &mut written, etoc_offset)?;
+        if let Some((_, packet)) = etoc_packet {
+            write_bytes(writer, &mut written, &packet)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn per_row(name: &str, value: UtfValue) -> UtfColumn {
+        UtfColumn {
+            name: name.to_string(),
+            storage: UtfStorage::PerRow,
+            value,
+        }
+    }
+
+    fn pad_to(bytes: &mut Vec<u8>, size: u64) {
+        bytes.resize(size as usize, 0);
+    }
+
+    /// Builds a CPK with a TOC, with the header at 0, the TOC at 0x800 and the files from 0x1000.
+    fn sample_cpk(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let toc_offset = HEADER_SIZE;
+        let content_offset = 0x1000;
+
+        let mut toc = UtfTable {
+            name: "CpkTocInfo".to_string(),
+            columns: vec![
+                per_row("DirName", UtfValue::String(String::new())),
+                per_row("FileName", UtfValue::String(String::new())),
+                per_row("FileSize", UtfValue::U32(0)),
+                per_row("ExtractSize", UtfValue::U32(0)),
+                per_row("FileOffset", UtfValue::U64(0)),
+                per_row("ID", UtfValue::U32(0)),
+            ],
+            rows: vec![],
+        };
+
+        let mut content = vec![];
+        for (id, (path, data)) in files.iter().enumerate() {
+            let (dir_name, file_name) = path.rsplit_once('/').unwrap_or(("", path));
+
+            toc.rows.push(vec![
+                UtfValue::String(dir_name.to_string()),
+                UtfValue::String(file_name.to_string()),
+                UtfValue::U32(data.len() as u32),
+                UtfValue::U32(data.len() as u32),
+                UtfValue::U64(content_offset + content.len() as u64 - toc_offset),
+                UtfValue::U32(id as u32),
+            ]);
+
+            content.extend_from_slice(data);
+            let aligned = align(content.len(), DEFAULT_ALIGN as usize) as u64;
+            pad_to(&mut content, aligned);
+        }
+
+        let toc_packet = packet(b"TOC ", &toc);
+
+        let header = UtfTable {
+            name: "CpkHeader".to_string(),
+            columns: vec![
+                per_row("ContentOffset", UtfValue::U64(0)),
+                per_row("ContentSize", UtfValue::U64(0)),
+                per_row("TocOffset", UtfValue::U64(0)),
+                per_row("TocSize", UtfValue::U64(0)),
+                per_row("EtocOffset", UtfValue::U64(0)),
+                per_row("Align", UtfValue::U16(0)),
+            ],
+            rows: vec![vec![
+                UtfValue::U64(content_offset),
+                UtfValue::U64(content.len() as u64),
+                UtfValue::U64(toc_offset),
+                UtfValue::U64(toc_packet.len() as u64),
+                UtfValue::U64(0),
+                UtfValue::U16(DEFAULT_ALIGN as u16),
+            ]],
+        };
+
+        let mut bytes = packet(b"CPK ", &header);
+        pad_to(&mut bytes, HEADER_SIZE - COPYRIGHT.len() as u64);
+        bytes.extend_from_slice(COPYRIGHT);
+        bytes.extend(toc_packet);
+        pad_to(&mut bytes, content_offset);
+        bytes.extend(content);
+
+        bytes
+    }
+
+    #[test]
+    fn extract_files() {
+        let bytes = sample_cpk(&[("data/a.xfbin", b"first"), ("b.xfbin", b"second")]);
+        assert!(is_cpk(&bytes));
+
+        let mut cpk = Cpk::from_bytes(bytes).unwrap();
+        assert_eq!(cpk.files().len(), 2);
+        assert_eq!(cpk.extract("data/a.xfbin").unwrap(), b"first");
+        assert_eq!(cpk.extract("\\data\\a.xfbin").unwrap(), b"first");
+        assert_eq!(cpk.extract("b.xfbin").unwrap(), b"second");
+        assert!(matches!(
+            cpk.extract("missing.xfbin"),
+            Err(NuccError::CpkFileNotFound)
+        ));
+    }
+
+    #[test]
+    fn replace_and_write() {
+        let bytes = sample_cpk(&[("data/a.xfbin", b"first"), ("b.xfbin", b"second")]);
+        let mut cpk = Cpk::from_bytes(bytes).unwrap();
+
+        let replaced = vec![0x42; 0x1800];
+        cpk.replace("data/a.xfbin", replaced.clone(), Compression::Crilayla)
+            .unwrap();
+
+        let mut output = vec![];
+        cpk.write(&mut output).unwrap();
+
+        let mut cpk = Cpk::from_bytes(output).unwrap();
+        assert!(cpk.find("data/a.xfbin").unwrap().is_compressed());
+        assert_eq!(cpk.extract("data/a.xfbin").unwrap(), replaced);
+        assert_eq!(cpk.extract("b.xfbin").unwrap(), b"second");
+    }
+
+    #[test]
+    fn read_invalid_packet_size() {
+        let mut bytes = sample_cpk(&[("a.xfbin", b"first")]);
+        bytes[0x08..0x10].copy_from_slice(&u64::MAX.to_le_bytes());
+
+        assert!(Cpk::from_bytes(bytes).is_err());
+    }
+
+    #[test]
+    fn extract_invalid_file_size() {
+        let mut cpk = Cpk::from_bytes(sample_cpk(&[("a.xfbin", b"first")])).unwrap();
+        cpk.files[0].size = u64::MAX;
+
+        assert!(matches!(cpk.extract("a.xfbin"), Err(NuccError::InvalidCpk)));
+    }
+}
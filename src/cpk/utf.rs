@@ -0,0 +1,599 @@
+use hashbrown::HashMap;
+
+use crate::error::NuccError;
+
+const STORAGE_ZERO: u8 = 0x10;
+const STORAGE_CONSTANT: u8 = 0x30;
+const STORAGE_PER_ROW: u8 = 0x50;
+
+const TYPE_U8: u8 = 0x00;
+const TYPE_I8: u8 = 0x01;
+const TYPE_U16: u8 = 0x02;
+const TYPE_I16: u8 = 0x03;
+const TYPE_U32: u8 = 0x04;
+const TYPE_I32: u8 = 0x05;
+const TYPE_U64: u8 = 0x06;
+const TYPE_I64: u8 = 0x07;
+const TYPE_F32: u8 = 0x08;
+const TYPE_F64: u8 = 0x09;
+const TYPE_STRING: u8 = 0x0A;
+const TYPE_DATA: u8 = 0x0B;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UtfStorage {
+    /// Every row has the default value of the column's type.
+    Zero,
+    /// Every row has the value stored in the column.
+    Constant,
+    PerRow,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum UtfValue {
+    U8(u8),
+    I8(i8),
+    U16(u16),
+    I16(i16),
+    U32(u32),
+    I32(i32),
+    U64(u64),
+    I64(i64),
+    F32(f32),
+    F64(f64),
+    String(String),
+    Data(Vec<u8>),
+}
+
+impl UtfValue {
+    fn default_of(value_type: u8) -> Result<UtfValue, NuccError> {
+        Ok(match value_type {
+            TYPE_U8 => UtfValue::U8(0),
+            TYPE_I8 => UtfValue::I8(0),
+            TYPE_U16 => UtfValue::U16(0),
+            TYPE_I16 => UtfValue::I16(0),
+            TYPE_U32 => UtfValue::U32(0),
+            TYPE_I32 => UtfValue::I32(0),
+            TYPE_U64 => UtfValue::U64(0),
+            TYPE_I64 => UtfValue::I64(0),
+            TYPE_F32 => UtfValue::F32(0.0),
+            TYPE_F64 => UtfValue::F64(0.0),
+            TYPE_STRING => UtfValue::String(String::new()),
+            TYPE_DATA => UtfValue::Data(vec![]),
+            _ => return Err(NuccError::InvalidCpk),
+        })
+    }
+
+    fn value_type(&self) -> u8 {
+        match self {
+            UtfValue::U8(_) => TYPE_U8,
+            UtfValue::I8(_) => TYPE_I8,
+            UtfValue::U16(_) => TYPE_U16,
+            UtfValue::I16(_) => TYPE_I16,
+            UtfValue::U32(_) => TYPE_U32,
+            UtfValue::I32(_) => TYPE_I32,
+            UtfValue::U64(_) => TYPE_U64,
+            UtfValue::I64(_) => TYPE_I64,
+            UtfValue::F32(_) => TYPE_F32,
+            UtfValue::F64(_) => TYPE_F64,
+            UtfValue::String(_) => TYPE_STRING,
+            UtfValue::Data(_) => TYPE_DATA,
+        }
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        match *self {
+            UtfValue::U8(v) => Some(v as u64),
+            UtfValue::I8(v) => u64::try_from(v).ok(),
+            UtfValue::U16(v) => Some(v as u64),
+            UtfValue::I16(v) => u64::try_from(v).ok(),
+            UtfValue::U32(v) => Some(v as u64),
+            UtfValue::I32(v) => u64::try_from(v).ok(),
+            UtfValue::U64(v) => Some(v),
+            UtfValue::I64(v) => u64::try_from(v).ok(),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            UtfValue::String(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_data(&self) -> Option<&[u8]> {
+        match self {
+            UtfValue::Data(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Converts an integer to the same type as this value. Returns None if it doesn't fit.
+    fn with_u64(&self, value: u64) -> Option<UtfValue> {
+        Some(match self {
+            UtfValue::U8(_) => UtfValue::U8(value.try_into().ok()?),
+            UtfValue::I8(_) => UtfValue::I8(value.try_into().ok()?),
+            UtfValue::U16(_) => UtfValue::U16(value.try_into().ok()?),
+            UtfValue::I16(_) => UtfValue::I16(value.try_into().ok()?),
+            UtfValue::U32(_) => UtfValue::U32(value.try_into().ok()?),
+            UtfValue::I32(_) => UtfValue::I32(value.try_into().ok()?),
+            UtfValue::U64(_) => UtfValue::U64(value),
+            UtfValue::I64(_) => UtfValue::I64(value.try_into().ok()?),
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct UtfColumn {
+    pub name: String,
+    pub storage: UtfStorage,
+
+    /// The value of zero and constant columns. Also defines the type of the column.
+    pub value: UtfValue,
+}
+
+/// A table in CRI's @UTF format, which is used for all CPK tables.
+#[derive(Debug, Clone)]
+pub struct UtfTable {
+    pub name: String,
+    pub columns: Vec<UtfColumn>,
+
+    /// Values of all columns, including zero and constant ones.
+    pub rows: Vec<Vec<UtfValue>>,
+}
+
+/// Tables are sometimes obfuscated with a simple XOR stream.
+fn decrypt_utf(bytes: &[u8]) -> Vec<u8> {
+    let mut m: u32 = 0x655F;
+    let t: u32 = 0x4115;
+
+    bytes
+        .iter()
+        .map(|b| {
+            let d = b ^ (m & 0xFF) as u8;
+            m = m.wrapping_mul(t);
+            d
+        })
+        .collect()
+}
+
+struct UtfReader<'a> {
+    bytes: &'a [u8],
+    strings_offset: usize,
+    data_offset: usize,
+}
+
+impl<'a> UtfReader<'a> {
+    fn slice(&self, offset: usize, size: usize) -> Result<&'a [u8], NuccError> {
+        let end = offset.checked_add(size).ok_or(NuccError::InvalidCpk)?;
+        self.bytes.get(offset..end).ok_or(NuccError::InvalidCpk)
+    }
+
+    fn u8(&self, offset: usize) -> Result<u8, NuccError> {
+        Ok(self.slice(offset, 1)?[0])
+    }
+
+    fn u16(&self, offset: usize) -> Result<u16, NuccError> {
+        Ok(u16::from_be_bytes(
+            self.slice(offset, 2)?.try_into().unwrap(),
+        ))
+    }
+
+    fn u32(&self, offset: usize) -> Result<u32, NuccError> {
+        Ok(u32::from_be_bytes(
+            self.slice(offset, 4)?.try_into().unwrap(),
+        ))
+    }
+
+    fn u64(&self, offset: usize) -> Result<u64, NuccError> {
+        Ok(u64::from_be_bytes(
+            self.slice(offset, 8)?.try_into().unwrap(),
+        ))
+    }
+
+    fn string(&self, offset: u32) -> Result<String, NuccError> {
+        let start = self.strings_offset + offset as usize;
+        let bytes = self.bytes.get(start..).ok_or(NuccError::InvalidCpk)?;
+        let end = bytes
+            .iter()
+            .position(|b| *b == 0)
+            .ok_or(NuccError::InvalidCpk)?;
+
+        Ok(codepage::to_encoding(932)
+            .unwrap()
+            .decode(&bytes[..end])
+            .0
+            .to_string())
+    }
+
+    /// Returns the value and its size.
+    fn value(&self, value_type: u8, offset: usize) -> Result<(UtfValue, usize), NuccError> {
+        Ok(match value_type {
+            TYPE_U8 => (UtfValue::U8(self.u8(offset)?), 1),
+            TYPE_I8 => (UtfValue::I8(self.u8(offset)? as i8), 1),
+            TYPE_U16 => (UtfValue::U16(self.u16(offset)?), 2),
+            TYPE_I16 => (UtfValue::I16(self.u16(offset)? as i16), 2),
+            TYPE_U32 => (UtfValue::U32(self.u32(offset)?), 4),
+            TYPE_I32 => (UtfValue::I32(self.u32(offset)? as i32), 4),
+            TYPE_U64 => (UtfValue::U64(self.u64(offset)?), 8),
+            TYPE_I64 => (UtfValue::I64(self.u64(offset)? as i64), 8),
+            TYPE_F32 => (UtfValue::F32(f32::from_bits(self.u32(offset)?)), 4),
+            TYPE_F64 => (UtfValue::F64(f64::from_bits(self.u64(offset)?)), 8),
+            TYPE_STRING => (UtfValue::String(self.string(self.u32(offset)?)?), 4),
+            TYPE_DATA => {
+                let data_offset = self.u32(offset)? as usize;
+                let size = self.u32(offset + 4)? as usize;
+
+                (
+                    UtfValue::Data(self.slice(self.data_offset + data_offset, size)?.to_vec()),
+                    8,
+                )
+            }
+            _ => return Err(NuccError::InvalidCpk),
+        })
+    }
+}
+
+#[derive(Default)]
+struct UtfWriter {
+    strings: Vec<u8>,
+    string_offsets: HashMap<String, u32>,
+    data: Vec<u8>,
+}
+
+impl UtfWriter {
+    fn string(&mut self, string: &str) -> u32 {
+        if let Some(offset) = self.string_offsets.get(string) {
+            return *offset;
+        }
+
+        let offset = self.strings.len() as u32;
+        self.strings
+            .extend_from_slice(&codepage::to_encoding(932).unwrap().encode(string).0);
+        self.strings.push(0);
+        self.string_offsets.insert(string.to_string(), offset);
+
+        offset
+    }
+
+    fn value(&mut self, value: &UtfValue, output: &mut Vec<u8>) {
+        match value {
+            UtfValue::U8(v) => output.push(*v),
+            UtfValue::I8(v) => output.push(*v as u8),
+            UtfValue::U16(v) => output.extend_from_slice(&v.to_be_bytes()),
+            UtfValue::I16(v) => output.extend_from_slice(&v.to_be_bytes()),
+            UtfValue::U32(v) => output.extend_from_slice(&v.to_be_bytes()),
+            UtfValue::I32(v) => output.extend_from_slice(&v.to_be_bytes()),
+            UtfValue::U64(v) => output.extend_from_slice(&v.to_be_bytes()),
+            UtfValue::I64(v) => output.extend_from_slice(&v.to_be_bytes()),
+            UtfValue::F32(v) => output.extend_from_slice(&v.to_bits().to_be_bytes()),
+            UtfValue::F64(v) => output.extend_from_slice(&v.to_bits().to_be_bytes()),
+            UtfValue::String(v) => {
+                let offset = self.string(v);
+                output.extend_from_slice(&offset.to_be_bytes());
+            }
+            UtfValue::Data(v) => {
+                // Nested tables are aligned
+                self.data.resize(align(self.data.len(), 8), 0);
+
+                let offset = match v.is_empty() {
+                    true => 0,
+                    false => self.data.len() as u32,
+                };
+                self.data.extend_from_slice(v);
+
+                output.extend_from_slice(&offset.to_be_bytes());
+                output.extend_from_slice(&(v.len() as u32).to_be_bytes());
+            }
+        }
+    }
+}
+
+pub(super) fn align(value: usize, alignment: usize) -> usize {
+    match value % alignment {
+        0 => value,
+        x => value + alignment - x,
+    }
+}
+
+impl UtfTable {
+    /// Reads a table that starts with "@UTF" (or its obfuscated version).
+    pub fn read(bytes: &[u8]) -> Result<UtfTable, NuccError> {
+        let decrypted;
+        let bytes = if bytes.starts_with(b"@UTF") {
+            bytes
+        } else {
+            decrypted = decrypt_utf(bytes);
+            &decrypted[..]
+        };
+
+        if !bytes.starts_with(b"@UTF") {
+            return Err(NuccError::InvalidCpk);
+        }
+
+        // All offsets are relative to the end of the magic and table size
+        let mut reader = UtfReader {
+            bytes,
+            strings_offset: 0,
+            data_offset: 0,
+        };
+
+        let rows_offset = reader.u16(0x0A)? as usize + 8;
+        reader.strings_offset = reader.u32(0x0C)? as usize + 8;
+        reader.data_offset = reader.u32(0x10)? as usize + 8;
+
+        let name = reader.string(reader.u32(0x14)?)?;
+        let column_count = reader.u16(0x18)? as usize;
+        let row_length = reader.u16(0x1A)? as usize;
+        let row_count = reader.u32(0x1C)? as usize;
+
+        // The rows are checked to fit in the table before they are read. Rows without per-row values
+        // take no space, but they still can't outnumber the table's bytes.
+        let rows_size = row_count
+            .checked_mul(row_length)
+            .and_then(|size| size.checked_add(rows_offset))
+            .ok_or(NuccError::InvalidCpk)?;
+        if rows_size > bytes.len() || row_count > bytes.len() {
+            return Err(NuccError::InvalidCpk);
+        }
+
+        let mut columns = vec![];
+        let mut offset = 0x20;
+
+        for _ in 0..column_count {
+            let flags = reader.u8(offset)?;
+            let name = reader.string(reader.u32(offset + 1)?)?;
+            offset += 5;
+
+            let value_type = flags & 0x0F;
+            let (storage, value) = match flags & 0xF0 {
+                STORAGE_ZERO => (UtfStorage::Zero, UtfValue::default_of(value_type)?),
+                STORAGE_CONSTANT => {
+                    let (value, size) = reader.value(value_type, offset)?;
+                    offset += size;
+
+                    (UtfStorage::Constant, value)
+                }
+                STORAGE_PER_ROW => (UtfStorage::PerRow, UtfValue::default_of(value_type)?),
+                _ => return Err(NuccError::InvalidCpk),
+            };
+
+            columns.push(UtfColumn {
+                name,
+                storage,
+                value,
+            });
+        }
+
+        let mut rows = vec![];
+        for i in 0..row_count {
+            let mut offset = rows_offset + i * row_length;
+            let mut row = vec![];
+
+            for column in columns.iter() {
+                row.push(match column.storage {
+                    UtfStorage::Zero | UtfStorage::Constant => column.value.clone(),
+                    UtfStorage::PerRow => {
+                        let (value, size) = reader.value(column.value.value_type(), offset)?;
+                        offset += size;
+
+                        value
+                    }
+                });
+            }
+
+            rows.push(row);
+        }
+
+        Ok(UtfTable {
+            name,
+            columns,
+            rows,
+        })
+    }
+
+    pub fn write(&self) -> Vec<u8> {
+        let mut writer = UtfWriter::default();
+
+        writer.string("<NULL>");
+        let name_offset = writer.string(&self.name);
+
+        // Zero and constant columns can only be kept if all rows still have the same value
+        let storages: Vec<UtfStorage> = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| match column.storage {
+                UtfStorage::PerRow => UtfStorage::PerRow,
+                storage => match self.rows.iter().all(|row| row[i] == column.value) {
+                    true => storage,
+                    false => UtfStorage::PerRow,
+                },
+            })
+            .collect();
+
+        let mut columns = vec![];
+        for (column, storage) in self.columns.iter().zip(storages.iter()) {
+            let flags = match storage {
+                UtfStorage::Zero => STORAGE_ZERO,
+                UtfStorage::Constant => STORAGE_CONSTANT,
+                UtfStorage::PerRow => STORAGE_PER_ROW,
+            };
+
+            columns.push(flags | column.value.value_type());
+            columns.extend_from_slice(&writer.string(&column.name).to_be_bytes());
+
+            if *storage == UtfStorage::Constant {
+                writer.value(&column.value, &mut columns);
+            }
+        }
+
+        let mut rows = vec![];
+        for row in self.rows.iter() {
+            for (value, storage) in row.iter().zip(storages.iter()) {
+                if *storage == UtfStorage::PerRow {
+                    writer.value(value, &mut rows);
+                }
+            }
+        }
+
+        let row_length = match self.rows.len() {
+            0 => 0,
+            count => rows.len() / count,
+        };
+
+        let rows_offset = 0x20 + columns.len();
+        let strings_offset = rows_offset + rows.len();
+        let data_offset = align(strings_offset + writer.strings.len(), 8);
+        let table_end = align(data_offset + writer.data.len(), 8);
+
+        let mut output = Vec::with_capacity(table_end);
+        output.extend_from_slice(b"@UTF");
+        output.extend_from_slice(&((table_end - 8) as u32).to_be_bytes());
+        output.extend_from_slice(&1u16.to_be_bytes());
+        output.extend_from_slice(&((rows_offset - 8) as u16).to_be_bytes());
+        output.extend_from_slice(&((strings_offset - 8) as u32).to_be_bytes());
+        output.extend_from_slice(&((data_offset - 8) as u32).to_be_bytes());
+        output.extend_from_slice(&name_offset.to_be_bytes());
+        output.extend_from_slice(&(self.columns.len() as u16).to_be_bytes());
+        output.extend_from_slice(&(row_length as u16).to_be_bytes());
+        output.extend_from_slice(&(self.rows.len() as u32).to_be_bytes());
+
+        output.extend(columns);
+        output.extend(rows);
+        output.extend(writer.strings);
+        output.resize(data_offset, 0);
+        output.extend(writer.data);
+        output.resize(table_end, 0);
+
+        output
+    }
+
+    pub fn column_index(&self, name: &str) -> Option<usize> {
+        self.columns.iter().position(|c| c.name == name)
+    }
+
+    pub fn get(&self, row: usize, name: &str) -> Option<&UtfValue> {
+        self.rows.get(row)?.get(self.column_index(name)?)
+    }
+
+    pub fn get_u64(&self, row: usize, name: &str) -> Option<u64> {
+        self.get(row, name)?.as_u64()
+    }
+
+    /// Sets an integer value, converted to the type of the column.
+    pub fn set_u64(&mut self, row: usize, name: &str, value: u64) -> Result<(), NuccError> {
+        let index = self.column_index(name).ok_or(NuccError::InvalidCpk)?;
+        let cell = self
+            .rows
+            .get_mut(row)
+            .and_then(|r| r.get_mut(index))
+            .ok_or(NuccError::InvalidCpk)?;
+
+        *cell = cell.with_u64(value).ok_or(NuccError::InvalidCpk)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(name: &str, storage: UtfStorage, value: UtfValue) -> UtfColumn {
+        UtfColumn {
+            name: name.to_string(),
+            storage,
+            value,
+        }
+    }
+
+    fn sample_table() -> UtfTable {
+        UtfTable {
+            name: "CpkTocInfo".to_string(),
+            columns: vec![
+                column(
+                    "FileName",
+                    UtfStorage::PerRow,
+                    UtfValue::String(String::new()),
+                ),
+                column("FileSize", UtfStorage::PerRow, UtfValue::U32(0)),
+                column(
+                    "UserString",
+                    UtfStorage::Constant,
+                    UtfValue::String("<NULL>".to_string()),
+                ),
+                column("Info", UtfStorage::Zero, UtfValue::U64(0)),
+                column("Data", UtfStorage::PerRow, UtfValue::Data(vec![])),
+            ],
+            rows: vec![
+                vec![
+                    UtfValue::String("a.xfbin".to_string()),
+                    UtfValue::U32(0x1234),
+                    UtfValue::String("<NULL>".to_string()),
+                    UtfValue::U64(0),
+                    UtfValue::Data(vec![1, 2, 3]),
+                ],
+                vec![
+                    UtfValue::String("b.xfbin".to_string()),
+                    UtfValue::U32(0x10),
+                    UtfValue::String("<NULL>".to_string()),
+                    UtfValue::U64(0),
+                    UtfValue::Data(vec![]),
+                ],
+            ],
+        }
+    }
+
+    fn assert_same(a: &UtfTable, b: &UtfTable) {
+        assert_eq!(a.name, b.name);
+        assert_eq!(a.rows, b.rows);
+
+        let columns = |t: &UtfTable| {
+            t.columns
+                .iter()
+                .map(|c| (c.name.clone(), c.storage, c.value.clone()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(columns(a), columns(b));
+    }
+
+    #[test]
+    fn write_read_round_trip() {
+        let table = sample_table();
+        assert_same(&UtfTable::read(&table.write()).unwrap(), &table);
+    }
+
+    #[test]
+    fn read_obfuscated() {
+        let table = sample_table();
+        let obfuscated = decrypt_utf(&table.write());
+
+        assert!(!obfuscated.starts_with(b"@UTF"));
+        assert_same(&UtfTable::read(&obfuscated).unwrap(), &table);
+    }
+
+    #[test]
+    fn changed_constant_is_written_per_row() {
+        let mut table = sample_table();
+        table.rows[1][2] = UtfValue::String("changed".to_string());
+
+        let read = UtfTable::read(&table.write()).unwrap();
+        assert_eq!(read.columns[2].storage, UtfStorage::PerRow);
+        assert_eq!(read.rows, table.rows);
+    }
+
+    #[test]
+    fn read_invalid_row_count() {
+        let mut bytes = sample_table().write();
+        bytes[0x1C..0x20].copy_from_slice(&u32::MAX.to_be_bytes());
+
+        assert!(UtfTable::read(&bytes).is_err());
+    }
+
+    #[test]
+    fn read_truncated() {
+        let bytes = sample_table().write();
+        assert!(UtfTable::read(&bytes[..0x30]).is_err());
+    }
+}
@@ -0,0 +1,54 @@
+use deku::DekuError;
+
+use crate::error::NuccError;
+
+// Offsets in the XFBIN header
+const ENCRYPTED_FLAG_OFFSET: usize = 0x09;
+const HEADER_SIZE: usize = 0x10;
+
+/// Decrypts/encrypts everything after the XFBIN header (the index and the chunks).
+///
+/// The games' encryption algorithm is unknown, so this library can't decrypt any retail XFBIN by itself.
+/// Encrypted XFBINs can only be read (and written) with a cipher supplied by the caller, and reading one
+/// without a cipher returns an error.
+pub trait XfbinCipher {
+    fn decrypt(&self, payload: Vec<u8>) -> Result<Vec<u8>, NuccError>;
+    fn encrypt(&self, payload: Vec<u8>) -> Result<Vec<u8>, NuccError>;
+}
+
+pub fn is_encrypted(bytes: &[u8]) -> bool {
+    bytes.len() > HEADER_SIZE && &bytes[..4] == b"NUCC" && bytes[ENCRYPTED_FLAG_OFFSET] != 0
+}
+
+pub(crate) fn decrypt(
+    mut bytes: Vec<u8>,
+    cipher: Option<&dyn XfbinCipher>,
+) -> Result<Vec<u8>, DekuError> {
+    let cipher = cipher.ok_or_else(|| {
+        DekuError::Parse(
+            "XFBIN is encrypted. Decryption is not implemented, so a cipher for its algorithm has to be given."
+                .to_string(),
+        )
+    })?;
+
+    let payload = cipher
+        .decrypt(bytes.split_off(HEADER_SIZE))
+        .map_err(|err| DekuError::Parse(format!("Could not decrypt XFBIN: {err}")))?;
+
+    // The rest of the file is read as a regular XFBIN
+    bytes[ENCRYPTED_FLAG_OFFSET] = 0;
+    bytes.extend(payload);
+
+    Ok(bytes)
+}
+
+pub(crate) fn encrypt(mut bytes: Vec<u8>, cipher: &dyn XfbinCipher) -> Result<Vec<u8>, DekuError> {
+    let payload = cipher
+        .encrypt(bytes.split_off(HEADER_SIZE))
+        .map_err(|err| DekuError::Parse(format!("Could not encrypt XFBIN: {err}")))?;
+
+    bytes[ENCRYPTED_FLAG_OFFSET] = 1;
+    bytes.extend(payload);
+
+    Ok(bytes)
+}
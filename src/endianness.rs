@@ -0,0 +1,57 @@
+pub use deku::ctx::Endian;
+
+// Offsets in the XFBIN header
+const VERSION_OFFSET: usize = 0x04;
+
+/// Byte order of an XFBIN. The container (header, index and chunk headers) and the chunk payloads
+/// are kept separately, as some platforms use a big endian container with little endian chunks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Endianness {
+    pub container: Endian,
+    pub chunks: Endian,
+}
+
+impl Endianness {
+    /// PS3/Xbox 360 era files.
+    pub const BIG: Self = Self {
+        container: Endian::Big,
+        chunks: Endian::Big,
+    };
+
+    /// PC/Switch era files that are fully little endian.
+    pub const LITTLE: Self = Self {
+        container: Endian::Little,
+        chunks: Endian::Little,
+    };
+
+    /// Big endian container with little endian chunk payloads.
+    pub const MIXED: Self = Self {
+        container: Endian::Big,
+        chunks: Endian::Little,
+    };
+
+    /// Detects the container's byte order from the version in the header.
+    /// Returns None if the bytes are not an XFBIN.
+    pub fn detect_container(bytes: &[u8]) -> Option<Endian> {
+        if bytes.len() < VERSION_OFFSET + 4 || &bytes[..4] != b"NUCC" {
+            return None;
+        }
+
+        let version: [u8; 4] = bytes[VERSION_OFFSET..(VERSION_OFFSET + 4)]
+            .try_into()
+            .unwrap();
+
+        // Versions are small, so only the low bytes are set in the correct byte order
+        Some(if u32::from_be_bytes(version) <= u16::MAX as u32 {
+            Endian::Big
+        } else {
+            Endian::Little
+        })
+    }
+}
+
+impl Default for Endianness {
+    fn default() -> Self {
+        Self::BIG
+    }
+}
@@ -5,6 +5,53 @@ use strum_macros::Display;
 #[derive(Debug, Display)]
 pub enum NuccError {
     GenericError,
+    IoError,
+
+    // Chunk errors
+    InvalidChunkIndex,
+    ChunkHandlerNotFound,
+
+    // Animation editing errors
+    InvalidTimeScale,
+    InvalidFrameRange,
+    AnmFrameSizeMismatch,
+    AnmClumpMismatch,
+    KeyframesMismatch,
+    InvalidAnmEntry,
+
+    // Animation import errors
+    InvalidGltf,
+    InvalidBvh,
+    AnimationNotFound,
+
+    // Compression errors
+    InvalidCompressedData,
+
+    // CPK errors
+    InvalidCpk,
+    CpkFileNotFound,
+
+    // Raw chunk errors
+    RawChunkIoError,
+    InvalidRawChunkMetadata,
+    RawChunkNotFound,
+
+    // Mesh errors
+    InvalidObj,
+    InvalidMesh,
+    MorphPrimitiveNotFound,
+    PrimitiveVertexNotFound,
+
+    // Image errors
+    InvalidImage,
+
+    // Font errors
+    InvalidBmFont,
+
+    // Binary errors
+    BinaryTypeNotFound,
+    BinaryHandlerNotFound,
+    InvalidBinary,
 }
 
 impl error::Error for NuccError {}
@@ -0,0 +1,135 @@
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumIter, EnumString};
+
+#[cfg(feature = "binary-types")]
+use crate::binary::NuccBinaryType;
+#[cfg(feature = "binary-types")]
+use crate::error::NuccError;
+#[cfg(feature = "binary-types")]
+use crate::nucc::NuccBinary;
+#[cfg(feature = "binary-types")]
+use crate::xfbin::Xfbin;
+
+/// Games that use the XFBIN format.
+#[derive(
+    Debug, Display, EnumString, EnumIter, Serialize, Deserialize, Clone, Copy, PartialEq, Eq,
+)]
+#[strum(ascii_case_insensitive)]
+pub enum Game {
+    NarutoStorm1,
+    NarutoStorm2,
+    NarutoStormGenerations,
+    NarutoStorm3,
+    NarutoStormRevolution,
+    NarutoStorm4,
+    NarutoStormConnections,
+    JojoAsb,
+    JojoEoh,
+    JojoAsbr,
+}
+
+/// Binary type versions used by a game.
+///
+/// Chunk versions and the XFBIN byte order are kept as read from each file, since the games' values
+/// (and any chunk layouts that depend on them) are not documented.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameProfile {
+    pub game: Game,
+
+    /// Names of the game in the binary types' version options, used to select the version index.
+    /// Only registered binary types have version options.
+    pub binary_version_names: &'static [&'static str],
+}
+
+impl Game {
+    pub fn profile(&self) -> GameProfile {
+        let binary_version_names: &'static [&'static str] = match self {
+            Game::NarutoStorm1 => &["Storm 1", "NS1"],
+            Game::NarutoStorm2 => &["Storm 2", "NS2"],
+            Game::NarutoStormGenerations => &["Generations", "NSG"],
+            Game::NarutoStorm3 => &["Storm 3", "NS3"],
+            Game::NarutoStormRevolution => &["Revolution", "NSR"],
+            Game::NarutoStorm4 => &["Storm 4", "NS4"],
+            Game::NarutoStormConnections => &["Connections", "NSC"],
+            Game::JojoAsb => &["ASB", "All Star Battle"],
+            Game::JojoEoh => &["EOH", "Eyes of Heaven"],
+            Game::JojoAsbr => &["ASBR", "All Star Battle R"],
+        };
+
+        GameProfile {
+            game: *self,
+            binary_version_names,
+        }
+    }
+}
+
+#[cfg(feature = "binary-types")]
+impl GameProfile {
+    /// Returns the index of the game's version in the binary type's version options.
+    /// Defaults to the first version if the game is not listed, or if the type has no versions.
+    pub fn binary_version(&self, binary_type: &NuccBinaryType) -> usize {
+        let names = self
+            .binary_version_names
+            .iter()
+            .map(|name| name.to_lowercase())
+            .collect::<Vec<_>>();
+
+        binary_type
+            .version_options()
+            .iter()
+            .position(|option| {
+                let option = option.to_lowercase();
+
+                // Names are compared to whole parts of the option, so "ASB" does not match "ASBR"
+                names.iter().any(|name| {
+                    option
+                        .split(|c: char| !c.is_alphanumeric() && c != ' ')
+                        .any(|part| part.trim() == name)
+                })
+            })
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(feature = "binary-types")]
+impl Xfbin {
+    /// Rewrites binary chunks of registered types from one game's binary versions to another's.
+    /// Chunk versions and the byte order are left unchanged.
+    pub fn convert_binaries(
+        &mut self,
+        from: &GameProfile,
+        to: &GameProfile,
+    ) -> Result<(), NuccError> {
+        for nucc_struct in self
+            .pages
+            .iter_mut()
+            .flat_map(|page| page.structs.iter_mut())
+        {
+            if let Some(binary) = nucc_struct.downcast_mut::<NuccBinary>() {
+                convert_binary(binary, from, to)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "binary-types")]
+fn convert_binary(
+    binary: &mut NuccBinary,
+    from: &GameProfile,
+    to: &GameProfile,
+) -> Result<(), NuccError> {
+    // Binaries of unsupported types have no versions to convert
+    if let Some((binary_type, endian)) = binary.get_binary_type() {
+        let from_version = from.binary_version(&binary_type);
+        let to_version = to.binary_version(&binary_type);
+
+        if from_version != to_version {
+            let parsed = binary.parse_data(Some((binary_type, endian)), None, from_version)?;
+            binary.update_data(parsed, endian, to_version)?;
+        }
+    }
+
+    Ok(())
+}
@@ -1,13 +1,31 @@
+#[cfg(feature = "binary-types")]
+pub mod binary;
+pub mod compression;
+pub mod cpk;
+pub mod encryption;
+pub mod endianness;
 pub mod error;
+pub mod game;
+pub mod mesh;
 pub mod nucc;
 mod nucc_chunk;
+pub mod raw_chunks;
 mod utils;
 pub mod xfbin;
 mod xfbin_file;
 
-use std::{fs, path::Path};
+use std::{
+    fs,
+    io::{Read, Seek},
+    path::Path,
+};
 
+use compression::Compression;
+use cpk::Cpk;
 use deku::{bitvec::BitView, DekuError, DekuRead, DekuWrite};
+use encryption::XfbinCipher;
+use endianness::Endianness;
+use error::NuccError;
 use utils::*;
 use xfbin::*;
 use xfbin_file::*;
@@ -18,19 +36,189 @@ pub fn read_xfbin(file_path: &dyn AsRef<Path>) -> Result<Xfbin, DekuError> {
     read_xfbin_bytes(fs::read(file_path).unwrap())
 }
 
+pub fn read_xfbin_with_cipher(
+    file_path: &dyn AsRef<Path>,
+    cipher: Option<&dyn XfbinCipher>,
+) -> Result<Xfbin, DekuError> {
+    read_xfbin_bytes_with_cipher(fs::read(file_path).unwrap(), cipher)
+}
+
+pub fn read_xfbin_with_options(
+    file_path: &dyn AsRef<Path>,
+    options: &XfbinReadOptions,
+) -> Result<Xfbin, DekuError> {
+    read_xfbin_bytes_with_options(fs::read(file_path).unwrap(), options)
+}
+
 pub fn read_xfbin_bytes(bytes: Vec<u8>) -> Result<Xfbin, DekuError> {
-    XfbinFile::read(bytes.view_bits(), ()).map(|(_, value)| value.into())
+    read_xfbin_bytes_with_options(bytes, &XfbinReadOptions::default())
+}
+
+/// Reads an XFBIN that might be compressed or encrypted.
+/// Returns an error if the file is encrypted and no cipher was given.
+pub fn read_xfbin_bytes_with_cipher(
+    bytes: Vec<u8>,
+    cipher: Option<&dyn XfbinCipher>,
+) -> Result<Xfbin, DekuError> {
+    read_xfbin_bytes_with_options(
+        bytes,
+        &XfbinReadOptions {
+            cipher,
+            ..Default::default()
+        },
+    )
+}
+
+/// Reads an XFBIN that might be compressed or encrypted, or stored inside a CPK archive at `options.cpk_path`.
+pub fn read_xfbin_bytes_with_options(
+    bytes: Vec<u8>,
+    options: &XfbinReadOptions,
+) -> Result<Xfbin, DekuError> {
+    let bytes = match cpk::is_cpk(&bytes) {
+        true => extract_from_cpk(Cpk::from_bytes(bytes), options.cpk_path)?,
+        false => bytes,
+    };
+
+    let mut bytes = compression::decompress(bytes)
+        .map_err(|err| DekuError::Parse(format!("Could not decompress XFBIN: {err}")))?;
+
+    if encryption::is_encrypted(&bytes) {
+        bytes = encryption::decrypt(bytes, options.cipher)?;
+    }
+
+    let container = match options.endianness {
+        Some(endianness) => endianness.container,
+        None => Endianness::detect_container(&bytes)
+            .ok_or_else(|| DekuError::Parse("Not a valid XFBIN.".to_string()))?,
+    };
+
+    let (_, mut xfbin_file) = XfbinFile::read(bytes.view_bits(), container)?;
+    xfbin_file.endianness = options.endianness.unwrap_or_else(|| Endianness {
+        container,
+        chunks: xfbin_file.detect_chunk_endian().unwrap_or(container),
+    });
+
+    Xfbin::try_from(xfbin_file)
+}
+
+fn extract_from_cpk<R: Read + Seek>(
+    cpk: Result<Cpk<R>, NuccError>,
+    file_path: Option<&str>,
+) -> Result<Vec<u8>, DekuError> {
+    let file_path = file_path.ok_or_else(|| {
+        DekuError::Parse("The file is a CPK archive, but no XFBIN path was given.".to_string())
+    })?;
+
+    cpk.and_then(|mut cpk| cpk.extract(file_path))
+        .map_err(|err| DekuError::Parse(format!("Could not extract {file_path} from CPK: {err}")))
+}
+
+/// Reads an XFBIN from inside a CPK archive without extracting it to disk.
+/// Only the XFBIN is read from the archive, instead of the whole file.
+pub fn read_xfbin_from_cpk(
+    cpk_path: &dyn AsRef<Path>,
+    file_path: &str,
+) -> Result<Xfbin, DekuError> {
+    read_xfbin_bytes(extract_from_cpk(Cpk::open(cpk_path), Some(file_path))?)
+}
+
+pub fn read_xfbin_from_cpk_bytes(cpk_bytes: Vec<u8>, file_path: &str) -> Result<Xfbin, DekuError> {
+    read_xfbin_bytes_with_options(
+        cpk_bytes,
+        &XfbinReadOptions {
+            cpk_path: Some(file_path),
+            ..Default::default()
+        },
+    )
+}
+
+#[derive(Default)]
+pub struct XfbinReadOptions<'a> {
+    /// Decrypts the XFBIN if it is encrypted.
+    pub cipher: Option<&'a dyn XfbinCipher>,
+
+    /// Byte order of the XFBIN. Detected from the header and the first page if not set.
+    pub endianness: Option<Endianness>,
+
+    /// Path of the XFBIN inside the archive, if the file is a CPK archive.
+    pub cpk_path: Option<&'a str>,
+}
+
+#[derive(Default)]
+pub struct XfbinWriteOptions<'a> {
+    /// Encrypts the XFBIN after it is written.
+    pub cipher: Option<&'a dyn XfbinCipher>,
+
+    /// Compresses the XFBIN after it is written (and encrypted).
+    pub compression: Compression,
+
+    /// Overrides the byte order the XFBIN was read with (i.e. to convert it to another platform).
+    pub endianness: Option<Endianness>,
 }
 
 pub fn write_xfbin(xfbin: Xfbin, file_path: &dyn AsRef<Path>) -> Result<(), DekuError> {
-    write_xfbin_bytes(xfbin).map(|output| {
+    write_xfbin_with_options(xfbin, file_path, &XfbinWriteOptions::default())
+}
+
+pub fn write_xfbin_with_cipher(
+    xfbin: Xfbin,
+    file_path: &dyn AsRef<Path>,
+    cipher: Option<&dyn XfbinCipher>,
+) -> Result<(), DekuError> {
+    write_xfbin_with_options(
+        xfbin,
+        file_path,
+        &XfbinWriteOptions {
+            cipher,
+            ..Default::default()
+        },
+    )
+}
+
+pub fn write_xfbin_with_options(
+    xfbin: Xfbin,
+    file_path: &dyn AsRef<Path>,
+    options: &XfbinWriteOptions,
+) -> Result<(), DekuError> {
+    write_xfbin_bytes_with_options(xfbin, options).map(|output| {
         fs::write(file_path, output).unwrap();
     })
 }
 
 pub fn write_xfbin_bytes(xfbin: Xfbin) -> Result<Vec<u8>, DekuError> {
+    write_xfbin_bytes_with_options(xfbin, &XfbinWriteOptions::default())
+}
+
+/// Writes an XFBIN, and encrypts it if a cipher was given.
+pub fn write_xfbin_bytes_with_cipher(
+    xfbin: Xfbin,
+    cipher: Option<&dyn XfbinCipher>,
+) -> Result<Vec<u8>, DekuError> {
+    write_xfbin_bytes_with_options(
+        xfbin,
+        &XfbinWriteOptions {
+            cipher,
+            ..Default::default()
+        },
+    )
+}
+
+pub fn write_xfbin_bytes_with_options(
+    mut xfbin: Xfbin,
+    options: &XfbinWriteOptions,
+) -> Result<Vec<u8>, DekuError> {
+    if let Some(endianness) = options.endianness {
+        xfbin.endianness = endianness;
+    }
+
     let mut output = DekuBitVec::new();
-    XfbinFile::from(xfbin)
-        .write(&mut output, ())
-        .map(|_| output.into_vec())
+    let xfbin_file = XfbinFile::try_from(xfbin)?;
+    xfbin_file.write(&mut output, xfbin_file.endianness.container)?;
+
+    let bytes = match options.cipher {
+        Some(cipher) => encryption::encrypt(output.into_vec(), cipher)?,
+        None => output.into_vec(),
+    };
+
+    Ok(options.compression.compress(bytes))
 }
@@ -0,0 +1,688 @@
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    fmt::Write as _,
+    fs,
+    io::{self, Write},
+    path::Path,
+};
+
+use gltf::binary::Glb;
+use hashbrown::HashMap;
+use serde_json::{json, value::RawValue};
+
+use crate::error::NuccError;
+use crate::nucc::{NuccModelHit, NuccModelPrimitiveBatch, NuccPrimitiveVertex};
+use crate::xfbin::Xfbin;
+
+/// A triangle mesh, used to export/import chunks that store geometry.
+/// Normals and UVs are either empty or have one value per position.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Mesh {
+    pub name: String,
+
+    pub positions: Vec<[f32; 3]>,
+    pub normals: Vec<[f32; 3]>,
+    pub uvs: Vec<[f32; 2]>,
+
+    /// Three indices per triangle.
+    pub indices: Vec<u32>,
+
+    pub morph_targets: Vec<MorphTarget>,
+}
+
+/// A blend shape of a mesh. Normal deltas are either empty or have one value per position delta.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct MorphTarget {
+    pub name: String,
+
+    /// One value per position of the mesh.
+    pub position_deltas: Vec<[f32; 3]>,
+    pub normal_deltas: Vec<[f32; 3]>,
+}
+
+impl Mesh {
+    /// Creates an indexed mesh from a list of triangles, merging vertices with the same position.
+    pub fn from_triangles(name: &str, triangles: &[[[f32; 3]; 3]]) -> Self {
+        let mut mesh = Mesh {
+            name: name.to_string(),
+            ..Default::default()
+        };
+
+        let mut vertex_map = HashMap::new();
+        for vertex in triangles.iter().flatten() {
+            let key = vertex.map(f32::to_bits);
+            let index = *vertex_map.entry(key).or_insert_with(|| {
+                mesh.positions.push(*vertex);
+                (mesh.positions.len() - 1) as u32
+            });
+
+            mesh.indices.push(index);
+        }
+
+        mesh
+    }
+
+    pub fn triangles(&self) -> impl Iterator<Item = [[f32; 3]; 3]> + '_ {
+        self.indices.chunks_exact(3).map(|triangle| {
+            [
+                self.positions[triangle[0] as usize],
+                self.positions[triangle[1] as usize],
+                self.positions[triangle[2] as usize],
+            ]
+        })
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.indices.len() / 3
+    }
+
+    /// Writes the meshes as objects of a Wavefront OBJ file.
+    pub fn write_obj(meshes: &[Mesh], writer: &mut dyn Write) -> io::Result<()> {
+        // OBJ indices are global and start from 1, and positions, UVs and normals are counted separately
+        let (mut position_offset, mut uv_offset, mut normal_offset) = (1, 1, 1);
+
+        for mesh in meshes {
+            let mut output = String::new();
+            writeln!(output, "o {}", mesh.name).unwrap();
+
+            for [x, y, z] in mesh.positions.iter() {
+                writeln!(output, "v {x} {y} {z}").unwrap();
+            }
+
+            for [u, v] in mesh.uvs.iter() {
+                writeln!(output, "vt {u} {}", 1.0 - v).unwrap();
+            }
+
+            for [x, y, z] in mesh.normals.iter() {
+                writeln!(output, "vn {x} {y} {z}").unwrap();
+            }
+
+            let face_vertex = |index: u32| {
+                let (v, vt, vn) = (
+                    index + position_offset,
+                    index + uv_offset,
+                    index + normal_offset,
+                );
+                match (mesh.uvs.is_empty(), mesh.normals.is_empty()) {
+                    (true, true) => format!("{v}"),
+                    (false, true) => format!("{v}/{vt}"),
+                    (true, false) => format!("{v}//{vn}"),
+                    (false, false) => format!("{v}/{vt}/{vn}"),
+                }
+            };
+
+            for triangle in mesh.indices.chunks_exact(3) {
+                writeln!(
+                    output,
+                    "f {} {} {}",
+                    face_vertex(triangle[0]),
+                    face_vertex(triangle[1]),
+                    face_vertex(triangle[2])
+                )
+                .unwrap();
+            }
+
+            writer.write_all(output.as_bytes())?;
+            position_offset += mesh.positions.len() as u32;
+            uv_offset += mesh.uvs.len() as u32;
+            normal_offset += mesh.normals.len() as u32;
+        }
+
+        Ok(())
+    }
+
+    /// Reads the objects (or groups) of a Wavefront OBJ file as meshes. Polygons are triangulated as fans.
+    pub fn read_obj(text: &str) -> Result<Vec<Mesh>, NuccError> {
+        fn parse_floats<const N: usize>(parts: &[&str]) -> Result<[f32; N], NuccError> {
+            let mut values = [0.0; N];
+            for (value, part) in values.iter_mut().zip(parts.iter()) {
+                *value = part.parse().map_err(|_| NuccError::InvalidObj)?;
+            }
+
+            Ok(values)
+        }
+
+        let mut positions = vec![];
+        let mut uvs = vec![];
+        let mut normals = vec![];
+
+        let mut meshes = vec![];
+        let mut mesh = Mesh::default();
+
+        // Vertices are shared between all objects, so each mesh maps them to its own indices
+        let mut vertex_map: HashMap<(usize, Option<usize>, Option<usize>), u32> = HashMap::new();
+
+        for line in text.lines() {
+            let parts = line.split_whitespace().collect::<Vec<_>>();
+
+            match parts.split_first() {
+                Some((&"v", rest)) if rest.len() >= 3 => positions.push(parse_floats::<3>(rest)?),
+                Some((&"vt", rest)) if rest.len() >= 2 => {
+                    let [u, v] = parse_floats::<2>(rest)?;
+                    uvs.push([u, 1.0 - v]);
+                }
+                Some((&"vn", rest)) if rest.len() >= 3 => normals.push(parse_floats::<3>(rest)?),
+                Some((&("o" | "g"), rest)) => {
+                    if !mesh.indices.is_empty() {
+                        meshes.push(mesh);
+                        vertex_map.clear();
+                    }
+
+                    mesh = Mesh {
+                        name: rest.join(" "),
+                        ..Default::default()
+                    };
+                }
+                Some((&"f", rest)) if rest.len() >= 3 => {
+                    let mut face = vec![];
+
+                    for vertex in rest {
+                        // Negative indices are relative to the end of the list
+                        let resolve = |index: Option<&str>,
+                                       count: usize|
+                         -> Result<Option<usize>, NuccError> {
+                            match index.filter(|i| !i.is_empty()) {
+                                None => Ok(None),
+                                Some(index) => {
+                                    let index: i64 =
+                                        index.parse().map_err(|_| NuccError::InvalidObj)?;
+                                    let resolved = if index < 0 {
+                                        count as i64 + index
+                                    } else {
+                                        index - 1
+                                    };
+
+                                    if resolved < 0 || resolved >= count as i64 {
+                                        return Err(NuccError::InvalidObj);
+                                    }
+
+                                    Ok(Some(resolved as usize))
+                                }
+                            }
+                        };
+
+                        let mut indices = vertex.split('/');
+                        let position = resolve(indices.next(), positions.len())?
+                            .ok_or(NuccError::InvalidObj)?;
+                        let uv = resolve(indices.next(), uvs.len())?;
+                        let normal = resolve(indices.next(), normals.len())?;
+
+                        let index =
+                            *vertex_map.entry((position, uv, normal)).or_insert_with(|| {
+                                mesh.positions.push(positions[position]);
+
+                                if let Some(uv) = uv {
+                                    mesh.uvs.push(uvs[uv]);
+                                }
+
+                                if let Some(normal) = normal {
+                                    mesh.normals.push(normals[normal]);
+                                }
+
+                                (mesh.positions.len() - 1) as u32
+                            });
+
+                        face.push(index);
+                    }
+
+                    for i in 1..(face.len() - 1) {
+                        mesh.indices.extend([face[0], face[i], face[i + 1]]);
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        if !mesh.indices.is_empty() {
+            meshes.push(mesh);
+        }
+
+        // Attributes that are missing for some vertices are dropped
+        for mesh in meshes.iter_mut() {
+            if mesh.uvs.len() != mesh.positions.len() {
+                mesh.uvs.clear();
+            }
+
+            if mesh.normals.len() != mesh.positions.len() {
+                mesh.normals.clear();
+            }
+        }
+
+        Ok(meshes)
+    }
+
+    /// Writes the meshes as a binary glTF (.glb), with one node per mesh.
+    /// Meshes without vertices are skipped, since glTF accessors can't be empty.
+    pub fn to_glb(meshes: &[Mesh]) -> Result<Vec<u8>, NuccError> {
+        use gltf::json::{
+            self, accessor, buffer,
+            validation::{Checked::Valid, USize64},
+            Index,
+        };
+
+        #[derive(Default)]
+        struct Buffers {
+            data: Vec<u8>,
+            views: Vec<buffer::View>,
+            accessors: Vec<json::Accessor>,
+        }
+
+        impl Buffers {
+            fn add_accessor(
+                &mut self,
+                data: impl Iterator<Item = [u8; 4]>,
+                count: usize,
+                component_type: accessor::ComponentType,
+                type_: accessor::Type,
+                target: buffer::Target,
+            ) -> Index<json::Accessor> {
+                // Every component is 4 bytes, so the views stay aligned
+                let start = self.data.len();
+                self.data.extend(data.flatten());
+
+                self.views.push(buffer::View {
+                    buffer: Index::new(0),
+                    byte_length: USize64::from(self.data.len() - start),
+                    byte_offset: Some(USize64::from(start)),
+                    byte_stride: None,
+                    extensions: Default::default(),
+                    extras: Default::default(),
+                    name: None,
+                    target: Some(Valid(target)),
+                });
+
+                self.accessors.push(json::Accessor {
+                    buffer_view: Some(Index::new(self.views.len() as u32 - 1)),
+                    byte_offset: None,
+                    count: USize64::from(count),
+                    component_type: Valid(accessor::GenericComponentType(component_type)),
+                    extensions: Default::default(),
+                    extras: Default::default(),
+                    type_: Valid(type_),
+                    min: None,
+                    max: None,
+                    name: None,
+                    normalized: false,
+                    sparse: None,
+                });
+
+                Index::new(self.accessors.len() as u32 - 1)
+            }
+
+            fn add_vectors<const N: usize>(
+                &mut self,
+                values: &[[f32; N]],
+                type_: accessor::Type,
+            ) -> Option<Index<json::Accessor>> {
+                if values.is_empty() {
+                    return None;
+                }
+
+                Some(self.add_accessor(
+                    values.iter().flatten().map(|v| v.to_le_bytes()),
+                    values.len(),
+                    accessor::ComponentType::F32,
+                    type_,
+                    buffer::Target::ArrayBuffer,
+                ))
+            }
+
+            /// POSITION accessors need their bounds.
+            fn add_positions(&mut self, values: &[[f32; 3]]) -> Option<Index<json::Accessor>> {
+                let index = self.add_vectors(values, accessor::Type::Vec3)?;
+
+                let (min, max) = values
+                    .iter()
+                    .fold((values[0], values[0]), |(min, max), value| {
+                        (
+                            std::array::from_fn(|i| min[i].min(value[i])),
+                            std::array::from_fn(|i| max[i].max(value[i])),
+                        )
+                    });
+
+                let accessor = &mut self.accessors[index.value()];
+                accessor.min = Some(serde_json::Value::from(min.to_vec()));
+                accessor.max = Some(serde_json::Value::from(max.to_vec()));
+
+                Some(index)
+            }
+        }
+
+        let mut buffers = Buffers::default();
+        let mut gltf_meshes = vec![];
+        let mut nodes = vec![];
+
+        for mesh in meshes.iter().filter(|m| !m.positions.is_empty()) {
+            let mut attributes = BTreeMap::new();
+
+            if let Some(positions) = buffers.add_positions(&mesh.positions) {
+                attributes.insert(Valid(json::mesh::Semantic::Positions), positions);
+            }
+
+            if let Some(normals) = buffers.add_vectors(&mesh.normals, accessor::Type::Vec3) {
+                attributes.insert(Valid(json::mesh::Semantic::Normals), normals);
+            }
+
+            if let Some(uvs) = buffers.add_vectors(&mesh.uvs, accessor::Type::Vec2) {
+                attributes.insert(Valid(json::mesh::Semantic::TexCoords(0)), uvs);
+            }
+
+            let indices = (!mesh.indices.is_empty()).then(|| {
+                buffers.add_accessor(
+                    mesh.indices.iter().map(|i| i.to_le_bytes()),
+                    mesh.indices.len(),
+                    accessor::ComponentType::U32,
+                    accessor::Type::Scalar,
+                    buffer::Target::ElementArrayBuffer,
+                )
+            });
+
+            let targets = mesh
+                .morph_targets
+                .iter()
+                .map(|target| json::mesh::MorphTarget {
+                    positions: buffers.add_positions(&target.position_deltas),
+                    normals: buffers.add_vectors(&target.normal_deltas, accessor::Type::Vec3),
+                    tangents: None,
+                })
+                .collect::<Vec<_>>();
+            let target_count = targets.len();
+
+            // Not part of the spec, but used by most importers for the names of the targets
+            let extras = (target_count != 0).then(|| {
+                let target_names = mesh
+                    .morph_targets
+                    .iter()
+                    .map(|t| &t.name)
+                    .collect::<Vec<_>>();
+                RawValue::from_string(json!({ "targetNames": target_names }).to_string()).unwrap()
+            });
+
+            gltf_meshes.push(json::Mesh {
+                extensions: Default::default(),
+                extras,
+                name: Some(mesh.name.clone()),
+                primitives: vec![json::mesh::Primitive {
+                    attributes,
+                    extensions: Default::default(),
+                    extras: Default::default(),
+                    indices,
+                    material: None,
+                    mode: Valid(json::mesh::Mode::Triangles),
+                    targets: (target_count != 0).then_some(targets),
+                }],
+                weights: (target_count != 0).then(|| vec![0.0; target_count]),
+            });
+
+            nodes.push(json::Node {
+                mesh: Some(Index::new(gltf_meshes.len() as u32 - 1)),
+                name: Some(mesh.name.clone()),
+                ..Default::default()
+            });
+        }
+
+        let Buffers {
+            data,
+            views,
+            accessors,
+        } = buffers;
+
+        let root = json::Root {
+            asset: json::Asset {
+                generator: Some("xfbin-lib-rs".to_string()),
+                ..Default::default()
+            },
+            scene: Some(Index::new(0)),
+            scenes: vec![json::Scene {
+                extensions: Default::default(),
+                extras: Default::default(),
+                name: None,
+                nodes: (0..nodes.len() as u32).map(Index::new).collect(),
+            }],
+            nodes,
+            meshes: gltf_meshes,
+            accessors,
+            buffer_views: views,
+            buffers: if data.is_empty() {
+                vec![]
+            } else {
+                vec![json::Buffer {
+                    byte_length: USize64::from(data.len()),
+                    extensions: Default::default(),
+                    extras: Default::default(),
+                    name: None,
+                    uri: None,
+                }]
+            },
+            ..Default::default()
+        };
+
+        let json_chunk = serde_json::to_vec(&root).map_err(|_| NuccError::InvalidGltf)?;
+
+        Glb {
+            header: gltf::binary::Header {
+                magic: *b"glTF",
+                version: 2,
+                // Calculated with the chunks' padding when the file is written
+                length: 0,
+            },
+            json: Cow::Owned(json_chunk),
+            bin: (!data.is_empty()).then_some(Cow::Owned(data)),
+        }
+        .to_vec()
+        .map_err(|_| NuccError::InvalidGltf)
+    }
+
+    /// Reads the triangle primitives of every node in a glTF file, with the nodes' transforms applied to the positions.
+    pub fn read_gltf(file_path: &dyn AsRef<Path>) -> Result<Vec<Mesh>, NuccError> {
+        type Matrix = [[f32; 4]; 4];
+
+        fn multiply(a: &Matrix, b: &Matrix) -> Matrix {
+            let mut result = [[0.0; 4]; 4];
+            for (c, column) in result.iter_mut().enumerate() {
+                for (r, value) in column.iter_mut().enumerate() {
+                    *value = (0..4).map(|k| a[k][r] * b[c][k]).sum();
+                }
+            }
+
+            result
+        }
+
+        fn transform(m: &Matrix, [x, y, z]: [f32; 3], w: f32) -> [f32; 3] {
+            [
+                m[0][0] * x + m[1][0] * y + m[2][0] * z + m[3][0] * w,
+                m[0][1] * x + m[1][1] * y + m[2][1] * z + m[3][1] * w,
+                m[0][2] * x + m[1][2] * y + m[2][2] * z + m[3][2] * w,
+            ]
+        }
+
+        fn read_node(
+            node: gltf::Node,
+            parent: &Matrix,
+            buffers: &[gltf::buffer::Data],
+            meshes: &mut Vec<Mesh>,
+        ) {
+            let matrix = multiply(parent, &node.transform().matrix());
+
+            if let Some(gltf_mesh) = node.mesh() {
+                for primitive in gltf_mesh
+                    .primitives()
+                    .filter(|p| p.mode() == gltf::mesh::Mode::Triangles)
+                {
+                    let reader = primitive.reader(|buffer| Some(&buffers[buffer.index()]));
+
+                    let positions = match reader.read_positions() {
+                        Some(positions) => positions
+                            .map(|p| transform(&matrix, p, 1.0))
+                            .collect::<Vec<_>>(),
+                        None => continue,
+                    };
+
+                    let indices = reader
+                        .read_indices()
+                        .map(|i| i.into_u32().collect())
+                        .unwrap_or_else(|| (0..positions.len() as u32).collect());
+
+                    meshes.push(Mesh {
+                        name: node
+                            .name()
+                            .or_else(|| gltf_mesh.name())
+                            .unwrap_or_default()
+                            .to_string(),
+                        normals: reader
+                            .read_normals()
+                            .map(|n| n.map(|n| transform(&matrix, n, 0.0)).collect())
+                            .unwrap_or_default(),
+                        uvs: reader
+                            .read_tex_coords(0)
+                            .map(|uvs| uvs.into_f32().collect())
+                            .unwrap_or_default(),
+                        morph_targets: reader
+                            .read_morph_targets()
+                            .enumerate()
+                            .map(|(i, (position_deltas, normal_deltas, _))| MorphTarget {
+                                name: format!("target_{i}"),
+                                position_deltas: position_deltas
+                                    .map(|p| p.map(|p| transform(&matrix, p, 0.0)).collect())
+                                    .unwrap_or_else(|| vec![[0.0; 3]; positions.len()]),
+                                normal_deltas: normal_deltas
+                                    .map(|n| n.map(|n| transform(&matrix, n, 0.0)).collect())
+                                    .unwrap_or_default(),
+                            })
+                            .collect(),
+                        positions,
+                        indices,
+                    });
+                }
+            }
+
+            for child in node.children() {
+                read_node(child, &matrix, buffers, meshes);
+            }
+        }
+
+        let (document, buffers, _) = gltf::import(file_path).map_err(|_| NuccError::InvalidGltf)?;
+
+        let identity = [
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ];
+
+        let mut meshes = vec![];
+        for scene in document.scenes() {
+            for node in scene.nodes() {
+                read_node(node, &identity, &buffers, &mut meshes);
+            }
+        }
+
+        Ok(meshes)
+    }
+
+    /// Reads meshes from an OBJ or glTF file, depending on its extension.
+    pub fn read_file(file_path: &dyn AsRef<Path>) -> Result<Vec<Mesh>, NuccError> {
+        let path = file_path.as_ref();
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or_default()
+            .to_lowercase();
+
+        match extension.as_str() {
+            "obj" => {
+                let text = fs::read_to_string(path).map_err(|_| NuccError::IoError)?;
+                Mesh::read_obj(&text)
+            }
+            _ => Mesh::read_gltf(&path),
+        }
+    }
+}
+
+impl Xfbin {
+    /// Returns the meshes of every struct that stores geometry, in page order.
+    pub fn meshes(&self) -> Result<Vec<Mesh>, NuccError> {
+        let vertex_buffers = self
+            .pages
+            .iter()
+            .flat_map(|page| page.structs.iter())
+            .filter_map(|s| s.downcast_ref::<NuccPrimitiveVertex>())
+            .map(|v| (&v.struct_info, v))
+            .collect::<HashMap<_, _>>();
+
+        let mut meshes = vec![];
+        for nucc_struct in self.pages.iter().flat_map(|page| page.structs.iter()) {
+            if let Some(model_hit) = nucc_struct.downcast_ref::<NuccModelHit>() {
+                meshes.extend(model_hit.to_meshes());
+            } else if let Some(batch) = nucc_struct.downcast_ref::<NuccModelPrimitiveBatch>() {
+                let vertex_buffer = vertex_buffers
+                    .get(&batch.vertex_buffer)
+                    .ok_or(NuccError::PrimitiveVertexNotFound)?;
+
+                meshes.extend(batch.to_meshes(vertex_buffer)?);
+            }
+        }
+
+        Ok(meshes)
+    }
+
+    pub fn export_meshes_obj(&self, file_path: &dyn AsRef<Path>) -> Result<(), NuccError> {
+        let mut output = vec![];
+        Mesh::write_obj(&self.meshes()?, &mut output).map_err(|_| NuccError::InvalidObj)?;
+        fs::write(file_path, output).map_err(|_| NuccError::IoError)
+    }
+
+    pub fn export_meshes_glb(&self, file_path: &dyn AsRef<Path>) -> Result<(), NuccError> {
+        fs::write(file_path, Mesh::to_glb(&self.meshes()?)?).map_err(|_| NuccError::IoError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn triangle(name: &str, with_attributes: bool) -> Mesh {
+        let mut mesh =
+            Mesh::from_triangles(name, &[[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]]);
+
+        if with_attributes {
+            mesh.uvs = vec![[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]];
+            mesh.normals = vec![[0.0, 0.0, 1.0]; 3];
+        }
+
+        mesh
+    }
+
+    #[test]
+    fn write_obj_counts_attributes_separately() {
+        // Hit meshes only have positions, so they must not advance the UV and normal indices
+        let meshes = [
+            triangle("hit", false),
+            triangle("model", true),
+            triangle("hit2", false),
+            triangle("model2", true),
+        ];
+
+        let mut output = vec![];
+        Mesh::write_obj(&meshes, &mut output).unwrap();
+        let text = String::from_utf8(output).unwrap();
+
+        let faces = text
+            .lines()
+            .filter(|line| line.starts_with("f "))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            faces,
+            [
+                "f 1 2 3",
+                "f 4/1/1 5/2/2 6/3/3",
+                "f 7 8 9",
+                "f 10/4/4 11/5/5 12/6/6",
+            ]
+        );
+
+        assert_eq!(Mesh::read_obj(&text).unwrap(), meshes);
+    }
+}
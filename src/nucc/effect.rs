@@ -0,0 +1,23 @@
+/// An RGBA color, with each component usually in the range [0, 1].
+pub type Color = [f32; 4];
+
+/// Shared by effect structs to recolor all of their colors at once.
+pub trait EffectColors {
+    fn colors_mut(&mut self) -> Vec<&mut Color>;
+
+    /// Multiplies every color by the tint, component-wise.
+    fn tint(&mut self, tint: Color) {
+        for color in self.colors_mut() {
+            for (component, t) in color.iter_mut().zip(tint) {
+                *component *= t;
+            }
+        }
+    }
+
+    /// Replaces the RGB of every color, keeping its alpha.
+    fn set_rgb(&mut self, rgb: [f32; 3]) {
+        for color in self.colors_mut() {
+            color[..3].copy_from_slice(&rgb);
+        }
+    }
+}
@@ -1,18 +1,56 @@
+pub mod effect;
 pub mod nucc_anm;
+pub mod nucc_anm_strm;
+pub mod nucc_anm_strm_frame;
+pub mod nucc_billboard;
 pub mod nucc_binary;
+pub mod nucc_dynamics;
+pub mod nucc_font;
+pub mod nucc_layer_set;
+pub mod nucc_lens_flare;
+pub mod nucc_model_hit;
+pub mod nucc_model_primitive_batch;
+pub mod nucc_morph_model;
+pub mod nucc_morph_primitive;
+pub mod nucc_nub;
+pub mod nucc_particle;
+pub mod nucc_primitive_vertex;
+pub mod nucc_sprite;
+pub mod nucc_sprite2;
+pub mod nucc_trail;
 pub mod nucc_unknown;
+pub mod registry;
 
 use std::fmt;
+use std::hash::Hash;
 
 use downcast_rs::{impl_downcast, Downcast};
 use hashbrown::HashMap;
 use serde::{Deserialize, Serialize};
 
+use super::error::NuccError;
 use super::xfbin_file::XfbinChunkMap;
 
 use super::nucc_chunk::*;
 pub use nucc_anm::NuccAnm;
+pub use nucc_anm_strm::NuccAnmStrm;
+pub use nucc_anm_strm_frame::NuccAnmStrmFrame;
+pub use nucc_billboard::NuccBillboard;
 pub use nucc_binary::NuccBinary;
+pub use nucc_dynamics::NuccDynamics;
+pub use nucc_font::NuccFont;
+pub use nucc_layer_set::NuccLayerSet;
+pub use nucc_lens_flare::NuccLensFlare;
+pub use nucc_model_hit::NuccModelHit;
+pub use nucc_model_primitive_batch::NuccModelPrimitiveBatch;
+pub use nucc_morph_model::NuccMorphModel;
+pub use nucc_morph_primitive::NuccMorphPrimitive;
+pub use nucc_nub::NuccNub;
+pub use nucc_particle::NuccParticle;
+pub use nucc_primitive_vertex::NuccPrimitiveVertex;
+pub use nucc_sprite::NuccSprite;
+pub use nucc_sprite2::NuccSprite2;
+pub use nucc_trail::NuccTrail;
 pub use nucc_unknown::NuccUnknown;
 
 #[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq, Eq, Hash)]
@@ -58,7 +96,7 @@ impl<'a> From<XfbinChunkMapConverter<'a>> for Vec<NuccStructInfo> {
     }
 }
 
-#[derive(Debug, Default, PartialEq, Eq, Clone, Hash)]
+#[derive(Debug, Serialize, Deserialize, Default, PartialEq, Eq, Clone, Hash)]
 pub struct NuccStructReference(pub String, pub NuccStructInfo);
 
 pub struct XfbinChunkReferenceConverter<'a>(
@@ -107,6 +145,9 @@ pub(crate) use impl_nucc_info;
 pub trait NuccStruct: NuccInfo + Downcast {
     fn chunk_type(&self) -> NuccChunkType;
     fn version(&self) -> u16;
+
+    /// Changes the version the struct's chunk is written with. Chunks are written with the same fields in every version.
+    fn set_version(&mut self, version: u16);
 }
 
 impl_downcast!(NuccStruct);
@@ -123,30 +164,488 @@ pub struct NuccChunkConverter<'a>(
     pub &'a mut HashMap<NuccStructReference, u32>,
 );
 
-impl<'a> From<NuccStructConverter<'a>> for Box<dyn NuccStruct> {
-    fn from(converter: NuccStructConverter) -> Self {
-        match converter.0.chunk_type() {
+/// Returns the index of a struct info or reference in a page's map, and adds it if it's not there yet.
+pub(crate) fn index_of<T: Clone + Eq + Hash>(map: &mut HashMap<T, u32>, key: &T) -> u32 {
+    let index = map.len() as u32;
+    *map.entry(key.clone()).or_insert(index)
+}
+
+/// Returns the position of a value in a chunk's own table, and appends it if it's not there yet.
+pub(crate) fn position_of<T: Clone + PartialEq>(vec: &mut Vec<T>, value: &T) -> u16 {
+    match vec.iter().position(|v| v == value) {
+        Some(index) => index as u16,
+        None => {
+            vec.push(value.clone());
+            (vec.len() - 1) as u16
+        }
+    }
+}
+
+/// Returns the struct info or reference at an index that was read from a chunk.
+pub(crate) fn resolve_index<T: Clone>(items: &[T], index: usize) -> Result<T, NuccError> {
+    items
+        .get(index)
+        .cloned()
+        .ok_or(NuccError::InvalidChunkIndex)
+}
+
+impl<'a> TryFrom<NuccStructConverter<'a>> for Box<dyn NuccStruct> {
+    type Error = NuccError;
+
+    fn try_from(converter: NuccStructConverter) -> Result<Self, Self::Error> {
+        Ok(match converter.0.chunk_type() {
             NuccChunkType::NuccChunkAnm => Box::new(NuccAnm::from(converter)),
+            NuccChunkType::NuccChunkAnmStrm => Box::new(NuccAnmStrm::from(converter)),
+            NuccChunkType::NuccChunkAnmStrmFrame => Box::new(NuccAnmStrmFrame::from(converter)),
             NuccChunkType::NuccChunkBinary => Box::new(NuccBinary::from(converter)),
+            NuccChunkType::NuccChunkDynamics => Box::new(NuccDynamics::try_from(converter)?),
+            NuccChunkType::NuccChunkModelHit => Box::new(NuccModelHit::try_from(converter)?),
+            NuccChunkType::NuccChunkBillboard => Box::new(NuccBillboard::try_from(converter)?),
+            NuccChunkType::NuccChunkTrail => Box::new(NuccTrail::try_from(converter)?),
+            NuccChunkType::NuccChunkParticle => Box::new(NuccParticle::try_from(converter)?),
+            NuccChunkType::NuccChunkSprite => Box::new(NuccSprite::try_from(converter)?),
+            NuccChunkType::NuccChunkSprite2 => Box::new(NuccSprite2::try_from(converter)?),
+            NuccChunkType::NuccChunkLayerSet => Box::new(NuccLayerSet::try_from(converter)?),
+            NuccChunkType::NuccChunkMorphModel => Box::new(NuccMorphModel::try_from(converter)?),
+            NuccChunkType::NuccChunkMorphPrimitive => Box::new(NuccMorphPrimitive::from(converter)),
+            NuccChunkType::NuccChunkModelPrimitiveBatch => {
+                Box::new(NuccModelPrimitiveBatch::try_from(converter)?)
+            }
+            NuccChunkType::NuccChunkPrimitiveVertex => {
+                Box::new(NuccPrimitiveVertex::from(converter))
+            }
+            NuccChunkType::NuccChunkNub => Box::new(NuccNub::try_from(converter)?),
+            NuccChunkType::NuccChunkFont => Box::new(NuccFont::try_from(converter)?),
+            NuccChunkType::NuccChunkLensFlare => Box::new(NuccLensFlare::try_from(converter)?),
             NuccChunkType::NuccChunkUnknown => Box::new(NuccUnknown::from(converter)),
             any => panic!("Unexpected NuccChunkType: {any}"),
-        }
+        })
     }
 }
 
-impl<'a> From<NuccChunkConverter<'a>> for Box<dyn NuccChunk> {
-    fn from(converter: NuccChunkConverter) -> Self {
-        match converter.0.chunk_type() {
+impl<'a> TryFrom<NuccChunkConverter<'a>> for Box<dyn NuccChunk> {
+    type Error = NuccError;
+
+    fn try_from(converter: NuccChunkConverter) -> Result<Self, Self::Error> {
+        Ok(match converter.0.chunk_type() {
             NuccChunkType::NuccChunkAnm => {
-                Box::<NuccChunkAnm>::from(converter) as Box<dyn NuccChunk>
+                Box::<NuccChunkAnm>::try_from(converter)? as Box<dyn NuccChunk>
+            }
+            NuccChunkType::NuccChunkAnmStrm => {
+                Box::<NuccChunkAnmStrm>::try_from(converter)? as Box<dyn NuccChunk>
+            }
+            NuccChunkType::NuccChunkAnmStrmFrame => {
+                Box::<NuccChunkAnmStrmFrame>::try_from(converter)? as Box<dyn NuccChunk>
             }
             NuccChunkType::NuccChunkBinary => {
                 Box::<NuccChunkBinary>::from(converter) as Box<dyn NuccChunk>
             }
+            NuccChunkType::NuccChunkDynamics => {
+                Box::<NuccChunkDynamics>::from(converter) as Box<dyn NuccChunk>
+            }
+            NuccChunkType::NuccChunkModelHit => {
+                Box::<NuccChunkModelHit>::from(converter) as Box<dyn NuccChunk>
+            }
+            NuccChunkType::NuccChunkBillboard => {
+                Box::<NuccChunkBillboard>::from(converter) as Box<dyn NuccChunk>
+            }
+            NuccChunkType::NuccChunkTrail => {
+                Box::<NuccChunkTrail>::from(converter) as Box<dyn NuccChunk>
+            }
+            NuccChunkType::NuccChunkParticle => {
+                Box::<NuccChunkParticle>::from(converter) as Box<dyn NuccChunk>
+            }
+            NuccChunkType::NuccChunkSprite => {
+                Box::<NuccChunkSprite>::from(converter) as Box<dyn NuccChunk>
+            }
+            NuccChunkType::NuccChunkSprite2 => {
+                Box::<NuccChunkSprite2>::from(converter) as Box<dyn NuccChunk>
+            }
+            NuccChunkType::NuccChunkLayerSet => {
+                Box::<NuccChunkLayerSet>::from(converter) as Box<dyn NuccChunk>
+            }
+            NuccChunkType::NuccChunkMorphModel => {
+                Box::<NuccChunkMorphModel>::from(converter) as Box<dyn NuccChunk>
+            }
+            NuccChunkType::NuccChunkMorphPrimitive => {
+                Box::<NuccChunkMorphPrimitive>::from(converter) as Box<dyn NuccChunk>
+            }
+            NuccChunkType::NuccChunkModelPrimitiveBatch => {
+                Box::<NuccChunkModelPrimitiveBatch>::from(converter) as Box<dyn NuccChunk>
+            }
+            NuccChunkType::NuccChunkPrimitiveVertex => {
+                Box::<NuccChunkPrimitiveVertex>::from(converter) as Box<dyn NuccChunk>
+            }
+            NuccChunkType::NuccChunkNub => {
+                Box::<NuccChunkNub>::from(converter) as Box<dyn NuccChunk>
+            }
+            NuccChunkType::NuccChunkFont => {
+                Box::<NuccChunkFont>::from(converter) as Box<dyn NuccChunk>
+            }
+            NuccChunkType::NuccChunkLensFlare => {
+                Box::<NuccChunkLensFlare>::from(converter) as Box<dyn NuccChunk>
+            }
             NuccChunkType::NuccChunkUnknown => {
                 Box::<NuccChunkUnknown>::from(converter) as Box<dyn NuccChunk>
             }
             any => panic!("Unexpected NuccChunkType: {any}"),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use deku::bitvec::*;
+    use deku::ctx::Endian;
+
+    use super::*;
+
+    /// Big endian chunk data, written field by field.
+    #[derive(Default)]
+    struct Data(Vec<u8>);
+
+    impl Data {
+        fn bytes(mut self, value: &[u8]) -> Self {
+            self.0.extend_from_slice(value);
+            self
+        }
+
+        fn u16(self, value: u16) -> Self {
+            self.bytes(&value.to_be_bytes())
+        }
+
+        fn u32(self, value: u32) -> Self {
+            self.bytes(&value.to_be_bytes())
+        }
+
+        fn i32(self, value: i32) -> Self {
+            self.bytes(&value.to_be_bytes())
         }
+
+        fn f32s(self, values: &[f32]) -> Self {
+            values
+                .iter()
+                .fold(self, |data, value| data.bytes(&value.to_be_bytes()))
+        }
+    }
+
+    fn struct_infos() -> Vec<NuccStructInfo> {
+        (0..3)
+            .map(|i| NuccStructInfo {
+                chunk_name: format!("chunk{i}"),
+                file_path: "c/test.max".to_string(),
+                chunk_type: "nuccChunkTexture".to_string(),
+            })
+            .collect()
+    }
+
+    fn struct_references() -> Vec<NuccStructReference> {
+        (0..3)
+            .map(|i| NuccStructReference(format!("coord{i}"), struct_infos()[0].clone()))
+            .collect()
+    }
+
+    fn read_struct(
+        chunk_type: NuccChunkType,
+        data: &Data,
+    ) -> Result<Box<dyn NuccStruct>, NuccError> {
+        let (_, chunk) = NuccChunkType::read_struct(
+            data.0.view_bits(),
+            &chunk_type.to_string(),
+            Endian::Big,
+            0x79,
+        )
+        .unwrap();
+
+        // Data that doesn't match the layout would be kept as an unknown chunk
+        assert_eq!(chunk.chunk_type(), chunk_type);
+
+        Box::<dyn NuccStruct>::try_from(NuccStructConverter(
+            chunk,
+            &struct_infos(),
+            &struct_references(),
+        ))
+    }
+
+    /// The page indices in the data have to be in the order the converter adds them to the page.
+    fn round_trip(chunk_type: NuccChunkType, data: Data) {
+        let nucc_struct = read_struct(chunk_type, &data).unwrap();
+
+        let mut page_struct_infos = HashMap::new();
+        let mut page_struct_references = HashMap::new();
+        let chunk = Box::<dyn NuccChunk>::try_from(NuccChunkConverter(
+            nucc_struct,
+            &mut page_struct_infos,
+            &mut page_struct_references,
+        ))
+        .unwrap();
+
+        let output = NuccChunkType::write_struct(chunk, Endian::Big, 0x79).unwrap();
+        assert_eq!(output.into_vec(), data.0);
+    }
+
+    // These layouts weren't checked against retail files, so the data below is made up and
+    // only tests that reading and writing a chunk doesn't change it
+
+    #[test]
+    fn dynamics_round_trip() {
+        #[rustfmt::skip]
+        let data = Data::default()
+            .u16(2) // spring_group_count
+            .u16(1) // collision_sphere_count
+            .u32(0) // clump_index
+            // spring_groups
+            .f32s(&[0.5, 1.0, 2.0, 0.25]).u16(0).u16(3).u32(1)
+            .f32s(&[0.25, 0.5, 1.0, 0.0]).u16(1).u16(2).u32(0)
+            // collision_spheres
+            .f32s(&[0.0, 1.0, 0.0]).f32s(&[2.0, 2.0, 2.0]).u16(1).u16(1).u16(2).u16(0)
+            // attached_group_indices
+            .u16(0).u16(1);
+
+        round_trip(NuccChunkType::NuccChunkDynamics, data);
+    }
+
+    #[test]
+    fn model_hit_round_trip() {
+        #[rustfmt::skip]
+        let data = Data::default()
+            .u32(2) // section_count
+            .u32(9) // total_vertex_count
+            // sections
+            .u32(3).bytes(&[1, 2, 3, 4]).u32(0)
+            .u32(6).bytes(&[0, 0, 0, 0x10]).u32(1)
+            // vertices
+            .f32s(&[0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0])
+            .f32s(&[0.0, 0.0, 1.0, 1.0, 0.0, 1.0, 0.0, 1.0, 1.0])
+            .f32s(&[2.0, 0.0, 0.0, 2.0, 1.0, 0.0, 2.0, 0.0, 1.0]);
+
+        round_trip(NuccChunkType::NuccChunkModelHit, data);
+    }
+
+    #[test]
+    fn model_hit_partial_triangle() {
+        #[rustfmt::skip]
+        let data = Data::default()
+            .u32(1) // section_count
+            .u32(2) // total_vertex_count
+            .u32(2).bytes(&[1, 2, 3, 4]).u32(0)
+            .f32s(&[0.0, 0.0, 0.0, 1.0, 0.0, 0.0]);
+
+        assert!(read_struct(NuccChunkType::NuccChunkModelHit, &data).is_err());
+    }
+
+    #[test]
+    fn model_hit_vertex_count_mismatch() {
+        #[rustfmt::skip]
+        let data = Data::default()
+            .u32(1) // section_count
+            .u32(6) // total_vertex_count
+            .u32(3).bytes(&[1, 2, 3, 4]).u32(0)
+            .f32s(&[0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0])
+            .f32s(&[0.0, 0.0, 1.0, 1.0, 0.0, 1.0, 0.0, 1.0, 1.0]);
+
+        assert!(read_struct(NuccChunkType::NuccChunkModelHit, &data).is_err());
+    }
+
+    #[test]
+    fn billboard_round_trip() {
+        #[rustfmt::skip]
+        let data = Data::default()
+            .u32(0) // material_index
+            .u32(0) // coord_index
+            .f32s(&[1.0, 2.0]) // width, height
+            .f32s(&[0.0, 0.5, 0.0]) // offset
+            .f32s(&[45.0]) // rotation
+            .f32s(&[1.0, 0.5, 0.25, 1.0]) // color
+            .u32(1); // flags
+
+        round_trip(NuccChunkType::NuccChunkBillboard, data);
+    }
+
+    #[test]
+    fn trail_round_trip() {
+        #[rustfmt::skip]
+        let data = Data::default()
+            .u32(0) // material_index
+            .u32(0) // start_coord_index
+            .u32(1) // end_coord_index
+            .u32(8) // segment_count
+            .f32s(&[0.5, 1.0, 0.0]) // lifetime, start_width, end_width
+            .f32s(&[1.0, 1.0, 1.0, 1.0]) // start_color
+            .f32s(&[1.0, 0.5, 0.0, 0.0]) // end_color
+            .u32(0); // flags
+
+        round_trip(NuccChunkType::NuccChunkTrail, data);
+    }
+
+    #[test]
+    fn particle_round_trip() {
+        #[rustfmt::skip]
+        let data = Data::default()
+            .u32(0) // material_index
+            .u32(0) // coord_index
+            .u32(100) // max_particles
+            .f32s(&[10.0, 1.0]) // emission_rate, emission_duration
+            .f32s(&[2.0, 0.5, 3.0, 1.0]) // lifetime, lifetime_variance, speed, speed_variance
+            .f32s(&[30.0]) // spread_angle
+            .f32s(&[0.0, 1.0, 0.0]) // direction
+            .f32s(&[0.0, -9.75, 0.0]) // gravity
+            .f32s(&[1.0, 0.5]) // start_size, end_size
+            .f32s(&[1.0, 1.0, 1.0, 1.0]) // start_color
+            .f32s(&[1.0, 1.0, 1.0, 0.0]) // end_color
+            .u32(2); // flags
+
+        round_trip(NuccChunkType::NuccChunkParticle, data);
+    }
+
+    #[test]
+    fn sprite_round_trip() {
+        #[rustfmt::skip]
+        let data = Data::default()
+            .u32(0) // texture_index
+            .f32s(&[0.0, 0.0, 64.0, 32.0]) // rect
+            .f32s(&[0.0, 0.0, 0.5, 0.25]) // uv
+            .f32s(&[1.0, 1.0, 1.0, 1.0]) // color
+            .u32(0); // flags
+
+        round_trip(NuccChunkType::NuccChunkSprite, data);
+    }
+
+    #[test]
+    fn sprite2_round_trip() {
+        #[rustfmt::skip]
+        let data = Data::default()
+            .u16(2) // texture_count
+            .u16(2) // frame_count
+            .u32(0).u32(1) // texture_indices
+            // frames
+            .u16(0).u16(0).f32s(&[0.0, 0.0, 64.0, 64.0]).f32s(&[0.0, 0.0, 0.5, 0.5])
+            .u16(1).u16(0).f32s(&[0.0, 0.0, 32.0, 32.0]).f32s(&[0.5, 0.5, 1.0, 1.0])
+            .f32s(&[1.0, 1.0, 1.0, 1.0]) // color
+            .u32(1); // flags
+
+        round_trip(NuccChunkType::NuccChunkSprite2, data);
+    }
+
+    #[test]
+    fn layer_set_round_trip() {
+        #[rustfmt::skip]
+        let data = Data::default()
+            .u32(1280) // width
+            .u32(720) // height
+            .u32(2) // layer_count
+            // layers
+            .bytes(b"root\0").i32(-1).i32(-1)
+            .f32s(&[0.0, 0.0, 1.0, 1.0, 0.0]).f32s(&[1.0, 1.0, 1.0, 1.0]).u32(1)
+            .bytes(b"icon\0").i32(0).i32(0)
+            .f32s(&[16.0, 8.0, 0.5, 0.5, 90.0]).f32s(&[1.0, 0.0, 0.0, 0.5]).u32(0);
+
+        round_trip(NuccChunkType::NuccChunkLayerSet, data);
+    }
+
+    #[test]
+    fn morph_model_round_trip() {
+        #[rustfmt::skip]
+        let data = Data::default()
+            .u32(0) // model_index
+            .u16(2) // primitive_count
+            .u16(2) // target_count
+            .u32(1).u32(2) // primitive_indices
+            .bytes(b"smile\0").bytes(b"blink\0"); // target_names
+
+        round_trip(NuccChunkType::NuccChunkMorphModel, data);
+    }
+
+    #[test]
+    fn morph_primitive_round_trip() {
+        #[rustfmt::skip]
+        let data = Data::default()
+            .u32(4) // vertex_count
+            .u32(1) // target_count
+            .u32(2) // delta_count
+            // deltas
+            .u32(0).f32s(&[0.0, 0.25, 0.0]).f32s(&[0.0, 0.0, 1.0])
+            .u32(3).f32s(&[0.5, 0.0, 0.0]).f32s(&[1.0, 0.0, 0.0]);
+
+        round_trip(NuccChunkType::NuccChunkMorphPrimitive, data);
+    }
+
+    #[test]
+    fn model_primitive_batch_round_trip() {
+        #[rustfmt::skip]
+        let data = Data::default()
+            .u32(0) // vertex_buffer_index
+            .u32(1) // material_index
+            .u32(2) // primitive_count
+            // primitives
+            .u16(0).u16(0).u32(0).u32(6)
+            .u16(1).u16(0).u32(6).u32(4);
+
+        round_trip(NuccChunkType::NuccChunkModelPrimitiveBatch, data);
+    }
+
+    #[test]
+    fn primitive_vertex_round_trip() {
+        #[rustfmt::skip]
+        let data = Data::default()
+            .u32(3) // vertex_count
+            .u32(3) // index_count
+            // vertices
+            .f32s(&[0.0, 0.0, 0.0, 0.0, 0.0, 1.0]).bytes(&[255, 255, 255, 255]).f32s(&[0.0, 0.0])
+            .f32s(&[1.0, 0.0, 0.0, 0.0, 0.0, 1.0]).bytes(&[255, 0, 0, 255]).f32s(&[1.0, 0.0])
+            .f32s(&[0.0, 1.0, 0.0, 0.0, 0.0, 1.0]).bytes(&[0, 255, 0, 128]).f32s(&[0.0, 1.0])
+            .u16(0).u16(1).u16(2); // indices
+
+        round_trip(NuccChunkType::NuccChunkPrimitiveVertex, data);
+    }
+
+    #[test]
+    fn nub_round_trip() {
+        #[rustfmt::skip]
+        let data = Data::default()
+            .u32(0) // clump_index
+            .u16(0) // coord_index
+            .u16(0) // unk
+            .f32s(&[0.0, 10.0, 0.0]) // position
+            .f32s(&[0.0, 90.0, 0.0]) // rotation
+            .f32s(&[1.0, 1.0, 1.0]); // scale
+
+        round_trip(NuccChunkType::NuccChunkNub, data);
+    }
+
+    #[test]
+    fn font_round_trip() {
+        #[rustfmt::skip]
+        let data = Data::default()
+            .u16(32) // line_height
+            .u16(26) // base
+            .u16(256) // texture_width
+            .u16(256) // texture_height
+            .u16(1) // texture_count
+            .u16(0) // unk
+            .u32(2) // glyph_count
+            .u32(0) // texture_indices
+            // glyphs
+            .u32(0x41).u16(0).u16(0).f32s(&[0.0, 0.0, 0.0625, 0.125]).u16(0).u16(2).u16(16).u16(0)
+            .u32(0x42).u16(0).u16(0).f32s(&[0.0625, 0.0, 0.125, 0.125]).u16(1).u16(2).u16(15).u16(0);
+
+        round_trip(NuccChunkType::NuccChunkFont, data);
+    }
+
+    #[test]
+    fn lens_flare_round_trip() {
+        #[rustfmt::skip]
+        let data = Data::default()
+            .u32(0) // light_index
+            .f32s(&[1.0, 100.0]) // intensity, fade_distance
+            .u32(0) // flags
+            .u16(1) // texture_count
+            .u16(2) // element_count
+            .u32(1) // texture_indices
+            // elements
+            .u16(0).u16(0).f32s(&[0.0, 1.0, 0.0]).f32s(&[1.0, 1.0, 1.0, 1.0])
+            .u16(0).u16(0).f32s(&[0.5, 0.25, 45.0]).f32s(&[1.0, 0.5, 0.0, 0.5]);
+
+        round_trip(NuccChunkType::NuccChunkLensFlare, data);
     }
 }
@@ -3,6 +3,7 @@ use std::slice::{Iter, IterMut};
 use super::{entry::Entry, EntryIter, NuccStructInfo, NuccStructReference};
 
 #[allow(dead_code)]
+#[derive(Clone)]
 pub struct Clump {
     pub clump_info: ClumpInfo,
 
@@ -44,6 +45,18 @@ impl Clump {
         }
     }
 
+    pub fn entry_struct_refs(&self) -> &[NuccStructReference] {
+        &self.entry_struct_refs
+    }
+
+    pub fn model_struct_refs(&self) -> &[NuccStructReference] {
+        &self.model_struct_refs
+    }
+
+    pub fn other_entry_struct_infos(&self) -> &[NuccStructInfo] {
+        &self.other_entry_struct_infos
+    }
+
     pub fn iter(&self) -> Iter<Entry> {
         self.root_entries.iter()
     }
@@ -61,6 +74,7 @@ impl Clump {
     // }
 }
 
+#[derive(Clone, PartialEq, Eq)]
 pub enum ClumpInfo {
     NoInfo,
     StructRef(NuccStructReference),
@@ -1,35 +1,37 @@
 use deku::bitvec::BitView;
+use deku::ctx::Endian;
 use deku::prelude::*;
 
 use crate::error::NuccError;
+use crate::utils::DekuBitVec;
 
 use crate::nucc_chunk::{Curve as ChunkCurve, CurveFormat, CurveHeader};
 
-#[derive(Debug, DekuRead, DekuWrite)]
+#[derive(Debug, Clone, Copy, PartialEq, DekuRead, DekuWrite)]
 #[deku(endian = "endian", ctx = "endian: deku::ctx::Endian")]
-pub struct Vector2(f32, f32);
+pub struct Vector2(pub f32, pub f32);
 
-#[derive(Debug, DekuRead, DekuWrite)]
+#[derive(Debug, Clone, Copy, PartialEq, DekuRead, DekuWrite)]
 #[deku(endian = "endian", ctx = "endian: deku::ctx::Endian")]
-pub struct Vector3(f32, f32, f32);
+pub struct Vector3(pub f32, pub f32, pub f32);
 
-#[derive(Debug, DekuRead, DekuWrite)]
+#[derive(Debug, Clone, Copy, PartialEq, DekuRead, DekuWrite)]
 #[deku(endian = "endian", ctx = "endian: deku::ctx::Endian")]
-pub struct Vector3Short(i16, i16, i16);
+pub struct Vector3Short(pub i16, pub i16, pub i16);
 
-#[derive(Debug, DekuRead, DekuWrite)]
+#[derive(Debug, Clone, Copy, PartialEq, DekuRead, DekuWrite)]
 #[deku(endian = "endian", ctx = "endian: deku::ctx::Endian")]
-pub struct Quaternion(f32, f32, f32, f32);
+pub struct Quaternion(pub f32, pub f32, pub f32, pub f32);
 
-#[derive(Debug, DekuRead, DekuWrite)]
+#[derive(Debug, Clone, Copy, PartialEq, DekuRead, DekuWrite)]
 #[deku(endian = "endian", ctx = "endian: deku::ctx::Endian")]
-pub struct QuaternionShort(i16, i16, i16, i16);
+pub struct QuaternionShort(pub i16, pub i16, pub i16, pub i16);
 
-#[derive(Debug, DekuRead, DekuWrite)]
+#[derive(Debug, Clone, Copy, PartialEq, DekuRead, DekuWrite)]
 #[deku(endian = "endian", ctx = "endian: deku::ctx::Endian")]
-pub struct RGB(u8, u8, u8);
+pub struct RGB(pub u8, pub u8, pub u8);
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Keyframes {
     None,
     Float(Vec<f32>),
@@ -57,6 +59,8 @@ impl Keyframes {
             Keyframes::Vector3(frames) => frames.len(),
             Keyframes::Vector3Short(frames) => frames.len(),
             Keyframes::Vector3Linear(frames) => frames.len(),
+            Keyframes::Vector3ShortLinear(frames) => frames.len(),
+            Keyframes::Quaternion(frames) => frames.len(),
             Keyframes::QuaternionShort(frames) => frames.len(),
             Keyframes::QuaternionLinear(frames) => frames.len(),
             Keyframes::RGB(frames) => frames.len(),
@@ -66,11 +70,15 @@ impl Keyframes {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Curve {
+    pub curve_index: u16,
     pub channel: Channel,
     pub interp_type: InterpolationType,
     keyframes: Keyframes,
+
+    /// Format the curve was read with, which is kept when writing if it still fits the keyframes.
+    curve_format: Option<CurveFormat>,
 }
 
 impl Curve {
@@ -101,6 +109,7 @@ impl Curve {
                 Keyframes::Vector3(_)
                 | Keyframes::Vector3Short(_)
                 | Keyframes::Vector3Linear(_)
+                | Keyframes::Quaternion(_)
                 | Keyframes::QuaternionShort(_)
                 | Keyframes::QuaternionLinear(_) => set_frames(keyframes),
                 _ => err,
@@ -122,16 +131,61 @@ impl Curve {
         }
     }
 
-    pub fn new(channel: Channel, interp_type: InterpolationType, keyframes: Keyframes) -> Self {
+    pub fn new(
+        curve_index: u16,
+        channel: Channel,
+        interp_type: InterpolationType,
+        keyframes: Keyframes,
+    ) -> Self {
         let mut curve = Self {
+            curve_index,
             channel,
             interp_type,
             keyframes: Keyframes::None,
+            curve_format: None,
         };
 
         curve.set_keyframes(keyframes).unwrap();
         curve
     }
+
+    /// Returns the curve with only its value at `frame`, keeping the curve's format if it can still hold the value.
+    pub(super) fn sample(&self, frame: u32, frame_size: u32) -> Curve {
+        Curve {
+            keyframes: self.keyframes.sample(frame, frame_size),
+            ..self.clone()
+        }
+    }
+
+    /// Removes the keyframes, but keeps the format they would be written with.
+    /// Header curves of streamed animations have no keyframes, and only define the format of the frames' curves.
+    pub(super) fn clear_frames(&mut self) {
+        if let Ok((header, _)) = <(CurveHeader, ChunkCurve)>::try_from(self.clone()) {
+            self.curve_format = Some(header.curve_format);
+        }
+
+        self.keyframes = without_frames(&self.keyframes);
+    }
+}
+
+/// Returns empty keyframes of the same type.
+fn without_frames(keyframes: &Keyframes) -> Keyframes {
+    match keyframes {
+        Keyframes::None => Keyframes::None,
+        Keyframes::Float(_) => Keyframes::Float(vec![]),
+        Keyframes::FloatLinear(_) => Keyframes::FloatLinear(vec![]),
+        Keyframes::Vector2(_) => Keyframes::Vector2(vec![]),
+        Keyframes::Vector2Linear(_) => Keyframes::Vector2Linear(vec![]),
+        Keyframes::Vector3(_) => Keyframes::Vector3(vec![]),
+        Keyframes::Vector3Short(_) => Keyframes::Vector3Short(vec![]),
+        Keyframes::Vector3Linear(_) => Keyframes::Vector3Linear(vec![]),
+        Keyframes::Vector3ShortLinear(_) => Keyframes::Vector3ShortLinear(vec![]),
+        Keyframes::Quaternion(_) => Keyframes::Quaternion(vec![]),
+        Keyframes::QuaternionShort(_) => Keyframes::QuaternionShort(vec![]),
+        Keyframes::QuaternionLinear(_) => Keyframes::QuaternionLinear(vec![]),
+        Keyframes::RGB(_) => Keyframes::RGB(vec![]),
+        Keyframes::Opacity(_) => Keyframes::Opacity(vec![]),
+    }
 }
 
 pub struct CurveChunkConverter(pub Channel, pub CurveHeader, pub ChunkCurve);
@@ -140,35 +194,9 @@ impl From<CurveChunkConverter> for Curve {
     fn from(converter: CurveChunkConverter) -> Self {
         let CurveChunkConverter(channel, header, chunk) = converter;
 
-        let interp_type = match header.curve_format {
-            CurveFormat::Vector3Fixed
-            | CurveFormat::EulerXYZFixed
-            | CurveFormat::FloatFixed
-            | CurveFormat::Vector2Fixed => InterpolationType::None,
-
-            CurveFormat::OpacityShortTable
-            | CurveFormat::ScaleShortTable
-            | CurveFormat::QuaternionShortTable
-            | CurveFormat::ColorRGBTable
-            | CurveFormat::Vector3Table
-            | CurveFormat::FloatTable
-            | CurveFormat::QuaternionTable => InterpolationType::None,
-
-            CurveFormat::FloatTableNoInterp
-            | CurveFormat::Vector3TableNoInterp
-            | CurveFormat::QuaternionShortTableNoInterp
-            | CurveFormat::OpacityShortTableNoInterp => InterpolationType::None,
-
-            CurveFormat::Vector3Linear
-            | CurveFormat::QuatnerionLinear
-            | CurveFormat::FloatLinear
-            | CurveFormat::Vector2Linear
-            | CurveFormat::Vector3ShortLinear => InterpolationType::Linear,
-
-            CurveFormat::Vector3Bezier => InterpolationType::Bezier,
-            CurveFormat::EulerInterpolated => todo!(),
-        };
+        let interp_type = interpolation(&header.curve_format);
 
+        // Chunk curve data is always kept in big endian (see ChunkCurve::swap_endian)
         let endianness = deku::ctx::Endian::Big;
         let mut data = chunk.data.view_bits();
 
@@ -202,8 +230,11 @@ impl From<CurveChunkConverter> for Curve {
 
                 vec
             }),
-            CurveFormat::Vector3Bezier => todo!(),
-            CurveFormat::EulerInterpolated => todo!(),
+            CurveFormat::Vector3Bezier | CurveFormat::EulerInterpolated => {
+                unreachable!(
+                    "Curves with an unsupported format are rejected when the chunk is read."
+                )
+            }
             CurveFormat::QuatnerionLinear => Keyframes::QuaternionLinear({
                 let mut vec = vec![];
                 vec.reserve_exact(header.frame_count as usize);
@@ -340,7 +371,7 @@ impl From<CurveChunkConverter> for Curve {
                 let mut vec = vec![];
                 vec.reserve_exact(header.frame_count as usize);
                 for _ in 0..header.frame_count {
-                    let (rest, frame) = i32::read(data, ()).unwrap();
+                    let (rest, frame) = i32::read(data, endianness).unwrap();
                     data = rest;
                     let (rest, value) = Vector3Short::read(data, endianness).unwrap();
                     data = rest;
@@ -352,11 +383,198 @@ impl From<CurveChunkConverter> for Curve {
             }),
         };
 
-        Curve::new(channel, interp_type, keyframes)
+        let mut curve = Curve::new(header.curve_index, channel, interp_type, keyframes);
+        curve.curve_format = Some(header.curve_format);
+        curve
+    }
+}
+
+fn interpolation(curve_format: &CurveFormat) -> InterpolationType {
+    match curve_format {
+        CurveFormat::Vector3Fixed
+        | CurveFormat::EulerXYZFixed
+        | CurveFormat::FloatFixed
+        | CurveFormat::Vector2Fixed => InterpolationType::None,
+
+        CurveFormat::OpacityShortTable
+        | CurveFormat::ScaleShortTable
+        | CurveFormat::QuaternionShortTable
+        | CurveFormat::ColorRGBTable
+        | CurveFormat::Vector3Table
+        | CurveFormat::FloatTable
+        | CurveFormat::QuaternionTable => InterpolationType::None,
+
+        CurveFormat::FloatTableNoInterp
+        | CurveFormat::Vector3TableNoInterp
+        | CurveFormat::QuaternionShortTableNoInterp
+        | CurveFormat::OpacityShortTableNoInterp => InterpolationType::None,
+
+        CurveFormat::Vector3Linear
+        | CurveFormat::QuatnerionLinear
+        | CurveFormat::FloatLinear
+        | CurveFormat::Vector2Linear
+        | CurveFormat::Vector3ShortLinear => InterpolationType::Linear,
+
+        CurveFormat::Vector3Bezier => InterpolationType::Bezier,
+        CurveFormat::EulerInterpolated => InterpolationType::Linear,
+    }
+}
+
+/// Whether the keyframes can still be written with the format they were read with.
+/// Fixed formats only hold a single value, or none in the header curves of streamed animations.
+fn fits_format(curve_format: &CurveFormat, keyframes: &Keyframes) -> bool {
+    let single = keyframes.keyframe_count() <= 1;
+
+    match (curve_format, keyframes) {
+        (CurveFormat::FloatFixed, Keyframes::Float(_))
+        | (CurveFormat::Vector2Fixed, Keyframes::Vector2(_))
+        | (CurveFormat::Vector3Fixed | CurveFormat::EulerXYZFixed, Keyframes::Vector3(_)) => single,
+
+        (CurveFormat::FloatTable | CurveFormat::FloatTableNoInterp, Keyframes::Float(_))
+        | (CurveFormat::Vector3Table | CurveFormat::Vector3TableNoInterp, Keyframes::Vector3(_))
+        | (
+            CurveFormat::QuaternionShortTable | CurveFormat::QuaternionShortTableNoInterp,
+            Keyframes::QuaternionShort(_),
+        )
+        | (
+            CurveFormat::OpacityShortTable | CurveFormat::OpacityShortTableNoInterp,
+            Keyframes::Opacity(_),
+        ) => true,
+
+        _ => false,
+    }
+}
+
+fn write_frames<T: DekuWrite<Endian>>(frames: &[T], output: &mut DekuBitVec) {
+    for value in frames {
+        value.write(output, Endian::Big).unwrap();
+    }
+}
+
+fn write_linear_frames<T: DekuWrite<Endian>>(frames: &[(i32, T)], output: &mut DekuBitVec) {
+    for (frame, value) in frames {
+        frame.write(output, Endian::Big).unwrap();
+        value.write(output, Endian::Big).unwrap();
+    }
+}
+
+impl TryFrom<Curve> for (CurveHeader, ChunkCurve) {
+    type Error = NuccError;
+
+    fn try_from(curve: Curve) -> Result<Self, Self::Error> {
+        let interpolated = curve.interp_type != InterpolationType::None;
+        let mut output = DekuBitVec::new();
+
+        // Keep the format the curve was read with, unless its keyframes or interpolation were changed
+        let source_format = curve.curve_format.filter(|format| {
+            fits_format(format, &curve.keyframes) && interpolation(format) == curve.interp_type
+        });
+
+        let (curve_format, frame_count) = match &curve.keyframes {
+            Keyframes::None => (CurveFormat::FloatFixed, 0),
+            Keyframes::Float(frames) => {
+                write_frames(frames, &mut output);
+                (
+                    match (interpolated, frames.len()) {
+                        (_, 1) => CurveFormat::FloatFixed,
+                        (true, _) => CurveFormat::FloatTable,
+                        (false, _) => CurveFormat::FloatTableNoInterp,
+                    },
+                    frames.len(),
+                )
+            }
+            Keyframes::FloatLinear(frames) => {
+                write_linear_frames(frames, &mut output);
+                (CurveFormat::FloatLinear, frames.len())
+            }
+            Keyframes::Vector2(frames) => {
+                // There is no table format for Vector2, so animated values have to be linear keyframes
+                if frames.len() > 1 {
+                    return Err(NuccError::KeyframesMismatch);
+                }
+
+                write_frames(frames, &mut output);
+                (CurveFormat::Vector2Fixed, frames.len())
+            }
+            Keyframes::Vector2Linear(frames) => {
+                write_linear_frames(frames, &mut output);
+                (CurveFormat::Vector2Linear, frames.len())
+            }
+            Keyframes::Vector3(frames) => {
+                write_frames(frames, &mut output);
+                (
+                    match (&curve.channel, interpolated, frames.len()) {
+                        (Channel::Rotation, _, 1) => CurveFormat::EulerXYZFixed,
+                        (_, _, 1) => CurveFormat::Vector3Fixed,
+                        (_, true, _) => CurveFormat::Vector3Table,
+                        (_, false, _) => CurveFormat::Vector3TableNoInterp,
+                    },
+                    frames.len(),
+                )
+            }
+            Keyframes::Vector3Short(frames) => {
+                write_frames(frames, &mut output);
+                (CurveFormat::ScaleShortTable, frames.len())
+            }
+            Keyframes::Vector3Linear(frames) => {
+                write_linear_frames(frames, &mut output);
+                (CurveFormat::Vector3Linear, frames.len())
+            }
+            Keyframes::Vector3ShortLinear(frames) => {
+                write_linear_frames(frames, &mut output);
+                (CurveFormat::Vector3ShortLinear, frames.len())
+            }
+            Keyframes::Quaternion(frames) => {
+                write_frames(frames, &mut output);
+                (CurveFormat::QuaternionTable, frames.len())
+            }
+            Keyframes::QuaternionShort(frames) => {
+                write_frames(frames, &mut output);
+                (
+                    if interpolated {
+                        CurveFormat::QuaternionShortTable
+                    } else {
+                        CurveFormat::QuaternionShortTableNoInterp
+                    },
+                    frames.len(),
+                )
+            }
+            Keyframes::QuaternionLinear(frames) => {
+                write_linear_frames(frames, &mut output);
+                (CurveFormat::QuatnerionLinear, frames.len())
+            }
+            Keyframes::RGB(frames) => {
+                write_frames(frames, &mut output);
+                (CurveFormat::ColorRGBTable, frames.len())
+            }
+            Keyframes::Opacity(frames) => {
+                write_frames(frames, &mut output);
+                (
+                    if interpolated {
+                        CurveFormat::OpacityShortTable
+                    } else {
+                        CurveFormat::OpacityShortTableNoInterp
+                    },
+                    frames.len(),
+                )
+            }
+        };
+
+        let curve_format = source_format.unwrap_or(curve_format);
+
+        let mut data = output.into_vec();
+        if data.len() % 4 != 0 {
+            data.resize(data.len() + 4 - (data.len() % 4), 0);
+        }
+
+        Ok((
+            CurveHeader::new(curve.curve_index, curve_format, frame_count as u16),
+            ChunkCurve { data },
+        ))
     }
 }
 
-#[derive(Debug, Clone)] // Needed so we can use iter::repeat on Channel::Property for EntryFormat::Material
+#[derive(Debug, Clone, PartialEq, Eq)] // Needed so we can use iter::repeat on Channel::Property for EntryFormat::Material
 pub enum Channel {
     Location,
     Rotation,
@@ -367,7 +585,7 @@ pub enum Channel {
     Property,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum InterpolationType {
     None,
     Linear,
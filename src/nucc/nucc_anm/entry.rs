@@ -5,10 +5,16 @@ use std::{
     vec::IntoIter,
 };
 
-use super::{curve::Curve, Channel, CurveChunkConverter, NuccStructInfo, NuccStructReference};
-use crate::nucc_chunk::{Entry as ChunkEntry, EntryFormat as ChunkEntryFormat};
+use super::{
+    curve::Curve, Channel, CurveChunkConverter, Keyframes, NuccStructInfo, NuccStructReference,
+};
+use crate::error::NuccError;
+use crate::nucc_chunk::{
+    ClumpCoordIndex, Curve as ChunkCurve, CurveHeader, Entry as ChunkEntry,
+    EntryFormat as ChunkEntryFormat,
+};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Entry {
     pub entry_info: EntryInfo,
     pub entry_format: EntryFormat,
@@ -53,6 +59,40 @@ impl From<ChunkEntry> for Entry {
     }
 }
 
+pub struct EntryChunkConverter(pub ClumpCoordIndex, pub EntryFormat, pub Vec<Curve>);
+
+impl TryFrom<EntryChunkConverter> for ChunkEntry {
+    type Error = NuccError;
+
+    fn try_from(converter: EntryChunkConverter) -> Result<Self, Self::Error> {
+        let EntryChunkConverter(coord_index, entry_format, curves) = converter;
+
+        let entry_format = match entry_format {
+            EntryFormat::Coord => ChunkEntryFormat::Coord,
+            EntryFormat::Camera => ChunkEntryFormat::Camera,
+            EntryFormat::Material => ChunkEntryFormat::Material,
+            EntryFormat::LightDirc => ChunkEntryFormat::LightDirc,
+            EntryFormat::LightPoint => ChunkEntryFormat::LightPoint,
+            EntryFormat::Ambient => ChunkEntryFormat::Ambient,
+        };
+
+        let (curve_headers, curves): (Vec<CurveHeader>, Vec<ChunkCurve>) = curves
+            .into_iter()
+            .filter(|c| !matches!(c.keyframes(), Keyframes::None))
+            .map(<(CurveHeader, ChunkCurve)>::try_from)
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .unzip();
+
+        Ok(ChunkEntry::new(
+            coord_index,
+            entry_format,
+            curve_headers,
+            curves,
+        ))
+    }
+}
+
 impl Default for Entry {
     fn default() -> Self {
         Self {
@@ -148,7 +188,7 @@ impl<'a> Iterator for EntryIter<'a> {
 //     }
 // }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum EntryFormat {
     Coord,
     Camera,
@@ -183,7 +223,7 @@ impl EntryFormat {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum EntryInfo {
     StructInfo(NuccStructInfo),
     StructRef(NuccStructReference),
@@ -0,0 +1,621 @@
+use std::{fs, path::Path, str::SplitWhitespace};
+
+use gltf::animation::{util::ReadOutputs, Interpolation};
+use hashbrown::HashMap;
+
+use crate::error::NuccError;
+use crate::nucc_chunk::NuccChunkType;
+
+use super::clump::Clump;
+use super::curve::*;
+use super::entry::{Entry, EntryFormat, EntryInfo};
+use super::retarget::quaternion_multiply;
+use super::{NuccAnm, NuccStructInfo, NuccStructReference};
+
+/// The clump imported animations are created for.
+pub struct ImportClump<'a> {
+    pub clump: NuccStructReference,
+
+    /// Coord references of the clump. Only nodes/joints with matching names are imported.
+    pub coords: &'a [NuccStructReference],
+    pub models: &'a [NuccStructReference],
+}
+
+pub struct ImportOptions {
+    /// Written as the animation's frame_size. Linear keyframes are stored in frame_size units per frame.
+    pub frame_size: u32,
+
+    /// Used for converting keyframe times in seconds to frames.
+    pub frame_rate: f32,
+
+    /// Multiplier for all imported locations.
+    pub location_scale: f32,
+
+    /// Store rotations that are sampled on every frame as short quaternions instead of floats.
+    pub short_rotations: bool,
+}
+
+impl Default for ImportOptions {
+    fn default() -> Self {
+        Self {
+            frame_size: 100,
+            frame_rate: 30.0,
+            location_scale: 1.0,
+            short_rotations: true,
+        }
+    }
+}
+
+/// Keyframes of a single channel, with times in frames.
+struct ImportedChannel<T> {
+    frames: Vec<(f32, T)>,
+    step: bool,
+}
+
+impl<T> ImportedChannel<T> {
+    fn fixed(value: T) -> Self {
+        Self {
+            frames: vec![(0.0, value)],
+            step: false,
+        }
+    }
+}
+
+struct ImportedCoord {
+    name: String,
+    parent: Option<String>,
+
+    location: ImportedChannel<Vector3>,
+    rotation: ImportedChannel<Quaternion>,
+    scale: ImportedChannel<Vector3>,
+}
+
+enum Layout {
+    Fixed,
+    Table,
+    Linear,
+}
+
+fn layout<T: PartialEq>(channel: &ImportedChannel<T>) -> Layout {
+    let frames = &channel.frames;
+
+    if frames.iter().all(|(_, v)| *v == frames[0].1) {
+        Layout::Fixed
+    } else if frames
+        .iter()
+        .enumerate()
+        .all(|(i, (f, _))| (f - i as f32).abs() < 0.001)
+    {
+        Layout::Table
+    } else if channel.step {
+        // Stepped keyframes can't be stored as linear keyframes, so they have to be baked
+        Layout::Table
+    } else {
+        Layout::Linear
+    }
+}
+
+/// Returns one value per frame. Values between keyframes are held, so this is only used for stepped keyframes.
+fn bake<T: Copy>(frames: &[(f32, T)]) -> Vec<T> {
+    let last_frame = frames[frames.len() - 1].0.round() as usize;
+
+    (0..=last_frame)
+        .map(|frame| {
+            frames
+                .iter()
+                .rev()
+                .find(|(f, _)| f.round() as usize <= frame)
+                .unwrap_or(&frames[0])
+                .1
+        })
+        .collect()
+}
+
+fn table_values<T: Copy>(channel: &ImportedChannel<T>) -> Vec<T> {
+    if channel.step {
+        bake(&channel.frames)
+    } else {
+        channel.frames.iter().map(|(_, v)| *v).collect()
+    }
+}
+
+fn linear_values<T: Copy>(channel: &ImportedChannel<T>, frame_size: u32) -> Vec<(i32, T)> {
+    channel
+        .frames
+        .iter()
+        .map(|(f, v)| ((f * frame_size as f32).round() as i32, *v))
+        .collect()
+}
+
+fn interp_type<T>(channel: &ImportedChannel<T>) -> InterpolationType {
+    if channel.step {
+        InterpolationType::None
+    } else {
+        InterpolationType::Linear
+    }
+}
+
+fn vector3_curve(
+    curve_index: u16,
+    channel_type: Channel,
+    channel: &ImportedChannel<Vector3>,
+    frame_size: u32,
+) -> Curve {
+    match layout(channel) {
+        Layout::Fixed => Curve::new(
+            curve_index,
+            channel_type,
+            InterpolationType::None,
+            Keyframes::Vector3(vec![channel.frames[0].1]),
+        ),
+        Layout::Table => Curve::new(
+            curve_index,
+            channel_type,
+            interp_type(channel),
+            Keyframes::Vector3(table_values(channel)),
+        ),
+        Layout::Linear => Curve::new(
+            curve_index,
+            channel_type,
+            InterpolationType::Linear,
+            Keyframes::Vector3Linear(linear_values(channel, frame_size)),
+        ),
+    }
+}
+
+fn rotation_curve(
+    curve_index: u16,
+    channel: &ImportedChannel<Quaternion>,
+    options: &ImportOptions,
+) -> Curve {
+    let to_short = |q: &Quaternion| {
+        let scale = i16::MAX as f32;
+        QuaternionShort(
+            (q.0 * scale).round() as i16,
+            (q.1 * scale).round() as i16,
+            (q.2 * scale).round() as i16,
+            (q.3 * scale).round() as i16,
+        )
+    };
+
+    let (interp, values) = match layout(channel) {
+        Layout::Fixed => (InterpolationType::Linear, vec![channel.frames[0].1]),
+        Layout::Table => (interp_type(channel), table_values(channel)),
+        Layout::Linear => {
+            return Curve::new(
+                curve_index,
+                Channel::Rotation,
+                InterpolationType::Linear,
+                Keyframes::QuaternionLinear(linear_values(channel, options.frame_size)),
+            )
+        }
+    };
+
+    let keyframes = if options.short_rotations {
+        Keyframes::QuaternionShort(values.iter().map(to_short).collect())
+    } else {
+        Keyframes::Quaternion(values)
+    };
+
+    Curve::new(curve_index, Channel::Rotation, interp, keyframes)
+}
+
+fn build_entries(
+    parent: Option<&String>,
+    children: &HashMap<Option<String>, Vec<String>>,
+    entries: &mut HashMap<String, Entry>,
+) -> Vec<Entry> {
+    let mut result = vec![];
+
+    for name in children.get(&parent.cloned()).into_iter().flatten() {
+        if let Some(mut entry) = entries.remove(name) {
+            entry.children = build_entries(Some(name), children, entries);
+            result.push(entry);
+        }
+    }
+
+    result
+}
+
+fn build_anm(
+    name: &str,
+    coords: Vec<ImportedCoord>,
+    target: &ImportClump,
+    options: &ImportOptions,
+) -> NuccAnm {
+    let frame_size = options.frame_size.max(1);
+
+    let last_frame = coords
+        .iter()
+        .flat_map(|c| {
+            c.location
+                .frames
+                .iter()
+                .map(|(f, _)| *f)
+                .chain(c.rotation.frames.iter().map(|(f, _)| *f))
+                .chain(c.scale.frames.iter().map(|(f, _)| *f))
+        })
+        .fold(0.0f32, f32::max);
+
+    let parents: HashMap<String, Option<String>> = coords
+        .iter()
+        .map(|c| (c.name.clone(), c.parent.clone()))
+        .collect();
+
+    let mut entries = HashMap::new();
+    let mut children: HashMap<Option<String>, Vec<String>> = HashMap::new();
+
+    for coord in coords {
+        let coord_ref = match target.coords.iter().find(|r| r.0 == coord.name) {
+            Some(coord_ref) => coord_ref.clone(),
+            None => continue,
+        };
+
+        // Parent the entry to its closest ancestor that exists in the clump
+        let mut parent = coord.parent.clone();
+        while let Some(parent_name) = &parent {
+            if target.coords.iter().any(|r| r.0 == *parent_name) {
+                break;
+            }
+
+            parent = parents.get(parent_name).cloned().flatten();
+        }
+
+        children.entry(parent).or_default().push(coord.name.clone());
+
+        let curves = vec![
+            vector3_curve(0, Channel::Location, &coord.location, frame_size),
+            rotation_curve(1, &coord.rotation, options),
+            vector3_curve(2, Channel::Scale, &coord.scale, frame_size),
+            Curve::new(
+                3,
+                Channel::Opacity,
+                InterpolationType::None,
+                Keyframes::Float(vec![1.0]),
+            ),
+        ];
+
+        entries.insert(
+            coord.name,
+            Entry {
+                entry_info: EntryInfo::StructRef(coord_ref),
+                entry_format: EntryFormat::Coord,
+                curves,
+                children: Default::default(),
+            },
+        );
+    }
+
+    let root_entries = build_entries(None, &children, &mut entries);
+
+    NuccAnm {
+        struct_info: NuccStructInfo {
+            chunk_name: name.to_string(),
+            file_path: String::new(),
+            chunk_type: NuccChunkType::NuccChunkAnm.to_string(),
+        },
+        version: 0x79,
+        frame_count: (last_frame * frame_size as f32).round() as u32,
+        frame_size,
+        clumps: vec![Clump::new_clump(
+            target.clump.clone(),
+            root_entries,
+            target.coords.to_vec(),
+            target.models.to_vec(),
+        )],
+    }
+}
+
+struct BvhJoint {
+    name: String,
+    parent: Option<usize>,
+    offset: Vector3,
+    channels: Vec<String>,
+}
+
+fn next_f32(tokens: &mut SplitWhitespace) -> Option<f32> {
+    tokens.next()?.parse::<f32>().ok()
+}
+
+fn parse_bvh(text: &str, options: &ImportOptions) -> Option<Vec<ImportedCoord>> {
+    let mut tokens = text.split_whitespace();
+    let mut joints: Vec<BvhJoint> = vec![];
+    let mut stack: Vec<Option<usize>> = vec![];
+    let mut current = None;
+
+    loop {
+        match tokens.next()? {
+            "HIERARCHY" => (),
+            "ROOT" | "JOINT" => {
+                joints.push(BvhJoint {
+                    name: tokens.next()?.to_string(),
+                    parent: stack.last().copied().flatten(),
+                    offset: Vector3(0.0, 0.0, 0.0),
+                    channels: vec![],
+                });
+                current = Some(joints.len() - 1);
+            }
+            "End" => {
+                // End sites only have an offset
+                tokens.next()?;
+                current = None;
+            }
+            "{" => stack.push(current),
+            "}" => {
+                stack.pop()?;
+            }
+            "OFFSET" => {
+                let offset = Vector3(
+                    next_f32(&mut tokens)?,
+                    next_f32(&mut tokens)?,
+                    next_f32(&mut tokens)?,
+                );
+
+                if let Some(index) = current {
+                    joints[index].offset = offset;
+                }
+            }
+            "CHANNELS" => {
+                let count = tokens.next()?.parse::<usize>().ok()?;
+                let channels = (0..count)
+                    .map(|_| tokens.next().map(String::from))
+                    .collect::<Option<Vec<_>>>()?;
+
+                joints[current?].channels = channels;
+            }
+            "MOTION" => break,
+            _ => return None,
+        }
+    }
+
+    // Frames: <count>
+    tokens.next()?;
+    let frame_count = tokens.next()?.parse::<usize>().ok()?;
+
+    // Frame Time: <seconds>
+    tokens.next()?;
+    tokens.next()?;
+    let frame_time = next_f32(&mut tokens)?;
+
+    let scale_location = |v: Vector3| {
+        Vector3(
+            v.0 * options.location_scale,
+            v.1 * options.location_scale,
+            v.2 * options.location_scale,
+        )
+    };
+
+    let mut locations = vec![vec![]; joints.len()];
+    let mut rotations = vec![vec![]; joints.len()];
+
+    for i in 0..frame_count {
+        let frame = i as f32 * frame_time * options.frame_rate;
+
+        for (j, joint) in joints.iter().enumerate() {
+            let mut location = joint.offset;
+            let mut rotation = Quaternion(0.0, 0.0, 0.0, 1.0);
+
+            for channel in joint.channels.iter() {
+                let value = next_f32(&mut tokens)?;
+                let half_angle = value.to_radians() / 2.0;
+
+                // Rotations are applied in the same order as the channels
+                match channel.as_str() {
+                    "Xposition" => location.0 = value,
+                    "Yposition" => location.1 = value,
+                    "Zposition" => location.2 = value,
+                    "Xrotation" => {
+                        rotation = quaternion_multiply(
+                            &rotation,
+                            &Quaternion(half_angle.sin(), 0.0, 0.0, half_angle.cos()),
+                        )
+                    }
+                    "Yrotation" => {
+                        rotation = quaternion_multiply(
+                            &rotation,
+                            &Quaternion(0.0, half_angle.sin(), 0.0, half_angle.cos()),
+                        )
+                    }
+                    "Zrotation" => {
+                        rotation = quaternion_multiply(
+                            &rotation,
+                            &Quaternion(0.0, 0.0, half_angle.sin(), half_angle.cos()),
+                        )
+                    }
+                    _ => return None,
+                }
+            }
+
+            if joint.channels.iter().any(|c| c.ends_with("position")) {
+                locations[j].push((frame, scale_location(location)));
+            }
+
+            if joint.channels.iter().any(|c| c.ends_with("rotation")) {
+                rotations[j].push((frame, rotation));
+            }
+        }
+    }
+
+    let coords = joints
+        .iter()
+        .zip(locations.into_iter().zip(rotations))
+        .map(|(joint, (location, rotation))| ImportedCoord {
+            name: joint.name.clone(),
+            parent: joint.parent.map(|p| joints[p].name.clone()),
+            // Joints without motion channels keep their rest values
+            location: match location.is_empty() {
+                true => ImportedChannel::fixed(scale_location(joint.offset)),
+                false => ImportedChannel {
+                    frames: location,
+                    step: false,
+                },
+            },
+            rotation: match rotation.is_empty() {
+                true => ImportedChannel::fixed(Quaternion(0.0, 0.0, 0.0, 1.0)),
+                false => ImportedChannel {
+                    frames: rotation,
+                    step: false,
+                },
+            },
+            scale: ImportedChannel::fixed(Vector3(1.0, 1.0, 1.0)),
+        })
+        .collect();
+
+    Some(coords)
+}
+
+impl NuccAnm {
+    /// Imports an animation from a glTF file. If `animation_name` is None, the first animation is imported.
+    pub fn import_gltf(
+        file_path: &dyn AsRef<Path>,
+        animation_name: Option<&str>,
+        target: &ImportClump,
+        options: &ImportOptions,
+    ) -> Result<NuccAnm, NuccError> {
+        let (document, buffers, _) = gltf::import(file_path).map_err(|_| NuccError::InvalidGltf)?;
+
+        let animation = document
+            .animations()
+            .find(|a| animation_name.is_none() || a.name() == animation_name)
+            .ok_or(NuccError::AnimationNotFound)?;
+
+        let mut parents = HashMap::new();
+        for node in document.nodes() {
+            for child in node.children() {
+                parents.insert(child.index(), node.index());
+            }
+        }
+
+        let mut coords = vec![];
+        let mut coord_indices = HashMap::new();
+
+        for node in document.nodes() {
+            let name = match node.name() {
+                Some(name) => name.to_string(),
+                None => continue,
+            };
+
+            let (location, rotation, scale) = node.transform().decomposed();
+
+            coord_indices.insert(node.index(), coords.len());
+            coords.push(ImportedCoord {
+                name,
+                parent: parents
+                    .get(&node.index())
+                    .and_then(|p| document.nodes().nth(*p))
+                    .and_then(|p| p.name().map(String::from)),
+                location: ImportedChannel::fixed(Vector3(
+                    location[0] * options.location_scale,
+                    location[1] * options.location_scale,
+                    location[2] * options.location_scale,
+                )),
+                rotation: ImportedChannel::fixed(Quaternion(
+                    rotation[0],
+                    rotation[1],
+                    rotation[2],
+                    rotation[3],
+                )),
+                scale: ImportedChannel::fixed(Vector3(scale[0], scale[1], scale[2])),
+            });
+        }
+
+        for channel in animation.channels() {
+            let coord = match coord_indices.get(&channel.target().node().index()) {
+                Some(index) => &mut coords[*index],
+                None => continue,
+            };
+
+            let reader = channel.reader(|buffer| Some(&buffers[buffer.index()][..]));
+            let frames: Vec<f32> = match reader.read_inputs() {
+                Some(inputs) => inputs.map(|t| t * options.frame_rate).collect(),
+                None => continue,
+            };
+
+            let interpolation = channel.sampler().interpolation();
+            let step = matches!(interpolation, Interpolation::Step);
+
+            // Cubic spline outputs are stored as (in-tangent, value, out-tangent)
+            let stride = match interpolation {
+                Interpolation::CubicSpline => 3,
+                _ => 1,
+            };
+            let skip = stride / 2;
+
+            match reader.read_outputs() {
+                Some(ReadOutputs::Translations(values)) => {
+                    coord.location = ImportedChannel {
+                        frames: frames
+                            .iter()
+                            .copied()
+                            .zip(values.skip(skip).step_by(stride).map(|v| {
+                                Vector3(
+                                    v[0] * options.location_scale,
+                                    v[1] * options.location_scale,
+                                    v[2] * options.location_scale,
+                                )
+                            }))
+                            .collect(),
+                        step,
+                    };
+                }
+                Some(ReadOutputs::Rotations(values)) => {
+                    coord.rotation = ImportedChannel {
+                        frames: frames
+                            .iter()
+                            .copied()
+                            .zip(
+                                values
+                                    .into_f32()
+                                    .skip(skip)
+                                    .step_by(stride)
+                                    .map(|q| Quaternion(q[0], q[1], q[2], q[3])),
+                            )
+                            .collect(),
+                        step,
+                    };
+                }
+                Some(ReadOutputs::Scales(values)) => {
+                    coord.scale = ImportedChannel {
+                        frames: frames
+                            .iter()
+                            .copied()
+                            .zip(
+                                values
+                                    .skip(skip)
+                                    .step_by(stride)
+                                    .map(|v| Vector3(v[0], v[1], v[2])),
+                            )
+                            .collect(),
+                        step,
+                    };
+                }
+                _ => continue,
+            }
+        }
+
+        Ok(build_anm(
+            animation.name().unwrap_or_default(),
+            coords,
+            target,
+            options,
+        ))
+    }
+
+    /// Imports an animation from a BVH file. Joint rotations are converted to quaternions.
+    pub fn import_bvh(
+        file_path: &dyn AsRef<Path>,
+        target: &ImportClump,
+        options: &ImportOptions,
+    ) -> Result<NuccAnm, NuccError> {
+        let text = fs::read_to_string(file_path).map_err(|_| NuccError::IoError)?;
+        let coords = parse_bvh(&text, options).ok_or(NuccError::InvalidBvh)?;
+
+        let name = file_path
+            .as_ref()
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default();
+
+        Ok(build_anm(name, coords, target, options))
+    }
+}
@@ -1,6 +1,10 @@
 pub mod clump;
 pub mod curve;
 pub mod entry;
+pub mod import;
+pub mod retarget;
+mod stream;
+mod timing;
 
 use super::*;
 
@@ -11,10 +15,20 @@ use entry::*;
 use hashbrown::{HashMap, HashSet};
 use itertools::Itertools;
 
+use crate::error::NuccError;
+use crate::nucc_chunk::{Clump as ChunkClump, Entry as ChunkEntry};
+
+// Both glob imports export a Clump
+use clump::Clump;
+
+#[derive(Clone)]
 pub struct NuccAnm {
     pub struct_info: NuccStructInfo,
     pub version: u16,
 
+    pub frame_count: u32,
+    pub frame_size: u32,
+
     pub clumps: Vec<Clump>,
 }
 
@@ -123,8 +137,10 @@ impl<'a> From<NuccStructConverter<'a>> for NuccAnm {
 
             let clump_parents = &clump_parents_vec[i];
 
+            // Coord indices refer to clumps by their position in the chunk, not by their struct reference index.
+            // coord_parents is grouped the same way above, and the chunk converter writes positions too.
             process_coords(
-                clump.clump_index as i16,
+                i as i16,
                 &mut entries_map,
                 &entry_struct_refs,
                 clump_parents,
@@ -132,13 +148,7 @@ impl<'a> From<NuccStructConverter<'a>> for NuccAnm {
 
             let clump_keys: Vec<ClumpCoordIndex> = entries_map
                 .keys()
-                .filter_map(|k| {
-                    if k.0 == clump.clump_index as i16 {
-                        Some(*k)
-                    } else {
-                        None
-                    }
-                })
+                .filter_map(|k| if k.0 == i as i16 { Some(*k) } else { None })
                 .collect();
 
             let mut root_entries = vec![];
@@ -193,21 +203,114 @@ impl<'a> From<NuccStructConverter<'a>> for NuccAnm {
         Self {
             struct_info: Default::default(),
             version: chunk.version,
+            frame_count: chunk.frame_count,
+            frame_size: chunk.frame_size,
             clumps,
         }
     }
 }
 
-impl<'a> From<NuccChunkConverter<'a>> for Box<NuccChunkAnm> {
-    fn from(converter: NuccChunkConverter) -> Self {
-        let NuccChunkConverter(boxed, _, _) = converter;
+impl<'a> TryFrom<NuccChunkConverter<'a>> for Box<NuccChunkAnm> {
+    type Error = NuccError;
+
+    fn try_from(converter: NuccChunkConverter) -> Result<Self, Self::Error> {
+        fn add_entries(
+            entries: Vec<entry::Entry>,
+            parent: Option<ClumpCoordIndex>,
+            coord_index: &mut dyn FnMut(&EntryInfo) -> Result<ClumpCoordIndex, NuccError>,
+            chunk: &mut NuccChunkAnm,
+        ) -> Result<(), NuccError> {
+            for entry in entries {
+                let entry_coord = coord_index(&entry.entry_info)?;
+
+                if let Some(parent) = parent {
+                    chunk
+                        .coord_parents
+                        .push(ParentChildIndex(parent, entry_coord));
+                }
+
+                chunk.entries.push(ChunkEntry::try_from(EntryChunkConverter(
+                    entry_coord,
+                    entry.entry_format,
+                    entry.curves,
+                ))?);
+
+                add_entries(entry.children, Some(entry_coord), coord_index, chunk)?;
+            }
+
+            Ok(())
+        }
+
+        let NuccChunkConverter(boxed, struct_infos, struct_references) = converter;
         let anm = boxed.downcast::<NuccAnm>().map(|s| *s).ok().unwrap();
 
         let mut chunk = NuccChunkAnm::default();
         chunk.version = anm.version;
+        chunk.frame_count = anm.frame_count;
+        chunk.frame_size = anm.frame_size;
+
+        let mut other_entry_struct_infos = vec![];
+
+        for clump in anm.clumps {
+            match clump.clump_info.clone() {
+                ClumpInfo::StructRef(struct_ref) => {
+                    let clump_index = chunk.clumps.len() as i16;
+                    let mut entry_struct_refs = clump.entry_struct_refs().to_vec();
+                    let model_struct_refs = clump.model_struct_refs().to_vec();
+
+                    add_entries(
+                        clump.root_entries,
+                        None,
+                        &mut |entry_info: &EntryInfo| match entry_info {
+                            EntryInfo::StructRef(entry_struct_ref) => Ok(ClumpCoordIndex(
+                                clump_index,
+                                position_of(&mut entry_struct_refs, entry_struct_ref),
+                            )),
+                            // Clump entries must reference a chunk
+                            EntryInfo::StructInfo(_) => Err(NuccError::InvalidAnmEntry),
+                        },
+                        &mut chunk,
+                    )?;
+
+                    chunk.clumps.push(ChunkClump::new(
+                        index_of(struct_references, &struct_ref),
+                        entry_struct_refs
+                            .iter()
+                            .map(|r| index_of(struct_references, r))
+                            .collect(),
+                        model_struct_refs
+                            .iter()
+                            .map(|r| index_of(struct_references, r))
+                            .collect(),
+                    ));
+                }
+                ClumpInfo::NoInfo => {
+                    other_entry_struct_infos.extend(clump.other_entry_struct_infos().to_vec());
+
+                    add_entries(
+                        clump.root_entries,
+                        None,
+                        &mut |entry_info: &EntryInfo| match entry_info {
+                            EntryInfo::StructInfo(info) => Ok(ClumpCoordIndex(
+                                -1,
+                                position_of(&mut other_entry_struct_infos, info),
+                            )),
+                            // Entries without a clump must not reference a chunk
+                            EntryInfo::StructRef(_) => Err(NuccError::InvalidAnmEntry),
+                        },
+                        &mut chunk,
+                    )?;
+                }
+            }
+        }
+
+        chunk.other_entry_chunk_indices = other_entry_struct_infos
+            .iter()
+            .map(|info| index_of(struct_infos, info))
+            .collect();
 
         chunk.update().expect("Could not update Anm chunk.");
-        Box::new(chunk)
+        Ok(Box::new(chunk))
     }
 }
 
@@ -219,4 +322,8 @@ impl NuccStruct for NuccAnm {
     fn version(&self) -> u16 {
         self.version
     }
+
+    fn set_version(&mut self, version: u16) {
+        self.version = version;
+    }
 }
@@ -0,0 +1,361 @@
+use std::mem;
+
+use hashbrown::HashMap;
+
+use crate::error::NuccError;
+
+use super::clump::{Clump, ClumpInfo};
+use super::curve::*;
+use super::entry::{Entry, EntryFormat, EntryInfo};
+use super::{NuccAnm, NuccStructInfo, NuccStructReference};
+
+/// Rest pose of a coord, as stored in its nuccChunkCoord.
+#[derive(Debug, Clone, Copy)]
+pub struct RestPose {
+    pub location: Vector3,
+    pub rotation: Quaternion,
+}
+
+/// The clump an animation is retargeted to.
+pub struct RetargetClump<'a> {
+    pub clump: NuccStructReference,
+
+    /// Coord references of the target clump, in the same order as the clump's coord list.
+    pub coords: &'a [NuccStructReference],
+    pub models: &'a [NuccStructReference],
+
+    /// Maps source coord names to target coord names. Names missing from the map are used as is.
+    pub bone_map: &'a HashMap<String, String>,
+
+    /// Source and target rest poses by coord name. Only available when the coord chunks were parsed.
+    pub rest_poses: Option<(&'a HashMap<String, RestPose>, &'a HashMap<String, RestPose>)>,
+}
+
+pub(super) fn quaternion_multiply(a: &Quaternion, b: &Quaternion) -> Quaternion {
+    Quaternion(
+        a.3 * b.0 + a.0 * b.3 + a.1 * b.2 - a.2 * b.1,
+        a.3 * b.1 - a.0 * b.2 + a.1 * b.3 + a.2 * b.0,
+        a.3 * b.2 + a.0 * b.1 - a.1 * b.0 + a.2 * b.3,
+        a.3 * b.3 - a.0 * b.0 - a.1 * b.1 - a.2 * b.2,
+    )
+}
+
+pub(super) fn quaternion_conjugate(q: &Quaternion) -> Quaternion {
+    Quaternion(-q.0, -q.1, -q.2, q.3)
+}
+
+/// Converts Euler angles in degrees to a quaternion. The angles are applied around X, then Y, then Z,
+/// the same as the rotations of coord chunks.
+pub(super) fn euler_to_quaternion(v: &Vector3) -> Quaternion {
+    let axis_rotation = |angle: f32, axis: usize| {
+        let (sin, cos) = (angle.to_radians() / 2.0).sin_cos();
+        let mut q = Quaternion(0.0, 0.0, 0.0, cos);
+        match axis {
+            0 => q.0 = sin,
+            1 => q.1 = sin,
+            _ => q.2 = sin,
+        }
+
+        q
+    };
+
+    quaternion_multiply(
+        &axis_rotation(v.2, 2),
+        &quaternion_multiply(&axis_rotation(v.1, 1), &axis_rotation(v.0, 0)),
+    )
+}
+
+/// Converts a quaternion to Euler angles in degrees, in the same order as `euler_to_quaternion`.
+pub(super) fn quaternion_to_euler(q: &Quaternion) -> Vector3 {
+    let Quaternion(x, y, z, w) = *q;
+
+    Vector3(
+        (2.0 * (w * x + y * z))
+            .atan2(1.0 - 2.0 * (x * x + y * y))
+            .to_degrees(),
+        (2.0 * (w * y - z * x)).clamp(-1.0, 1.0).asin().to_degrees(),
+        (2.0 * (w * z + x * y))
+            .atan2(1.0 - 2.0 * (y * y + z * z))
+            .to_degrees(),
+    )
+}
+
+/// Corrects Euler rotations through quaternions. Each angle is kept within 180 degrees of the previous
+/// keyframe's, so that interpolating between them doesn't spin the other way around.
+fn correct_euler<'a>(
+    frames: impl Iterator<Item = &'a mut Vector3>,
+    correct_rotation: impl Fn(&Quaternion) -> Quaternion,
+) {
+    let closest = |angle: f32, previous: f32| angle - ((angle - previous) / 360.0).round() * 360.0;
+    let mut previous: Option<Vector3> = None;
+
+    for v in frames {
+        let mut corrected = quaternion_to_euler(&correct_rotation(&euler_to_quaternion(v)));
+        if let Some(previous) = previous {
+            corrected = Vector3(
+                closest(corrected.0, previous.0),
+                closest(corrected.1, previous.1),
+                closest(corrected.2, previous.2),
+            );
+        }
+
+        *v = corrected;
+        previous = Some(corrected);
+    }
+}
+
+fn correct_rest_pose(
+    curve: &mut Curve,
+    source: &RestPose,
+    target: &RestPose,
+) -> Result<(), NuccError> {
+    // Rotations are stored relative to the parent, so the source rest pose is replaced with the target's:
+    // target_rest * inverse(source_rest) * rotation
+    let correction = quaternion_multiply(&target.rotation, &quaternion_conjugate(&source.rotation));
+    let correct_rotation = |q: &Quaternion| quaternion_multiply(&correction, q);
+
+    let correct_location = |v: &Vector3| {
+        Vector3(
+            v.0 - source.location.0 + target.location.0,
+            v.1 - source.location.1 + target.location.1,
+            v.2 - source.location.2 + target.location.2,
+        )
+    };
+
+    match curve.channel {
+        Channel::Rotation => match curve.keyframes_mut() {
+            Keyframes::Quaternion(frames) => {
+                for q in frames.iter_mut() {
+                    *q = correct_rotation(q);
+                }
+            }
+            Keyframes::QuaternionLinear(frames) => {
+                for (_, q) in frames.iter_mut() {
+                    *q = correct_rotation(q);
+                }
+            }
+            Keyframes::QuaternionShort(frames) => {
+                let scale = i16::MAX as f32;
+
+                for q in frames.iter_mut() {
+                    let corrected = correct_rotation(&Quaternion(
+                        q.0 as f32 / scale,
+                        q.1 as f32 / scale,
+                        q.2 as f32 / scale,
+                        q.3 as f32 / scale,
+                    ));
+
+                    *q = QuaternionShort(
+                        (corrected.0 * scale).round() as i16,
+                        (corrected.1 * scale).round() as i16,
+                        (corrected.2 * scale).round() as i16,
+                        (corrected.3 * scale).round() as i16,
+                    );
+                }
+            }
+            Keyframes::Vector3(frames) => correct_euler(frames.iter_mut(), correct_rotation),
+            Keyframes::Vector3Linear(frames) => correct_euler(
+                frames.iter_mut().filter(|(f, _)| *f >= 0).map(|(_, v)| v),
+                correct_rotation,
+            ),
+            // The scale of short Euler rotations is unknown, so they can't be converted
+            Keyframes::Vector3Short(_) | Keyframes::Vector3ShortLinear(_) => {
+                return Err(NuccError::KeyframesMismatch)
+            }
+            _ => (),
+        },
+        Channel::Location => match curve.keyframes_mut() {
+            Keyframes::Vector3(frames) => {
+                for v in frames.iter_mut() {
+                    *v = correct_location(v);
+                }
+            }
+            Keyframes::Vector3Linear(frames) => {
+                for (_, v) in frames.iter_mut() {
+                    *v = correct_location(v);
+                }
+            }
+            _ => (),
+        },
+        _ => (),
+    }
+
+    Ok(())
+}
+
+/// Points a coord entry to its target coord. Returns None if the coord does not exist in the target clump.
+fn retarget_entry(mut entry: Entry, target: &RetargetClump) -> Result<Option<Entry>, NuccError> {
+    let source_name = match (&entry.entry_format, &entry.entry_info) {
+        (EntryFormat::Coord, EntryInfo::StructRef(struct_ref)) => struct_ref.0.clone(),
+        _ => return Ok(Some(entry)),
+    };
+
+    let target_name = target.bone_map.get(&source_name).unwrap_or(&source_name);
+    let target_ref = match target.coords.iter().find(|r| r.0 == *target_name) {
+        Some(target_ref) => target_ref,
+        None => return Ok(None),
+    };
+
+    if let Some((source_poses, target_poses)) = target.rest_poses {
+        if let (Some(source_pose), Some(target_pose)) = (
+            source_poses.get(&source_name),
+            target_poses.get(target_name),
+        ) {
+            for curve in entry.curves.iter_mut() {
+                correct_rest_pose(curve, source_pose, target_pose)?;
+            }
+        }
+    }
+
+    entry.entry_info = EntryInfo::StructRef(target_ref.clone());
+    Ok(Some(entry))
+}
+
+fn retarget_entries(
+    entries: Vec<Entry>,
+    target: &RetargetClump,
+    other_struct_refs: &mut Vec<NuccStructReference>,
+) -> Result<Vec<Entry>, NuccError> {
+    let mut retargeted = vec![];
+
+    for mut entry in entries {
+        let children = retarget_entries(mem::take(&mut entry.children), target, other_struct_refs)?;
+
+        match retarget_entry(entry, target)? {
+            Some(mut entry) => {
+                // Entries that are not coords (i.e. materials) keep pointing to their original chunks
+                if let EntryInfo::StructRef(struct_ref) = &entry.entry_info {
+                    if !target.coords.contains(struct_ref)
+                        && !other_struct_refs.contains(struct_ref)
+                    {
+                        other_struct_refs.push(struct_ref.clone());
+                    }
+                }
+
+                entry.children = children;
+                retargeted.push(entry);
+            }
+            // Children of coords that don't exist in the target are moved up to the closest parent
+            None => retargeted.extend(children),
+        }
+    }
+
+    Ok(retargeted)
+}
+
+impl NuccAnm {
+    /// Creates a copy of this animation where the clump referenced by `source_clump` is replaced with `target`.
+    /// Entries of coords that don't exist in the target clump are removed.
+    pub fn retarget(
+        &self,
+        source_clump: &NuccStructInfo,
+        target: &RetargetClump,
+    ) -> Result<NuccAnm, NuccError> {
+        let mut anm = self.clone();
+        let mut found = false;
+
+        for clump in anm.clumps.iter_mut() {
+            match &clump.clump_info {
+                ClumpInfo::StructRef(struct_ref) if struct_ref.1 == *source_clump => (),
+                _ => continue,
+            }
+
+            let mut entry_struct_refs = target.coords.to_vec();
+            let mut other_struct_refs = vec![];
+
+            let root_entries = retarget_entries(
+                mem::take(&mut clump.root_entries),
+                target,
+                &mut other_struct_refs,
+            )?;
+            entry_struct_refs.extend(other_struct_refs);
+
+            *clump = Clump::new_clump(
+                target.clump.clone(),
+                root_entries,
+                entry_struct_refs,
+                target.models.to_vec(),
+            );
+
+            found = true;
+        }
+
+        if !found {
+            return Err(NuccError::AnmClumpMismatch);
+        }
+
+        Ok(anm)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: &Vector3, b: &Vector3) {
+        for (a, b) in [(a.0, b.0), (a.1, b.1), (a.2, b.2)] {
+            assert!((a - b).abs() < 1e-3, "{a} != {b}");
+        }
+    }
+
+    #[test]
+    fn euler_round_trip() {
+        for v in [
+            Vector3(0.0, 0.0, 0.0),
+            Vector3(90.0, 0.0, 0.0),
+            Vector3(10.0, 20.0, 30.0),
+            Vector3(-45.0, 60.0, 170.0),
+        ] {
+            assert_close(&quaternion_to_euler(&euler_to_quaternion(&v)), &v);
+        }
+    }
+
+    #[test]
+    fn correct_euler_rest_pose() {
+        let source = RestPose {
+            location: Vector3(0.0, 0.0, 0.0),
+            rotation: euler_to_quaternion(&Vector3(0.0, 0.0, 0.0)),
+        };
+        let target = RestPose {
+            location: Vector3(0.0, 0.0, 0.0),
+            rotation: euler_to_quaternion(&Vector3(0.0, 0.0, 90.0)),
+        };
+
+        let mut curve = Curve::new(
+            0,
+            Channel::Rotation,
+            InterpolationType::Linear,
+            Keyframes::Vector3Linear(vec![
+                (0, Vector3(0.0, 0.0, 0.0)),
+                (10, Vector3(0.0, 0.0, 100.0)),
+            ]),
+        );
+        correct_rest_pose(&mut curve, &source, &target).unwrap();
+
+        match curve.keyframes() {
+            Keyframes::Vector3Linear(frames) => {
+                assert_close(&frames[0].1, &Vector3(0.0, 0.0, 90.0));
+                // 190 degrees, instead of wrapping around to -170
+                assert_close(&frames[1].1, &Vector3(0.0, 0.0, 190.0));
+            }
+            keyframes => panic!("Unexpected keyframes: {keyframes:?}"),
+        }
+    }
+
+    #[test]
+    fn correct_short_euler_rest_pose() {
+        let pose = RestPose {
+            location: Vector3(0.0, 0.0, 0.0),
+            rotation: Quaternion(0.0, 0.0, 0.0, 1.0),
+        };
+
+        let mut curve = Curve::new(
+            0,
+            Channel::Rotation,
+            InterpolationType::None,
+            Keyframes::Vector3Short(vec![Vector3Short(0, 0, 0)]),
+        );
+
+        assert!(correct_rest_pose(&mut curve, &pose, &pose).is_err());
+    }
+}
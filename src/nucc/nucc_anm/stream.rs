@@ -0,0 +1,249 @@
+use std::mem;
+
+use hashbrown::HashMap;
+
+use crate::error::NuccError;
+use crate::nucc::nucc_anm_strm_frame::FrameEntry;
+use crate::nucc::{position_of, NuccAnmStrm, NuccAnmStrmFrame};
+use crate::nucc_chunk::ClumpCoordIndex;
+
+use super::clump::{Clump, ClumpInfo};
+use super::curve::*;
+use super::entry::{Entry, EntryInfo};
+use super::timing::for_each_entry_mut;
+use super::{NuccAnm, NuccChunkType, NuccStructInfo};
+
+fn collect_entries<'a>(entries: &'a [Entry], collected: &mut Vec<&'a Entry>) {
+    for entry in entries {
+        collected.push(entry);
+        collect_entries(&entry.children, collected);
+    }
+}
+
+/// Assigns coord indices to the entries of the clumps the same way they are written to the chunk.
+fn coord_indices(clumps: &[Clump]) -> HashMap<EntryInfo, ClumpCoordIndex> {
+    let mut coord_indices = HashMap::new();
+    let mut clump_index = 0;
+    let mut other_entry_struct_infos = vec![];
+
+    for clump in clumps {
+        let mut entries = vec![];
+        collect_entries(&clump.root_entries, &mut entries);
+
+        match &clump.clump_info {
+            ClumpInfo::StructRef(_) => {
+                let mut entry_struct_refs = clump.entry_struct_refs().to_vec();
+
+                for entry in entries {
+                    if let EntryInfo::StructRef(struct_ref) = &entry.entry_info {
+                        let index = position_of(&mut entry_struct_refs, struct_ref);
+                        coord_indices.insert(
+                            entry.entry_info.clone(),
+                            ClumpCoordIndex(clump_index, index),
+                        );
+                    }
+                }
+
+                clump_index += 1;
+            }
+            ClumpInfo::NoInfo => {
+                other_entry_struct_infos.extend(clump.other_entry_struct_infos().to_vec());
+
+                for entry in entries {
+                    if let EntryInfo::StructInfo(info) = &entry.entry_info {
+                        let index = position_of(&mut other_entry_struct_infos, info);
+                        coord_indices.insert(entry.entry_info.clone(), ClumpCoordIndex(-1, index));
+                    }
+                }
+            }
+        }
+    }
+
+    coord_indices
+}
+
+fn sample_curves(curves: &[Curve], frame: u32, frame_size: u32) -> Vec<Curve> {
+    curves
+        .iter()
+        .filter(|c| c.keyframes().keyframe_count() != 0)
+        .map(|c| c.sample(frame, frame_size))
+        .collect()
+}
+
+/// Combines the keyframes of a curve from every frame, which are sorted by frame.
+fn combine_frames(frames: Vec<(u32, Keyframes)>, frame_size: u32) -> Result<Keyframes, NuccError> {
+    let mut frames = frames.into_iter();
+    let mut keyframes = match frames.next() {
+        Some((frame, mut keyframes)) => {
+            // The first keyframe of a curve is not always in the first frame
+            keyframes.delay(frame, frame_size);
+            keyframes
+        }
+        None => return Ok(Keyframes::None),
+    };
+
+    for (frame, other) in frames {
+        keyframes.append(other, frame, frame_size)?;
+    }
+
+    Ok(keyframes)
+}
+
+impl NuccAnmStrm {
+    /// Combines the keyframes of `frames` into a regular animation. The frames don't have to be sorted.
+    pub fn to_anm(&self, frames: &[&NuccAnmStrmFrame]) -> Result<NuccAnm, NuccError> {
+        let frame_size = self.frame_size.max(1);
+        let coord_indices = coord_indices(&self.clumps);
+
+        // The header curves only define the format of each channel
+        let mut curves = HashMap::new();
+        let mut clumps = self.clumps.clone();
+        for clump in clumps.iter_mut() {
+            for_each_entry_mut(&mut clump.root_entries, &mut |entry: &mut Entry| {
+                let mut entry_curves = mem::take(&mut entry.curves);
+                for curve in entry_curves.iter_mut() {
+                    *curve.keyframes_mut() = Keyframes::None;
+                }
+
+                let coord_index = *coord_indices
+                    .get(&entry.entry_info)
+                    .ok_or(NuccError::InvalidAnmEntry)?;
+                curves.insert(coord_index, entry_curves);
+                Ok(())
+            })?;
+        }
+
+        let mut frames = frames.to_vec();
+        frames.sort_by_key(|f| f.frame);
+
+        // The keyframes of each curve are collected from all frames first, and combined once
+        let mut curve_frames = HashMap::new();
+        for frame in frames {
+            for frame_entry in frame.entries.iter() {
+                let entry_curves = curves
+                    .get_mut(&frame_entry.coord_index)
+                    .ok_or(NuccError::AnmClumpMismatch)?;
+
+                for frame_curve in frame_entry.curves.iter() {
+                    if !entry_curves
+                        .iter()
+                        .any(|c| c.curve_index == frame_curve.curve_index)
+                    {
+                        let mut curve = frame_curve.clone();
+                        *curve.keyframes_mut() = Keyframes::None;
+                        entry_curves.push(curve);
+                    }
+
+                    curve_frames
+                        .entry((frame_entry.coord_index, frame_curve.curve_index))
+                        .or_insert_with(Vec::new)
+                        .push((frame.frame, frame_curve.keyframes().clone()));
+                }
+            }
+        }
+
+        for ((coord_index, curve_index), keyframes) in curve_frames {
+            let curve = curves
+                .get_mut(&coord_index)
+                .and_then(|entry_curves| {
+                    entry_curves
+                        .iter_mut()
+                        .find(|c| c.curve_index == curve_index)
+                })
+                .ok_or(NuccError::AnmClumpMismatch)?;
+
+            curve
+                .set_keyframes(combine_frames(keyframes, frame_size)?)
+                .map_err(|_| NuccError::KeyframesMismatch)?;
+        }
+
+        for clump in clumps.iter_mut() {
+            for_each_entry_mut(&mut clump.root_entries, &mut |entry: &mut Entry| {
+                entry.curves = coord_indices
+                    .get(&entry.entry_info)
+                    .and_then(|coord_index| curves.remove(coord_index))
+                    .unwrap_or_default();
+                Ok(())
+            })?;
+        }
+
+        Ok(NuccAnm {
+            struct_info: NuccStructInfo {
+                chunk_type: NuccChunkType::NuccChunkAnm.to_string(),
+                ..self.struct_info.clone()
+            },
+            version: self.version,
+            frame_count: self.frame_count,
+            frame_size: self.frame_size,
+            clumps,
+        })
+    }
+}
+
+impl NuccAnm {
+    /// Splits the animation into a streamed animation header and one frame struct for every frame.
+    /// Linear keyframes are sampled, so every frame has the values of all curves.
+    pub fn to_strm(&self) -> (NuccAnmStrm, Vec<NuccAnmStrmFrame>) {
+        let frame_size = self.frame_size.max(1);
+        let coord_indices = coord_indices(&self.clumps);
+
+        let mut entries = vec![];
+        for clump in self.clumps.iter() {
+            collect_entries(&clump.root_entries, &mut entries);
+        }
+
+        let frames = (0..self.frame_count.div_ceil(frame_size).max(1))
+            .map(|i| {
+                let frame = i * frame_size;
+
+                NuccAnmStrmFrame {
+                    struct_info: NuccStructInfo {
+                        chunk_type: NuccChunkType::NuccChunkAnmStrmFrame.to_string(),
+                        ..self.struct_info.clone()
+                    },
+                    version: self.version,
+                    frame,
+                    unk: 0,
+                    // Entries that don't match the kind of their clump have no coord index, and are not written
+                    entries: entries
+                        .iter()
+                        .filter_map(|entry| {
+                            Some(FrameEntry {
+                                coord_index: *coord_indices.get(&entry.entry_info)?,
+                                entry_format: entry.entry_format.clone(),
+                                curves: sample_curves(&entry.curves, frame, frame_size),
+                            })
+                        })
+                        .collect(),
+                }
+            })
+            .collect();
+
+        let mut clumps = self.clumps.clone();
+        for clump in clumps.iter_mut() {
+            for_each_entry_mut(&mut clump.root_entries, &mut |entry: &mut Entry| {
+                // The header curves have the formats of the frames' curves, which are sampled the same way
+                entry.curves = sample_curves(&entry.curves, 0, frame_size);
+                for curve in entry.curves.iter_mut() {
+                    curve.clear_frames();
+                }
+
+                Ok(())
+            })
+            .expect("Could not clear the curves of the streamed animation.");
+        }
+
+        let anm_strm = NuccAnmStrm {
+            struct_info: NuccStructInfo {
+                chunk_type: NuccChunkType::NuccChunkAnmStrm.to_string(),
+                ..self.struct_info.clone()
+            },
+            version: self.version,
+            frame_count: self.frame_count,
+            frame_size: self.frame_size,
+            clumps,
+        };
+
+        (anm_strm, frames)
+    }
+}
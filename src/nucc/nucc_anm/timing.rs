@@ -0,0 +1,1016 @@
+use std::{iter, mem};
+
+use hashbrown::HashMap;
+
+use crate::error::NuccError;
+
+use super::curve::*;
+use super::entry::{Entry, EntryInfo};
+use super::NuccAnm;
+
+// Time values (frame_count, linear keyframe frames and the arguments of these methods) are all in the same unit.
+// Table curves store one value per frame_size units, and fixed curves are tables with a single value.
+
+trait Interpolate: Clone {
+    fn interpolate(&self, other: &Self, t: f32) -> Self;
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+fn lerp_i16(a: i16, b: i16, t: f32) -> i16 {
+    lerp(a as f32, b as f32, t).round() as i16
+}
+
+impl Interpolate for f32 {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        lerp(*self, *other, t)
+    }
+}
+
+impl Interpolate for i16 {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        lerp_i16(*self, *other, t)
+    }
+}
+
+impl Interpolate for Vector2 {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Vector2(lerp(self.0, other.0, t), lerp(self.1, other.1, t))
+    }
+}
+
+impl Interpolate for Vector3 {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Vector3(
+            lerp(self.0, other.0, t),
+            lerp(self.1, other.1, t),
+            lerp(self.2, other.2, t),
+        )
+    }
+}
+
+impl Interpolate for Vector3Short {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Vector3Short(
+            lerp_i16(self.0, other.0, t),
+            lerp_i16(self.1, other.1, t),
+            lerp_i16(self.2, other.2, t),
+        )
+    }
+}
+
+impl Interpolate for Quaternion {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        // Normalized lerp along the shortest path
+        let dot = self.0 * other.0 + self.1 * other.1 + self.2 * other.2 + self.3 * other.3;
+        let sign = if dot < 0.0 { -1.0 } else { 1.0 };
+
+        let x = lerp(self.0, other.0 * sign, t);
+        let y = lerp(self.1, other.1 * sign, t);
+        let z = lerp(self.2, other.2 * sign, t);
+        let w = lerp(self.3, other.3 * sign, t);
+
+        let length = (x * x + y * y + z * z + w * w).sqrt();
+        if length > 0.0 {
+            Quaternion(x / length, y / length, z / length, w / length)
+        } else {
+            Quaternion(x, y, z, w)
+        }
+    }
+}
+
+impl Interpolate for QuaternionShort {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        let dot = self.0 as i32 * other.0 as i32
+            + self.1 as i32 * other.1 as i32
+            + self.2 as i32 * other.2 as i32
+            + self.3 as i32 * other.3 as i32;
+        let sign = if dot < 0 { -1.0 } else { 1.0 };
+
+        let component = |a: i16, b: i16| lerp(a as f32, b as f32 * sign, t).round() as i16;
+
+        QuaternionShort(
+            component(self.0, other.0),
+            component(self.1, other.1),
+            component(self.2, other.2),
+            component(self.3, other.3),
+        )
+    }
+}
+
+impl Interpolate for RGB {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        let component = |a: u8, b: u8| lerp(a as f32, b as f32, t).round() as u8;
+
+        RGB(
+            component(self.0, other.0),
+            component(self.1, other.1),
+            component(self.2, other.2),
+        )
+    }
+}
+
+fn sample_table<T: Interpolate>(frames: &[T], position: f32) -> T {
+    let last = frames.len() - 1;
+
+    if position <= 0.0 {
+        frames[0].clone()
+    } else if position >= last as f32 {
+        frames[last].clone()
+    } else {
+        let index = position.floor() as usize;
+        frames[index].interpolate(&frames[index + 1], position - index as f32)
+    }
+}
+
+fn sample_linear<T: Interpolate>(frames: &[(i32, T)], frame: i32) -> T {
+    match frames.iter().position(|(f, _)| *f >= frame) {
+        Some(0) => frames[0].1.clone(),
+        Some(i) => {
+            let (start, a) = &frames[i - 1];
+            let (end, b) = &frames[i];
+            a.interpolate(b, (frame - start) as f32 / (end - start) as f32)
+        }
+        None => frames[frames.len() - 1].1.clone(),
+    }
+}
+
+/// Splits off the keyframes with negative frames, which mark the end of a linear curve.
+fn split_terminators<T>(frames: &mut Vec<(i32, T)>) -> Vec<(i32, T)> {
+    // Terminators are at the end, so only they are searched
+    let index = frames
+        .iter()
+        .rposition(|(f, _)| *f >= 0)
+        .map_or(0, |i| i + 1);
+
+    frames.split_off(index)
+}
+
+fn sample_linear_value<T: Interpolate>(frames: &[(i32, T)], frame: i32) -> T {
+    match frames.iter().position(|(f, _)| *f < 0) {
+        Some(0) => frames[0].1.clone(),
+        Some(end) => sample_linear(&frames[..end], frame),
+        None => sample_linear(frames, frame),
+    }
+}
+
+fn table_to_linear<T>(frames: Vec<T>, frame_size: u32) -> Vec<(i32, T)> {
+    frames
+        .into_iter()
+        .enumerate()
+        .map(|(i, v)| ((i as u32 * frame_size) as i32, v))
+        .collect()
+}
+
+fn retime_table<T: Interpolate>(frames: &mut Vec<T>, factor: f32) {
+    // Fixed curves have a single value, so there is nothing to resample
+    if frames.len() < 2 {
+        return;
+    }
+
+    let frame_count = ((frames.len() - 1) as f32 * factor).round() as usize + 1;
+    let resampled = (0..frame_count)
+        .map(|i| sample_table(frames, i as f32 / factor))
+        .collect();
+
+    *frames = resampled;
+}
+
+fn retime_linear<T>(frames: &mut Vec<(i32, T)>, factor: f32) {
+    let mut terminators = split_terminators(frames);
+
+    for (frame, _) in frames.iter_mut() {
+        *frame = (*frame as f32 * factor).round() as i32;
+    }
+
+    // Keyframes that were squashed into the same frame keep the first value
+    frames.dedup_by_key(|(frame, _)| *frame);
+    frames.append(&mut terminators);
+}
+
+fn trim_table<T>(frames: &mut Vec<T>, start: usize, end: usize) {
+    if frames.len() < 2 {
+        return;
+    }
+
+    let last = frames.len() - 1;
+    frames.truncate(end.min(last) + 1);
+    frames.drain(..start.min(last));
+}
+
+fn trim_linear<T: Interpolate>(frames: &mut Vec<(i32, T)>, start: i32, end: i32) {
+    let mut terminators = split_terminators(frames);
+
+    if !frames.is_empty() {
+        let first = sample_linear(frames, start);
+        let last = sample_linear(frames, end);
+
+        let mut trimmed = vec![(0, first)];
+        trimmed.extend(
+            frames
+                .drain(..)
+                .filter(|(f, _)| *f > start && *f < end)
+                .map(|(f, v)| (f - start, v)),
+        );
+        trimmed.push((end - start, last));
+
+        *frames = trimmed;
+    }
+
+    frames.append(&mut terminators);
+}
+
+fn append_table<T: Clone + PartialEq>(
+    mut frames: Vec<T>,
+    mut other: Vec<T>,
+    offset: usize,
+) -> Vec<T> {
+    // Two fixed curves with the same value can stay fixed
+    if frames.len() <= 1 && frames == other {
+        return frames;
+    }
+
+    // Hold the last value until the appended frames start
+    if let Some(last) = frames.last().cloned() {
+        frames.resize(offset, last);
+    }
+
+    frames.append(&mut other);
+    frames
+}
+
+fn append_linear<T: Clone>(
+    mut frames: Vec<(i32, T)>,
+    mut other: Vec<(i32, T)>,
+    offset: i32,
+) -> Vec<(i32, T)> {
+    let mut terminators = split_terminators(&mut frames);
+    let other_terminators = split_terminators(&mut other);
+
+    if terminators.is_empty() {
+        terminators = other_terminators;
+    }
+
+    // Keyframes are sorted, so the ones that are replaced are removed from the end.
+    // This keeps appending one frame at a time linear.
+    while frames.last().map_or(false, |(f, _)| *f >= offset) {
+        frames.pop();
+    }
+
+    // Hold the last value until the appended keyframes start, instead of blending into them
+    if let Some((frame, value)) = frames.last().cloned() {
+        if frame < offset - 1 {
+            frames.push((offset - 1, value));
+        }
+    }
+
+    if let Some((frame, value)) = other.first().cloned() {
+        if frame > 0 {
+            frames.push((offset, value));
+        }
+    }
+
+    frames.extend(other.into_iter().map(|(f, v)| (f + offset, v)));
+    frames.append(&mut terminators);
+    frames
+}
+
+fn delay_table<T: Clone>(frames: &mut Vec<T>, index: usize) {
+    if let Some(first) = frames.first().cloned() {
+        frames.splice(0..0, iter::repeat(first).take(index));
+    }
+}
+
+fn delay_linear<T>(frames: &mut [(i32, T)], offset: i32) {
+    // Terminators keep their negative frames
+    for (frame, _) in frames.iter_mut().filter(|(f, _)| *f >= 0) {
+        *frame += offset;
+    }
+}
+
+impl Keyframes {
+    pub fn is_linear(&self) -> bool {
+        matches!(
+            self,
+            Keyframes::FloatLinear(_)
+                | Keyframes::Vector2Linear(_)
+                | Keyframes::Vector3Linear(_)
+                | Keyframes::Vector3ShortLinear(_)
+                | Keyframes::QuaternionLinear(_)
+        )
+    }
+
+    /// Rescales the keyframe times by `factor`. Table curves are resampled, fixed curves are left as is.
+    pub fn retime(&mut self, factor: f32) {
+        match self {
+            Keyframes::None => (),
+            Keyframes::Float(frames) => retime_table(frames, factor),
+            Keyframes::FloatLinear(frames) => retime_linear(frames, factor),
+            Keyframes::Vector2(frames) => retime_table(frames, factor),
+            Keyframes::Vector2Linear(frames) => retime_linear(frames, factor),
+            Keyframes::Vector3(frames) => retime_table(frames, factor),
+            Keyframes::Vector3Short(frames) => retime_table(frames, factor),
+            Keyframes::Vector3Linear(frames) => retime_linear(frames, factor),
+            Keyframes::Vector3ShortLinear(frames) => retime_linear(frames, factor),
+            Keyframes::Quaternion(frames) => retime_table(frames, factor),
+            Keyframes::QuaternionShort(frames) => retime_table(frames, factor),
+            Keyframes::QuaternionLinear(frames) => retime_linear(frames, factor),
+            Keyframes::RGB(frames) => retime_table(frames, factor),
+            Keyframes::Opacity(frames) => retime_table(frames, factor),
+        }
+    }
+
+    /// Crops the keyframes to the range `start..=end` and moves `start` to frame 0.
+    pub fn trim(&mut self, start: u32, end: u32, frame_size: u32) {
+        let start_index = (start / frame_size) as usize;
+        let end_index = end.div_ceil(frame_size) as usize;
+        let (start, end) = (start as i32, end as i32);
+
+        match self {
+            Keyframes::None => (),
+            Keyframes::Float(frames) => trim_table(frames, start_index, end_index),
+            Keyframes::FloatLinear(frames) => trim_linear(frames, start, end),
+            Keyframes::Vector2(frames) => trim_table(frames, start_index, end_index),
+            Keyframes::Vector2Linear(frames) => trim_linear(frames, start, end),
+            Keyframes::Vector3(frames) => trim_table(frames, start_index, end_index),
+            Keyframes::Vector3Short(frames) => trim_table(frames, start_index, end_index),
+            Keyframes::Vector3Linear(frames) => trim_linear(frames, start, end),
+            Keyframes::Vector3ShortLinear(frames) => trim_linear(frames, start, end),
+            Keyframes::Quaternion(frames) => trim_table(frames, start_index, end_index),
+            Keyframes::QuaternionShort(frames) => trim_table(frames, start_index, end_index),
+            Keyframes::QuaternionLinear(frames) => trim_linear(frames, start, end),
+            Keyframes::RGB(frames) => trim_table(frames, start_index, end_index),
+            Keyframes::Opacity(frames) => trim_table(frames, start_index, end_index),
+        }
+    }
+
+    /// Moves the keyframes so that they start at `offset`. Table curves hold their first value until then.
+    pub fn delay(&mut self, offset: u32, frame_size: u32) {
+        let index = (offset / frame_size) as usize;
+        let offset = offset as i32;
+
+        match self {
+            Keyframes::None => (),
+            Keyframes::Float(frames) => delay_table(frames, index),
+            Keyframes::FloatLinear(frames) => delay_linear(frames, offset),
+            // Vector2 curves can only be fixed, so they become linear to start later
+            Keyframes::Vector2(frames) if offset > 0 => {
+                let mut frames = table_to_linear(mem::take(frames), frame_size);
+                delay_linear(&mut frames, offset);
+                *self = Keyframes::Vector2Linear(frames);
+            }
+            Keyframes::Vector2(_) => (),
+            Keyframes::Vector2Linear(frames) => delay_linear(frames, offset),
+            Keyframes::Vector3(frames) => delay_table(frames, index),
+            Keyframes::Vector3Short(frames) => delay_table(frames, index),
+            Keyframes::Vector3Linear(frames) => delay_linear(frames, offset),
+            Keyframes::Vector3ShortLinear(frames) => delay_linear(frames, offset),
+            Keyframes::Quaternion(frames) => delay_table(frames, index),
+            Keyframes::QuaternionShort(frames) => delay_table(frames, index),
+            Keyframes::QuaternionLinear(frames) => delay_linear(frames, offset),
+            Keyframes::RGB(frames) => delay_table(frames, index),
+            Keyframes::Opacity(frames) => delay_table(frames, index),
+        }
+    }
+
+    /// Returns the value at `frame` as a single table keyframe. Linear keyframes are sampled into their table type.
+    pub fn sample(&self, frame: u32, frame_size: u32) -> Keyframes {
+        if self.keyframe_count() == 0 {
+            return Keyframes::None;
+        }
+
+        let position = frame as f32 / frame_size as f32;
+        let frame = frame as i32;
+
+        match self {
+            Keyframes::None => Keyframes::None,
+            Keyframes::Float(frames) => Keyframes::Float(vec![sample_table(frames, position)]),
+            Keyframes::FloatLinear(frames) => {
+                Keyframes::Float(vec![sample_linear_value(frames, frame)])
+            }
+            Keyframes::Vector2(frames) => Keyframes::Vector2(vec![sample_table(frames, position)]),
+            Keyframes::Vector2Linear(frames) => {
+                Keyframes::Vector2(vec![sample_linear_value(frames, frame)])
+            }
+            Keyframes::Vector3(frames) => Keyframes::Vector3(vec![sample_table(frames, position)]),
+            Keyframes::Vector3Short(frames) => {
+                Keyframes::Vector3Short(vec![sample_table(frames, position)])
+            }
+            Keyframes::Vector3Linear(frames) => {
+                Keyframes::Vector3(vec![sample_linear_value(frames, frame)])
+            }
+            Keyframes::Vector3ShortLinear(frames) => {
+                Keyframes::Vector3Short(vec![sample_linear_value(frames, frame)])
+            }
+            Keyframes::Quaternion(frames) => {
+                Keyframes::Quaternion(vec![sample_table(frames, position)])
+            }
+            Keyframes::QuaternionShort(frames) => {
+                Keyframes::QuaternionShort(vec![sample_table(frames, position)])
+            }
+            Keyframes::QuaternionLinear(frames) => {
+                Keyframes::Quaternion(vec![sample_linear_value(frames, frame)])
+            }
+            Keyframes::RGB(frames) => Keyframes::RGB(vec![sample_table(frames, position)]),
+            Keyframes::Opacity(frames) => Keyframes::Opacity(vec![sample_table(frames, position)]),
+        }
+    }
+
+    /// Appends `other` so that its first frame starts at `offset`.
+    /// Table and linear keyframes of the same type can be mixed, in which case the result is linear.
+    pub fn append(
+        &mut self,
+        other: Keyframes,
+        offset: u32,
+        frame_size: u32,
+    ) -> Result<(), NuccError> {
+        let index = (offset / frame_size) as usize;
+        let frame = offset as i32;
+
+        let keyframes = match (mem::replace(self, Keyframes::None), other) {
+            (Keyframes::None, Keyframes::None) => Keyframes::None,
+
+            (Keyframes::Float(a), Keyframes::Float(b)) => {
+                Keyframes::Float(append_table(a, b, index))
+            }
+            (Keyframes::Float(a), Keyframes::FloatLinear(b)) => {
+                Keyframes::FloatLinear(append_linear(table_to_linear(a, frame_size), b, frame))
+            }
+            (Keyframes::FloatLinear(a), Keyframes::Float(b)) => {
+                Keyframes::FloatLinear(append_linear(a, table_to_linear(b, frame_size), frame))
+            }
+            (Keyframes::FloatLinear(a), Keyframes::FloatLinear(b)) => {
+                Keyframes::FloatLinear(append_linear(a, b, frame))
+            }
+
+            (Keyframes::Vector2(a), Keyframes::Vector2(b)) => {
+                // Vector2 curves can only be fixed, so they become linear once they have more than one value
+                match append_table(a, b, index) {
+                    frames if frames.len() > 1 => {
+                        Keyframes::Vector2Linear(table_to_linear(frames, frame_size))
+                    }
+                    frames => Keyframes::Vector2(frames),
+                }
+            }
+            (Keyframes::Vector2(a), Keyframes::Vector2Linear(b)) => {
+                Keyframes::Vector2Linear(append_linear(table_to_linear(a, frame_size), b, frame))
+            }
+            (Keyframes::Vector2Linear(a), Keyframes::Vector2(b)) => {
+                Keyframes::Vector2Linear(append_linear(a, table_to_linear(b, frame_size), frame))
+            }
+            (Keyframes::Vector2Linear(a), Keyframes::Vector2Linear(b)) => {
+                Keyframes::Vector2Linear(append_linear(a, b, frame))
+            }
+
+            (Keyframes::Vector3(a), Keyframes::Vector3(b)) => {
+                Keyframes::Vector3(append_table(a, b, index))
+            }
+            (Keyframes::Vector3(a), Keyframes::Vector3Linear(b)) => {
+                Keyframes::Vector3Linear(append_linear(table_to_linear(a, frame_size), b, frame))
+            }
+            (Keyframes::Vector3Linear(a), Keyframes::Vector3(b)) => {
+                Keyframes::Vector3Linear(append_linear(a, table_to_linear(b, frame_size), frame))
+            }
+            (Keyframes::Vector3Linear(a), Keyframes::Vector3Linear(b)) => {
+                Keyframes::Vector3Linear(append_linear(a, b, frame))
+            }
+
+            (Keyframes::Vector3Short(a), Keyframes::Vector3Short(b)) => {
+                Keyframes::Vector3Short(append_table(a, b, index))
+            }
+            (Keyframes::Vector3Short(a), Keyframes::Vector3ShortLinear(b)) => {
+                Keyframes::Vector3ShortLinear(append_linear(
+                    table_to_linear(a, frame_size),
+                    b,
+                    frame,
+                ))
+            }
+            (Keyframes::Vector3ShortLinear(a), Keyframes::Vector3Short(b)) => {
+                Keyframes::Vector3ShortLinear(append_linear(
+                    a,
+                    table_to_linear(b, frame_size),
+                    frame,
+                ))
+            }
+            (Keyframes::Vector3ShortLinear(a), Keyframes::Vector3ShortLinear(b)) => {
+                Keyframes::Vector3ShortLinear(append_linear(a, b, frame))
+            }
+
+            (Keyframes::Quaternion(a), Keyframes::Quaternion(b)) => {
+                Keyframes::Quaternion(append_table(a, b, index))
+            }
+            (Keyframes::Quaternion(a), Keyframes::QuaternionLinear(b)) => {
+                Keyframes::QuaternionLinear(append_linear(table_to_linear(a, frame_size), b, frame))
+            }
+            (Keyframes::QuaternionLinear(a), Keyframes::Quaternion(b)) => {
+                Keyframes::QuaternionLinear(append_linear(a, table_to_linear(b, frame_size), frame))
+            }
+            (Keyframes::QuaternionLinear(a), Keyframes::QuaternionLinear(b)) => {
+                Keyframes::QuaternionLinear(append_linear(a, b, frame))
+            }
+
+            (Keyframes::QuaternionShort(a), Keyframes::QuaternionShort(b)) => {
+                Keyframes::QuaternionShort(append_table(a, b, index))
+            }
+            (Keyframes::RGB(a), Keyframes::RGB(b)) => Keyframes::RGB(append_table(a, b, index)),
+            (Keyframes::Opacity(a), Keyframes::Opacity(b)) => {
+                Keyframes::Opacity(append_table(a, b, index))
+            }
+
+            (keyframes, _) => {
+                *self = keyframes;
+                return Err(NuccError::KeyframesMismatch);
+            }
+        };
+
+        *self = keyframes;
+        Ok(())
+    }
+}
+
+pub(super) fn for_each_entry_mut<F>(entries: &mut [Entry], f: &mut F) -> Result<(), NuccError>
+where
+    F: FnMut(&mut Entry) -> Result<(), NuccError>,
+{
+    for entry in entries {
+        f(entry)?;
+        for_each_entry_mut(&mut entry.children, f)?;
+    }
+
+    Ok(())
+}
+
+fn take_entry_curves(entries: Vec<Entry>, curves: &mut HashMap<EntryInfo, Vec<Curve>>) {
+    for entry in entries {
+        curves.insert(entry.entry_info, entry.curves);
+        take_entry_curves(entry.children, curves);
+    }
+}
+
+impl NuccAnm {
+    fn for_each_curve_mut<F>(&mut self, mut f: F) -> Result<(), NuccError>
+    where
+        F: FnMut(&mut Curve) -> Result<(), NuccError>,
+    {
+        for clump in self.clumps.iter_mut() {
+            for_each_entry_mut(&mut clump.root_entries, &mut |entry: &mut Entry| {
+                entry.curves.iter_mut().try_for_each(&mut f)
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Rescales the animation's time by `factor`. Values above 1 slow it down, and values below 1 speed it up.
+    pub fn retime(&mut self, factor: f32) -> Result<(), NuccError> {
+        if !factor.is_finite() || factor <= 0.0 {
+            return Err(NuccError::InvalidTimeScale);
+        }
+
+        self.frame_count = (self.frame_count as f32 * factor).round() as u32;
+        self.for_each_curve_mut(|curve| {
+            curve.keyframes_mut().retime(factor);
+            Ok(())
+        })
+    }
+
+    /// Crops the animation to the range `start..end`, given in the same unit as `frame_count`.
+    pub fn trim(&mut self, start: u32, end: u32) -> Result<(), NuccError> {
+        let end = end.min(self.frame_count);
+        if start >= end {
+            return Err(NuccError::InvalidFrameRange);
+        }
+
+        let frame_size = self.frame_size.max(1);
+
+        self.frame_count = end - start;
+        self.for_each_curve_mut(|curve| {
+            curve.keyframes_mut().trim(start, end, frame_size);
+            Ok(())
+        })
+    }
+
+    /// Loops the animation so that it plays `count` times in total.
+    pub fn repeat(&mut self, count: u32) -> Result<(), NuccError> {
+        if count == 0 {
+            return Err(NuccError::InvalidFrameRange);
+        }
+
+        let anm = self.clone();
+        for _ in 1..count {
+            self.append(anm.clone())?;
+        }
+
+        Ok(())
+    }
+
+    /// Appends `other` to the end of this animation. Both animations must target the same clumps.
+    /// Entries that are missing from `other` hold their last value.
+    pub fn append(&mut self, other: NuccAnm) -> Result<(), NuccError> {
+        if self.frame_size != other.frame_size {
+            return Err(NuccError::AnmFrameSizeMismatch);
+        }
+
+        let mut other_curves = HashMap::new();
+        for clump in other.clumps {
+            if !self.clumps.iter().any(|c| c.clump_info == clump.clump_info) {
+                return Err(NuccError::AnmClumpMismatch);
+            }
+
+            take_entry_curves(clump.root_entries, &mut other_curves);
+        }
+
+        let offset = self.frame_count;
+        let frame_size = self.frame_size.max(1);
+
+        // Work on a copy so that self is left untouched if the curves don't match
+        let mut clumps = self.clumps.clone();
+        for clump in clumps.iter_mut() {
+            for_each_entry_mut(&mut clump.root_entries, &mut |entry: &mut Entry| {
+                let curves = match other_curves.remove(&entry.entry_info) {
+                    Some(curves) => curves,
+                    None => return Ok(()),
+                };
+
+                if curves.len() != entry.curves.len() {
+                    return Err(NuccError::KeyframesMismatch);
+                }
+
+                for (curve, mut other_curve) in entry.curves.iter_mut().zip(curves) {
+                    if curve.channel != other_curve.channel {
+                        return Err(NuccError::KeyframesMismatch);
+                    }
+
+                    let keyframes = mem::replace(other_curve.keyframes_mut(), Keyframes::None);
+                    curve
+                        .keyframes_mut()
+                        .append(keyframes, offset, frame_size)?;
+                }
+
+                Ok(())
+            })?;
+        }
+
+        self.clumps = clumps;
+        self.frame_count += other.frame_count;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{clump::Clump, entry::EntryFormat};
+    use super::*;
+    use crate::nucc::NuccStructInfo;
+
+    const FRAME_SIZE: u32 = 100;
+
+    fn v3(value: f32) -> Vector3 {
+        Vector3(value, value, value)
+    }
+
+    /// An animation with a single entry, whose only curve is on `channel`.
+    fn anm(frame_count: u32, channel: Channel, keyframes: Keyframes) -> NuccAnm {
+        let struct_info = NuccStructInfo {
+            chunk_name: "bone".to_string(),
+            ..Default::default()
+        };
+
+        let curve_index = EntryFormat::Coord
+            .iter_channels()
+            .position(|c| c == channel)
+            .unwrap() as u16;
+
+        let entry = Entry {
+            entry_info: EntryInfo::StructInfo(struct_info.clone()),
+            entry_format: EntryFormat::Coord,
+            curves: vec![Curve::new(
+                curve_index,
+                channel,
+                InterpolationType::Linear,
+                keyframes,
+            )],
+            children: vec![],
+        };
+
+        NuccAnm {
+            struct_info: Default::default(),
+            version: 0x79,
+            frame_count,
+            frame_size: FRAME_SIZE,
+            clumps: vec![Clump::new_other(vec![entry], vec![struct_info])],
+        }
+    }
+
+    fn keyframes(anm: &NuccAnm) -> &Keyframes {
+        anm.clumps[0].root_entries[0].curves[0].keyframes()
+    }
+
+    fn assert_floats(keyframes: &Keyframes, expected: &[f32]) {
+        match keyframes {
+            Keyframes::Float(frames) => {
+                assert_eq!(frames.len(), expected.len(), "{frames:?} != {expected:?}");
+                for (value, expected_value) in frames.iter().zip(expected) {
+                    assert!(
+                        (value - expected_value).abs() < 1e-4,
+                        "{frames:?} != {expected:?}"
+                    );
+                }
+            }
+            _ => panic!("Expected float table keyframes, got {keyframes:?}"),
+        }
+    }
+
+    #[test]
+    fn retime_table() {
+        let mut keyframes = Keyframes::Float(vec![0.0, 10.0, 20.0]);
+        keyframes.retime(2.0);
+        assert_floats(&keyframes, &[0.0, 5.0, 10.0, 15.0, 20.0]);
+    }
+
+    #[test]
+    fn retime_table_by_fraction() {
+        let mut anm = anm(300, Channel::Opacity, Keyframes::Float(vec![0.0, 3.0, 6.0]));
+        anm.retime(1.5).unwrap();
+
+        assert_eq!(anm.frame_count, 450);
+        assert_floats(keyframes(&anm), &[0.0, 2.0, 4.0, 6.0]);
+    }
+
+    #[test]
+    fn retime_linear() {
+        let mut keyframes = Keyframes::FloatLinear(vec![(0, 0.0), (2, 1.0), (3, 2.0), (-1, 2.0)]);
+        keyframes.retime(1.5);
+
+        // Terminators keep their frame, and halves are rounded away from zero
+        assert_eq!(
+            keyframes,
+            Keyframes::FloatLinear(vec![(0, 0.0), (3, 1.0), (5, 2.0), (-1, 2.0)])
+        );
+    }
+
+    #[test]
+    fn retime_linear_squashed() {
+        let mut keyframes =
+            Keyframes::Vector3Linear(vec![(0, v3(0.0)), (1, v3(1.0)), (3, v3(3.0))]);
+        keyframes.retime(0.4);
+
+        assert_eq!(
+            keyframes,
+            Keyframes::Vector3Linear(vec![(0, v3(0.0)), (1, v3(3.0))])
+        );
+    }
+
+    #[test]
+    fn retime_fixed() {
+        let mut keyframes = Keyframes::Vector2(vec![Vector2(1.0, 2.0)]);
+        keyframes.retime(1.5);
+        assert_eq!(keyframes, Keyframes::Vector2(vec![Vector2(1.0, 2.0)]));
+    }
+
+    #[test]
+    fn retime_invalid_factor() {
+        let mut anm = anm(100, Channel::Opacity, Keyframes::Float(vec![0.0, 1.0]));
+
+        assert!(matches!(anm.retime(0.0), Err(NuccError::InvalidTimeScale)));
+        assert!(matches!(anm.retime(-1.0), Err(NuccError::InvalidTimeScale)));
+        assert!(matches!(
+            anm.retime(f32::NAN),
+            Err(NuccError::InvalidTimeScale)
+        ));
+        assert_eq!(anm.frame_count, 100);
+    }
+
+    #[test]
+    fn trim_table() {
+        let mut anm = anm(
+            400,
+            Channel::Opacity,
+            Keyframes::Float(vec![0.0, 1.0, 2.0, 3.0, 4.0]),
+        );
+        anm.trim(100, 300).unwrap();
+
+        assert_eq!(anm.frame_count, 200);
+        assert_floats(keyframes(&anm), &[1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn trim_table_ends() {
+        let frames = vec![0.0, 1.0, 2.0, 3.0, 4.0];
+
+        let mut start = Keyframes::Float(frames.clone());
+        start.trim(0, 200, FRAME_SIZE);
+        assert_floats(&start, &[0.0, 1.0, 2.0]);
+
+        let mut end = Keyframes::Float(frames.clone());
+        end.trim(300, 1000, FRAME_SIZE);
+        assert_floats(&end, &[3.0, 4.0]);
+
+        // Ranges that don't line up with the table include the frames around them
+        let mut between = Keyframes::Float(frames);
+        between.trim(150, 250, FRAME_SIZE);
+        assert_floats(&between, &[1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn trim_linear() {
+        let mut keyframes = Keyframes::FloatLinear(vec![(0, 0.0), (10, 10.0), (20, 20.0)]);
+        keyframes.trim(5, 15, FRAME_SIZE);
+
+        assert_eq!(
+            keyframes,
+            Keyframes::FloatLinear(vec![(0, 5.0), (5, 10.0), (10, 15.0)])
+        );
+    }
+
+    #[test]
+    fn trim_linear_ends() {
+        let frames = vec![(0, 0.0), (10, 10.0), (20, 20.0), (-1, 20.0)];
+
+        let mut whole = Keyframes::FloatLinear(frames.clone());
+        whole.trim(0, 20, FRAME_SIZE);
+        assert_eq!(whole, Keyframes::FloatLinear(frames.clone()));
+
+        let mut end = Keyframes::FloatLinear(frames);
+        end.trim(10, 20, FRAME_SIZE);
+        assert_eq!(
+            end,
+            Keyframes::FloatLinear(vec![(0, 10.0), (10, 20.0), (-1, 20.0)])
+        );
+    }
+
+    #[test]
+    fn trim_fixed() {
+        let mut anm = anm(200, Channel::Scale, Keyframes::Vector3(vec![v3(1.0)]));
+        anm.trim(100, 200).unwrap();
+
+        assert_eq!(anm.frame_count, 100);
+        assert_eq!(keyframes(&anm), &Keyframes::Vector3(vec![v3(1.0)]));
+    }
+
+    #[test]
+    fn trim_invalid_range() {
+        let mut anm = anm(200, Channel::Opacity, Keyframes::Float(vec![0.0, 1.0, 2.0]));
+
+        assert!(matches!(
+            anm.trim(200, 100),
+            Err(NuccError::InvalidFrameRange)
+        ));
+        assert!(matches!(
+            anm.trim(200, 300),
+            Err(NuccError::InvalidFrameRange)
+        ));
+        assert_eq!(anm.frame_count, 200);
+    }
+
+    #[test]
+    fn repeat_table() {
+        let mut anm = anm(
+            300,
+            Channel::Location,
+            Keyframes::Vector3(vec![v3(0.0), v3(1.0)]),
+        );
+        anm.repeat(3).unwrap();
+
+        // The last value is held until the next loop starts
+        assert_eq!(anm.frame_count, 900);
+        assert_eq!(
+            keyframes(&anm),
+            &Keyframes::Vector3(vec![
+                v3(0.0),
+                v3(1.0),
+                v3(1.0),
+                v3(0.0),
+                v3(1.0),
+                v3(1.0),
+                v3(0.0),
+                v3(1.0),
+            ])
+        );
+    }
+
+    #[test]
+    fn repeat_fixed() {
+        let mut anm = anm(100, Channel::Scale, Keyframes::Vector3(vec![v3(1.0)]));
+        anm.repeat(2).unwrap();
+
+        assert_eq!(anm.frame_count, 200);
+        assert_eq!(keyframes(&anm), &Keyframes::Vector3(vec![v3(1.0)]));
+    }
+
+    #[test]
+    fn repeat_zero() {
+        let mut anm = anm(100, Channel::Scale, Keyframes::Vector3(vec![v3(1.0)]));
+        assert!(matches!(anm.repeat(0), Err(NuccError::InvalidFrameRange)));
+    }
+
+    #[test]
+    fn append_linear() {
+        let mut first = anm(
+            100,
+            Channel::Location,
+            Keyframes::Vector3Linear(vec![(0, v3(0.0)), (50, v3(1.0))]),
+        );
+        let second = anm(
+            100,
+            Channel::Location,
+            Keyframes::Vector3Linear(vec![(0, v3(2.0)), (100, v3(3.0))]),
+        );
+        first.append(second).unwrap();
+
+        assert_eq!(first.frame_count, 200);
+        assert_eq!(
+            keyframes(&first),
+            &Keyframes::Vector3Linear(vec![
+                (0, v3(0.0)),
+                (50, v3(1.0)),
+                (99, v3(1.0)),
+                (100, v3(2.0)),
+                (200, v3(3.0)),
+            ])
+        );
+    }
+
+    #[test]
+    fn append_table_to_linear() {
+        let mut first = anm(100, Channel::Opacity, Keyframes::Float(vec![1.0, 2.0]));
+        let second = anm(
+            100,
+            Channel::Opacity,
+            Keyframes::FloatLinear(vec![(50, 5.0), (100, 6.0)]),
+        );
+        first.append(second).unwrap();
+
+        // The appended curve starts later, so its first value is moved to the start
+        assert_eq!(
+            keyframes(&first),
+            &Keyframes::FloatLinear(vec![
+                (0, 1.0),
+                (99, 1.0),
+                (100, 5.0),
+                (150, 5.0),
+                (200, 6.0)
+            ])
+        );
+    }
+
+    #[test]
+    fn append_fixed_vector2() {
+        let mut same = Keyframes::Vector2(vec![Vector2(1.0, 1.0)]);
+        same.append(Keyframes::Vector2(vec![Vector2(1.0, 1.0)]), 100, FRAME_SIZE)
+            .unwrap();
+        assert_eq!(same, Keyframes::Vector2(vec![Vector2(1.0, 1.0)]));
+
+        // Vector2 tables can only be fixed
+        let mut different = Keyframes::Vector2(vec![Vector2(1.0, 1.0)]);
+        different
+            .append(Keyframes::Vector2(vec![Vector2(2.0, 2.0)]), 100, FRAME_SIZE)
+            .unwrap();
+        assert_eq!(
+            different,
+            Keyframes::Vector2Linear(vec![(0, Vector2(1.0, 1.0)), (100, Vector2(2.0, 2.0))])
+        );
+    }
+
+    #[test]
+    fn append_mismatch() {
+        let mut keyframes = Keyframes::Float(vec![1.0]);
+        let result = keyframes.append(Keyframes::Vector3(vec![v3(1.0)]), 100, FRAME_SIZE);
+
+        assert!(matches!(result, Err(NuccError::KeyframesMismatch)));
+        assert_eq!(keyframes, Keyframes::Float(vec![1.0]));
+
+        let mut first = anm(100, Channel::Opacity, Keyframes::Float(vec![1.0]));
+        let mut second = first.clone();
+        second.frame_size = 50;
+
+        assert!(matches!(
+            first.append(second),
+            Err(NuccError::AnmFrameSizeMismatch)
+        ));
+    }
+
+    #[test]
+    fn delay() {
+        let mut table = Keyframes::Float(vec![1.0, 2.0]);
+        table.delay(200, FRAME_SIZE);
+        assert_floats(&table, &[1.0, 1.0, 1.0, 2.0]);
+
+        let mut linear = Keyframes::FloatLinear(vec![(0, 1.0), (10, 2.0), (-1, 2.0)]);
+        linear.delay(5, FRAME_SIZE);
+        assert_eq!(
+            linear,
+            Keyframes::FloatLinear(vec![(5, 1.0), (15, 2.0), (-1, 2.0)])
+        );
+
+        let mut fixed = Keyframes::Vector2(vec![Vector2(1.0, 2.0)]);
+        fixed.delay(100, FRAME_SIZE);
+        assert_eq!(
+            fixed,
+            Keyframes::Vector2Linear(vec![(100, Vector2(1.0, 2.0))])
+        );
+    }
+}
@@ -0,0 +1,86 @@
+use super::nucc_anm::clump::Clump;
+use super::*;
+
+use crate::error::NuccError;
+
+/// Header of a streamed animation. Its clumps have the same structure as a NuccAnm's, but the curves have no
+/// keyframes. Use `to_anm` with the page's NuccAnmStrmFrame structs to get the full animation.
+#[derive(Clone)]
+pub struct NuccAnmStrm {
+    pub struct_info: NuccStructInfo,
+    pub version: u16,
+
+    pub frame_count: u32,
+    pub frame_size: u32,
+
+    pub clumps: Vec<Clump>,
+}
+
+impl_nucc_info!(NuccAnmStrm, struct_info);
+
+impl<'a> From<NuccStructConverter<'a>> for NuccAnmStrm {
+    fn from(converter: NuccStructConverter<'a>) -> Self {
+        let NuccStructConverter(boxed, struct_infos, struct_references) = converter;
+        let chunk = boxed
+            .downcast::<NuccChunkAnmStrm>()
+            .map(|c| *c)
+            .ok()
+            .unwrap();
+
+        let anm = NuccAnm::from(NuccStructConverter(
+            Box::new(chunk.anm),
+            struct_infos,
+            struct_references,
+        ));
+
+        Self {
+            struct_info: Default::default(),
+            version: chunk.version,
+            frame_count: anm.frame_count,
+            frame_size: anm.frame_size,
+            clumps: anm.clumps,
+        }
+    }
+}
+
+impl<'a> TryFrom<NuccChunkConverter<'a>> for Box<NuccChunkAnmStrm> {
+    type Error = NuccError;
+
+    fn try_from(converter: NuccChunkConverter) -> Result<Self, Self::Error> {
+        let NuccChunkConverter(boxed, struct_infos, struct_references) = converter;
+        let anm_strm = boxed.downcast::<NuccAnmStrm>().map(|s| *s).ok().unwrap();
+
+        let anm = NuccAnm {
+            struct_info: anm_strm.struct_info,
+            version: anm_strm.version,
+            frame_count: anm_strm.frame_count,
+            frame_size: anm_strm.frame_size,
+            clumps: anm_strm.clumps,
+        };
+
+        let chunk = Box::<NuccChunkAnm>::try_from(NuccChunkConverter(
+            Box::new(anm),
+            struct_infos,
+            struct_references,
+        ))?;
+
+        Ok(Box::new(NuccChunkAnmStrm {
+            version: anm_strm.version,
+            anm: *chunk,
+        }))
+    }
+}
+
+impl NuccStruct for NuccAnmStrm {
+    fn chunk_type(&self) -> NuccChunkType {
+        NuccChunkType::NuccChunkAnmStrm
+    }
+
+    fn version(&self) -> u16 {
+        self.version
+    }
+
+    fn set_version(&mut self, version: u16) {
+        self.version = version;
+    }
+}
@@ -0,0 +1,111 @@
+use deku::DekuUpdate;
+
+use super::nucc_anm::curve::Curve;
+use super::nucc_anm::entry::{Entry, EntryChunkConverter, EntryFormat};
+use super::*;
+
+use crate::error::NuccError;
+pub use crate::nucc_chunk::ClumpCoordIndex;
+use crate::nucc_chunk::Entry as ChunkEntry;
+
+/// Entry of a single frame. The coord index points to a coord in the clumps of the NuccAnmStrm.
+#[derive(Debug, Clone)]
+pub struct FrameEntry {
+    pub coord_index: ClumpCoordIndex,
+    pub entry_format: EntryFormat,
+
+    /// Each curve has a single keyframe.
+    pub curves: Vec<Curve>,
+}
+
+#[derive(Clone)]
+pub struct NuccAnmStrmFrame {
+    pub struct_info: NuccStructInfo,
+    pub version: u16,
+
+    /// In the same unit as the frame_count of the NuccAnmStrm.
+    pub frame: u32,
+    pub unk: u16,
+
+    pub entries: Vec<FrameEntry>,
+}
+
+impl_nucc_info!(NuccAnmStrmFrame, struct_info);
+
+impl<'a> From<NuccStructConverter<'a>> for NuccAnmStrmFrame {
+    fn from(converter: NuccStructConverter<'a>) -> Self {
+        let NuccStructConverter(boxed, _, _) = converter;
+        let chunk = boxed
+            .downcast::<NuccChunkAnmStrmFrame>()
+            .map(|c| *c)
+            .ok()
+            .unwrap();
+
+        let entries = chunk
+            .entries
+            .into_iter()
+            .map(|chunk_entry| {
+                let coord_index = chunk_entry.coord_index;
+                let entry = Entry::from(chunk_entry);
+
+                FrameEntry {
+                    coord_index,
+                    entry_format: entry.entry_format,
+                    curves: entry.curves,
+                }
+            })
+            .collect();
+
+        Self {
+            struct_info: Default::default(),
+            version: chunk.version,
+            frame: chunk.frame,
+            unk: chunk.unk,
+            entries,
+        }
+    }
+}
+
+impl<'a> TryFrom<NuccChunkConverter<'a>> for Box<NuccChunkAnmStrmFrame> {
+    type Error = NuccError;
+
+    fn try_from(converter: NuccChunkConverter) -> Result<Self, Self::Error> {
+        let NuccChunkConverter(boxed, _, _) = converter;
+        let frame = boxed
+            .downcast::<NuccAnmStrmFrame>()
+            .map(|s| *s)
+            .ok()
+            .unwrap();
+
+        let mut chunk = NuccChunkAnmStrmFrame::default();
+        chunk.version = frame.version;
+        chunk.frame = frame.frame;
+        chunk.unk = frame.unk;
+        chunk.entries = frame
+            .entries
+            .into_iter()
+            .map(|e| {
+                ChunkEntry::try_from(EntryChunkConverter(e.coord_index, e.entry_format, e.curves))
+            })
+            .collect::<Result<_, _>>()?;
+
+        chunk
+            .update()
+            .expect("Could not update AnmStrmFrame chunk.");
+        Ok(Box::new(chunk))
+    }
+}
+
+impl NuccStruct for NuccAnmStrmFrame {
+    fn chunk_type(&self) -> NuccChunkType {
+        NuccChunkType::NuccChunkAnmStrmFrame
+    }
+
+    fn version(&self) -> u16 {
+        self.version
+    }
+
+    fn set_version(&mut self, version: u16) {
+        self.version = version;
+    }
+}
@@ -0,0 +1,91 @@
+use super::effect::{Color, EffectColors};
+use super::*;
+
+use crate::error::NuccError;
+
+/// A textured quad that always faces the camera.
+#[derive(Clone)]
+pub struct NuccBillboard {
+    pub struct_info: NuccStructInfo,
+    pub version: u16,
+
+    pub material: NuccStructInfo,
+    pub coord: NuccStructReference,
+
+    pub width: f32,
+    pub height: f32,
+
+    /// Relative to the coord.
+    pub offset: [f32; 3],
+    pub rotation: f32,
+
+    pub color: Color,
+    pub flags: u32,
+}
+
+impl_nucc_info!(NuccBillboard, struct_info);
+
+impl EffectColors for NuccBillboard {
+    fn colors_mut(&mut self) -> Vec<&mut Color> {
+        vec![&mut self.color]
+    }
+}
+
+impl<'a> TryFrom<NuccStructConverter<'a>> for NuccBillboard {
+    type Error = NuccError;
+
+    fn try_from(converter: NuccStructConverter<'a>) -> Result<Self, Self::Error> {
+        let NuccStructConverter(boxed, struct_infos, struct_references) = converter;
+        let chunk = boxed
+            .downcast::<NuccChunkBillboard>()
+            .map(|c| *c)
+            .ok()
+            .unwrap();
+
+        Ok(Self {
+            struct_info: Default::default(),
+            version: chunk.version,
+            material: resolve_index(struct_infos, chunk.material_index as usize)?,
+            coord: resolve_index(struct_references, chunk.coord_index as usize)?,
+            width: chunk.width,
+            height: chunk.height,
+            offset: chunk.offset,
+            rotation: chunk.rotation,
+            color: chunk.color,
+            flags: chunk.flags,
+        })
+    }
+}
+
+impl<'a> From<NuccChunkConverter<'a>> for Box<NuccChunkBillboard> {
+    fn from(converter: NuccChunkConverter) -> Self {
+        let NuccChunkConverter(boxed, struct_infos, struct_references) = converter;
+        let billboard = boxed.downcast::<NuccBillboard>().map(|s| *s).ok().unwrap();
+
+        Box::new(NuccChunkBillboard {
+            version: billboard.version,
+            material_index: index_of(struct_infos, &billboard.material),
+            coord_index: index_of(struct_references, &billboard.coord),
+            width: billboard.width,
+            height: billboard.height,
+            offset: billboard.offset,
+            rotation: billboard.rotation,
+            color: billboard.color,
+            flags: billboard.flags,
+        })
+    }
+}
+
+impl NuccStruct for NuccBillboard {
+    fn chunk_type(&self) -> NuccChunkType {
+        NuccChunkType::NuccChunkBillboard
+    }
+
+    fn version(&self) -> u16 {
+        self.version
+    }
+
+    fn set_version(&mut self, version: u16) {
+        self.version = version;
+    }
+}
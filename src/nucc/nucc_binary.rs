@@ -1,9 +1,16 @@
-use deku::{ctx::Endian, DekuUpdate};
-use strum::IntoEnumIterator;
+use deku::DekuUpdate;
 
-use xfbin_nucc_binary::{
+#[cfg(feature = "binary-types")]
+use deku::ctx::Endian;
+
+#[cfg(feature = "binary-types")]
+use crate::binary::{
     NuccBinaryParsed, NuccBinaryParsedReader, NuccBinaryParsedWriter, NuccBinaryType,
 };
+#[cfg(feature = "binary-types")]
+use crate::error::NuccError;
+#[cfg(feature = "binary-types")]
+use crate::game::GameProfile;
 
 use super::*;
 
@@ -16,6 +23,7 @@ pub struct NuccBinary {
 
 impl_nucc_info!(NuccBinary, struct_info);
 
+#[cfg(feature = "binary-types")]
 impl NuccBinary {
     pub fn get_binary_type(&self) -> Option<(NuccBinaryType, Endian)> {
         for binary_type in NuccBinaryType::iter() {
@@ -34,17 +42,61 @@ impl NuccBinary {
         binary_type_opt: Option<(NuccBinaryType, Endian)>,
         endianness: Option<Endian>,
         version: usize,
-    ) -> Option<Box<dyn NuccBinaryParsed>> {
-        binary_type_opt
+    ) -> Result<Box<dyn NuccBinaryParsed>, NuccError> {
+        let (b_type, endian) = binary_type_opt
             .or_else(|| self.get_binary_type())
-            .map(|(b_type, endian)| {
-                NuccBinaryParsedReader(b_type, &self.data, endianness.unwrap_or(endian), version)
-                    .into()
-            })
+            .ok_or(NuccError::BinaryTypeNotFound)?;
+
+        NuccBinaryParsedReader(b_type, &self.data, endianness.unwrap_or(endian), version).try_into()
+    }
+
+    /// Finds the first version that parses the data and writes it back to the same bytes.
+    /// Falls back to the first version that parses without failing, or None if no version does.
+    pub fn detect_version(&self, binary_type: NuccBinaryType, endian: Endian) -> Option<usize> {
+        let version_count = binary_type.version_options().len().max(1);
+        let mut parsable = None;
+
+        for version in 0..version_count {
+            let parsed: Box<dyn NuccBinaryParsed> =
+                match NuccBinaryParsedReader(binary_type, &self.data, endian, version).try_into() {
+                    Ok(parsed) => parsed,
+                    Err(_) => continue,
+                };
+
+            match Vec::<u8>::try_from(NuccBinaryParsedWriter(parsed, endian, version)) {
+                Ok(data) if data == self.data => return Some(version),
+                Ok(_) => {
+                    parsable.get_or_insert(version);
+                }
+                Err(_) => (),
+            }
+        }
+
+        parsable
     }
 
-    pub fn update_data(&mut self, nucc_parsed: Box<dyn NuccBinaryParsed>, version: usize) {
-        self.data = NuccBinaryParsedWriter(nucc_parsed, version).into();
+    /// Parses the data with the version that the game uses for the binary's type.
+    pub fn parse_data_for_game(
+        &self,
+        profile: &GameProfile,
+    ) -> Result<Box<dyn NuccBinaryParsed>, NuccError> {
+        let (binary_type, endian) = self
+            .get_binary_type()
+            .ok_or(NuccError::BinaryTypeNotFound)?;
+
+        let version = profile.binary_version(&binary_type);
+        NuccBinaryParsedReader(binary_type, &self.data, endian, version).try_into()
+    }
+
+    /// Writes the parsed binary to the chunk's data. The data is left unchanged if it can't be written.
+    pub fn update_data(
+        &mut self,
+        nucc_parsed: Box<dyn NuccBinaryParsed>,
+        endian: Endian,
+        version: usize,
+    ) -> Result<(), NuccError> {
+        self.data = NuccBinaryParsedWriter(nucc_parsed, endian, version).try_into()?;
+        Ok(())
     }
 }
 
@@ -90,4 +142,8 @@ impl NuccStruct for NuccBinary {
     fn version(&self) -> u16 {
         self.version
     }
+
+    fn set_version(&mut self, version: u16) {
+        self.version = version;
+    }
 }
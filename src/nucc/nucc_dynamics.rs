@@ -0,0 +1,183 @@
+use deku::DekuUpdate;
+use serde::{Deserialize, Serialize};
+
+use super::*;
+
+use crate::error::NuccError;
+use crate::nucc_chunk::{CollisionSphere as ChunkCollisionSphere, SpringGroup as ChunkSpringGroup};
+
+/// Physics parameters of the bones in a spring group.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SpringParams {
+    pub bounciness: f32,
+    pub elasticity: f32,
+    pub stiffness: f32,
+    pub movement: f32,
+}
+
+/// A chain of `bone_count` bones that starts from the coord.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpringGroup {
+    pub coord: NuccStructReference,
+    pub bone_count: u16,
+    pub params: SpringParams,
+    pub flags: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CollisionSphere {
+    pub coord: NuccStructReference,
+    pub offset: [f32; 3],
+    pub scale: [f32; 3],
+
+    /// Whether the sphere only collides with the attached spring groups.
+    pub attach_groups: bool,
+
+    /// Indices of the spring groups in the NuccDynamics.
+    pub attached_groups: Vec<u16>,
+    pub unk: u16,
+}
+
+#[derive(Clone)]
+pub struct NuccDynamics {
+    pub struct_info: NuccStructInfo,
+    pub version: u16,
+
+    /// The clump that the coords belong to.
+    pub clump: NuccStructInfo,
+
+    pub spring_groups: Vec<SpringGroup>,
+    pub collision_spheres: Vec<CollisionSphere>,
+}
+
+impl_nucc_info!(NuccDynamics, struct_info);
+
+impl NuccDynamics {
+    /// Returns the spring group that starts from the coord with the given name.
+    pub fn spring_group_mut(&mut self, coord_name: &str) -> Option<&mut SpringGroup> {
+        self.spring_groups
+            .iter_mut()
+            .find(|group| group.coord.0 == coord_name)
+    }
+
+    /// Returns the collision spheres that are attached to the coord with the given name.
+    pub fn collision_spheres_of(&self, coord_name: &str) -> Vec<&CollisionSphere> {
+        self.collision_spheres
+            .iter()
+            .filter(|sphere| sphere.coord.0 == coord_name)
+            .collect()
+    }
+}
+
+impl<'a> TryFrom<NuccStructConverter<'a>> for NuccDynamics {
+    type Error = NuccError;
+
+    fn try_from(converter: NuccStructConverter<'a>) -> Result<Self, Self::Error> {
+        let NuccStructConverter(boxed, struct_infos, struct_references) = converter;
+        let chunk = boxed
+            .downcast::<NuccChunkDynamics>()
+            .map(|c| *c)
+            .ok()
+            .unwrap();
+
+        let spring_groups = chunk
+            .spring_groups
+            .into_iter()
+            .map(|group| {
+                Ok(SpringGroup {
+                    coord: resolve_index(struct_references, group.coord_index as usize)?,
+                    bone_count: group.bone_count,
+                    params: SpringParams {
+                        bounciness: group.bounciness,
+                        elasticity: group.elasticity,
+                        stiffness: group.stiffness,
+                        movement: group.movement,
+                    },
+                    flags: group.flags,
+                })
+            })
+            .collect::<Result<_, NuccError>>()?;
+
+        let mut attached_group_indices = chunk.attached_group_indices.into_iter();
+        let collision_spheres = chunk
+            .collision_spheres
+            .into_iter()
+            .map(|sphere| {
+                Ok(CollisionSphere {
+                    coord: resolve_index(struct_references, sphere.coord_index as usize)?,
+                    offset: sphere.offset,
+                    scale: sphere.scale,
+                    attach_groups: sphere.attach_groups != 0,
+                    attached_groups: attached_group_indices
+                        .by_ref()
+                        .take(sphere.attached_group_count as usize)
+                        .collect(),
+                    unk: sphere.unk,
+                })
+            })
+            .collect::<Result<_, NuccError>>()?;
+
+        Ok(Self {
+            struct_info: Default::default(),
+            version: chunk.version,
+            clump: resolve_index(struct_infos, chunk.clump_index as usize)?,
+            spring_groups,
+            collision_spheres,
+        })
+    }
+}
+
+impl<'a> From<NuccChunkConverter<'a>> for Box<NuccChunkDynamics> {
+    fn from(converter: NuccChunkConverter) -> Self {
+        let NuccChunkConverter(boxed, struct_infos, struct_references) = converter;
+        let dynamics = boxed.downcast::<NuccDynamics>().map(|s| *s).ok().unwrap();
+
+        let mut chunk = NuccChunkDynamics::default();
+        chunk.version = dynamics.version;
+        chunk.clump_index = index_of(struct_infos, &dynamics.clump);
+
+        chunk.spring_groups = dynamics
+            .spring_groups
+            .iter()
+            .map(|group| ChunkSpringGroup {
+                bounciness: group.params.bounciness,
+                elasticity: group.params.elasticity,
+                stiffness: group.params.stiffness,
+                movement: group.params.movement,
+                coord_index: index_of(struct_references, &group.coord) as u16,
+                bone_count: group.bone_count,
+                flags: group.flags,
+            })
+            .collect();
+
+        for sphere in dynamics.collision_spheres {
+            chunk.collision_spheres.push(ChunkCollisionSphere {
+                offset: sphere.offset,
+                scale: sphere.scale,
+                coord_index: index_of(struct_references, &sphere.coord) as u16,
+                attach_groups: sphere.attach_groups as u16,
+                attached_group_count: sphere.attached_groups.len() as u16,
+                unk: sphere.unk,
+            });
+
+            chunk.attached_group_indices.extend(sphere.attached_groups);
+        }
+
+        chunk.update().expect("Could not update Dynamics chunk.");
+        Box::new(chunk)
+    }
+}
+
+impl NuccStruct for NuccDynamics {
+    fn chunk_type(&self) -> NuccChunkType {
+        NuccChunkType::NuccChunkDynamics
+    }
+
+    fn version(&self) -> u16 {
+        self.version
+    }
+
+    fn set_version(&mut self, version: u16) {
+        self.version = version;
+    }
+}
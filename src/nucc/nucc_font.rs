@@ -0,0 +1,359 @@
+use std::{fmt::Write as _, fs, path::Path};
+
+use deku::DekuUpdate;
+use hashbrown::HashMap;
+use serde::{Deserialize, Serialize};
+
+use super::nucc_sprite::SpriteUv;
+use super::*;
+
+use crate::error::NuccError;
+use crate::nucc_chunk::Glyph as ChunkGlyph;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Glyph {
+    /// Unicode code point of the character.
+    pub code: u32,
+
+    /// Index in the textures of the NuccFont.
+    pub texture: usize,
+    pub uv: SpriteUv,
+
+    /// In pixels.
+    pub x_offset: i16,
+    pub y_offset: i16,
+    pub advance: i16,
+
+    pub unk: u16,
+}
+
+impl Glyph {
+    pub fn char(&self) -> Option<char> {
+        char::from_u32(self.code)
+    }
+}
+
+#[derive(Clone)]
+pub struct NuccFont {
+    pub struct_info: NuccStructInfo,
+    pub version: u16,
+
+    pub line_height: u16,
+    pub base: u16,
+
+    /// Size of every texture, in pixels.
+    pub texture_width: u16,
+    pub texture_height: u16,
+
+    pub textures: Vec<NuccStructInfo>,
+    pub glyphs: Vec<Glyph>,
+    pub unk: u16,
+}
+
+impl_nucc_info!(NuccFont, struct_info);
+
+/// Splits a line of a BMFont text descriptor into its tag and its key/value pairs.
+fn parse_bmfont_line(line: &str) -> Option<(&str, HashMap<&str, &str>)> {
+    let line = line.trim();
+    let (tag, mut rest) = line.split_once(' ').unwrap_or((line, ""));
+
+    let mut values = HashMap::new();
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            break;
+        }
+
+        let (key, after_key) = rest.split_once('=')?;
+        let (value, after_value) = match after_key.strip_prefix('"') {
+            Some(quoted) => {
+                let (value, after) = quoted.split_once('"')?;
+                (value, after)
+            }
+            None => after_key.split_once(' ').unwrap_or((after_key, "")),
+        };
+
+        values.insert(key.trim(), value);
+        rest = after_value;
+    }
+
+    Some((tag, values))
+}
+
+impl NuccFont {
+    pub fn glyph(&self, c: char) -> Option<&Glyph> {
+        self.glyphs.iter().find(|glyph| glyph.code == c as u32)
+    }
+
+    /// Adds the glyph, or replaces the glyph with the same code.
+    pub fn set_glyph(&mut self, glyph: Glyph) {
+        match self.glyphs.iter_mut().find(|g| g.code == glyph.code) {
+            Some(existing) => *existing = glyph,
+            None => self.glyphs.push(glyph),
+        }
+    }
+
+    /// Returns a BMFont text descriptor of the font. Pages are named after the textures' chunk names.
+    pub fn to_bmfont(&self) -> String {
+        let width = self.texture_width as f32;
+        let height = self.texture_height as f32;
+
+        let mut output = String::new();
+        writeln!(
+            output,
+            "info face=\"{}\" size={} unicode=1",
+            self.struct_info.chunk_name, self.line_height
+        )
+        .unwrap();
+        writeln!(
+            output,
+            "common lineHeight={} base={} scaleW={} scaleH={} pages={}",
+            self.line_height,
+            self.base,
+            self.texture_width,
+            self.texture_height,
+            self.textures.len()
+        )
+        .unwrap();
+
+        for (i, texture) in self.textures.iter().enumerate() {
+            writeln!(output, "page id={i} file=\"{}.png\"", texture.chunk_name).unwrap();
+        }
+
+        writeln!(output, "chars count={}", self.glyphs.len()).unwrap();
+        for glyph in self.glyphs.iter() {
+            let uv = glyph.uv;
+            writeln!(
+                output,
+                "char id={} x={} y={} width={} height={} xoffset={} yoffset={} xadvance={} page={} chnl=15",
+                glyph.code,
+                (uv.left * width).round(),
+                (uv.top * height).round(),
+                ((uv.right - uv.left) * width).round(),
+                ((uv.bottom - uv.top) * height).round(),
+                glyph.x_offset,
+                glyph.y_offset,
+                glyph.advance,
+                glyph.texture
+            )
+            .unwrap();
+        }
+
+        output
+    }
+
+    /// Replaces the glyphs and metrics with the ones in a BMFont text descriptor.
+    /// Pages are matched to the existing textures by their id. Pages without a texture are added as
+    /// textures named after their file, which should exist as nuccChunkTexture chunks when repacking.
+    pub fn set_bmfont(&mut self, text: &str) -> Result<(), NuccError> {
+        fn value<T: std::str::FromStr>(
+            values: &HashMap<&str, &str>,
+            key: &str,
+        ) -> Result<T, NuccError> {
+            values
+                .get(key)
+                .and_then(|v| v.parse().ok())
+                .ok_or(NuccError::InvalidBmFont)
+        }
+
+        let mut common = None;
+        let mut pages = vec![];
+        let mut glyphs = vec![];
+
+        for (tag, values) in text.lines().filter_map(parse_bmfont_line) {
+            match tag {
+                "common" => {
+                    common = Some((
+                        value::<u16>(&values, "lineHeight")?,
+                        value::<u16>(&values, "base")?,
+                        value::<u16>(&values, "scaleW")?,
+                        value::<u16>(&values, "scaleH")?,
+                    ))
+                }
+                "page" => pages.push((
+                    value::<usize>(&values, "id")?,
+                    values.get("file").copied().unwrap_or_default().to_string(),
+                )),
+                "char" => glyphs.push(values),
+                _ => (),
+            }
+        }
+
+        let (line_height, base, texture_width, texture_height) =
+            common.ok_or(NuccError::InvalidBmFont)?;
+        if texture_width == 0 || texture_height == 0 {
+            return Err(NuccError::InvalidBmFont);
+        }
+
+        pages.sort_by_key(|(id, _)| *id);
+        if pages.iter().enumerate().any(|(i, (id, _))| i != *id) {
+            return Err(NuccError::InvalidBmFont);
+        }
+
+        let file_path = self
+            .textures
+            .first()
+            .map(|texture| texture.file_path.clone())
+            .unwrap_or_default();
+
+        // The font is only changed once the whole file is read
+        let mut textures = self.textures.clone();
+        for (id, file) in pages.iter().skip(textures.len()) {
+            let chunk_name = Path::new(file)
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .map(String::from)
+                .unwrap_or_else(|| format!("{}_{id}", self.struct_info.chunk_name));
+
+            textures.push(NuccStructInfo {
+                chunk_name,
+                file_path: file_path.clone(),
+                chunk_type: "nuccChunkTexture".to_string(),
+            });
+        }
+        textures.truncate(pages.len());
+
+        let width = texture_width as f32;
+        let height = texture_height as f32;
+
+        let mut new_glyphs = Vec::with_capacity(glyphs.len());
+        for values in glyphs.iter() {
+            let code = value::<u32>(&values, "id")?;
+            let texture = value::<usize>(&values, "page")?;
+            if texture >= textures.len() {
+                return Err(NuccError::InvalidBmFont);
+            }
+
+            let x = value::<f32>(&values, "x")?;
+            let y = value::<f32>(&values, "y")?;
+
+            new_glyphs.push(Glyph {
+                code,
+                texture,
+                uv: SpriteUv {
+                    left: x / width,
+                    top: y / height,
+                    right: (x + value::<f32>(&values, "width")?) / width,
+                    bottom: (y + value::<f32>(&values, "height")?) / height,
+                },
+                x_offset: value(&values, "xoffset")?,
+                y_offset: value(&values, "yoffset")?,
+                advance: value(&values, "xadvance")?,
+                unk: self
+                    .glyphs
+                    .iter()
+                    .find(|g| g.code == code)
+                    .map(|g| g.unk)
+                    .unwrap_or_default(),
+            });
+        }
+
+        self.line_height = line_height;
+        self.base = base;
+        self.texture_width = texture_width;
+        self.texture_height = texture_height;
+        self.textures = textures;
+        self.glyphs = new_glyphs;
+
+        Ok(())
+    }
+
+    pub fn export_bmfont(&self, file_path: &dyn AsRef<Path>) -> Result<(), NuccError> {
+        fs::write(file_path, self.to_bmfont()).map_err(|_| NuccError::IoError)
+    }
+
+    pub fn import_bmfont(&mut self, file_path: &dyn AsRef<Path>) -> Result<(), NuccError> {
+        let text = fs::read_to_string(file_path).map_err(|_| NuccError::IoError)?;
+        self.set_bmfont(&text)
+    }
+}
+
+impl<'a> TryFrom<NuccStructConverter<'a>> for NuccFont {
+    type Error = NuccError;
+
+    fn try_from(converter: NuccStructConverter<'a>) -> Result<Self, Self::Error> {
+        let NuccStructConverter(boxed, struct_infos, _) = converter;
+        let chunk = boxed.downcast::<NuccChunkFont>().map(|c| *c).ok().unwrap();
+
+        Ok(Self {
+            struct_info: Default::default(),
+            version: chunk.version,
+            line_height: chunk.line_height,
+            base: chunk.base,
+            texture_width: chunk.texture_width,
+            texture_height: chunk.texture_height,
+            textures: chunk
+                .texture_indices
+                .iter()
+                .map(|&i| resolve_index(struct_infos, i as usize))
+                .collect::<Result<_, _>>()?,
+            glyphs: chunk
+                .glyphs
+                .into_iter()
+                .map(|glyph| Glyph {
+                    code: glyph.code,
+                    texture: glyph.texture_slot as usize,
+                    uv: glyph.uv.into(),
+                    x_offset: glyph.x_offset,
+                    y_offset: glyph.y_offset,
+                    advance: glyph.advance,
+                    unk: glyph.unk,
+                })
+                .collect(),
+            unk: chunk.unk,
+        })
+    }
+}
+
+impl<'a> From<NuccChunkConverter<'a>> for Box<NuccChunkFont> {
+    fn from(converter: NuccChunkConverter) -> Self {
+        let NuccChunkConverter(boxed, struct_infos, _) = converter;
+        let font = boxed.downcast::<NuccFont>().map(|s| *s).ok().unwrap();
+
+        let mut chunk = NuccChunkFont::default();
+        chunk.version = font.version;
+        chunk.line_height = font.line_height;
+        chunk.base = font.base;
+        chunk.texture_width = font.texture_width;
+        chunk.texture_height = font.texture_height;
+        chunk.unk = font.unk;
+
+        chunk.texture_indices = font
+            .textures
+            .into_iter()
+            .map(|texture| index_of(struct_infos, &texture))
+            .collect();
+
+        chunk.glyphs = font
+            .glyphs
+            .into_iter()
+            .map(|glyph| ChunkGlyph {
+                code: glyph.code,
+                texture_slot: glyph.texture as u16,
+                unk: glyph.unk,
+                uv: glyph.uv.into(),
+                x_offset: glyph.x_offset,
+                y_offset: glyph.y_offset,
+                advance: glyph.advance,
+                padding: 0,
+            })
+            .collect();
+
+        chunk.update().expect("Could not update Font chunk.");
+        Box::new(chunk)
+    }
+}
+
+impl NuccStruct for NuccFont {
+    fn chunk_type(&self) -> NuccChunkType {
+        NuccChunkType::NuccChunkFont
+    }
+
+    fn version(&self) -> u16 {
+        self.version
+    }
+
+    fn set_version(&mut self, version: u16) {
+        self.version = version;
+    }
+}
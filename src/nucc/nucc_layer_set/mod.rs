@@ -0,0 +1,167 @@
+pub mod render;
+
+use deku::DekuUpdate;
+use serde::{Deserialize, Serialize};
+
+use super::*;
+
+use crate::error::NuccError;
+use crate::nucc_chunk::Layer as ChunkLayer;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Layer {
+    pub name: String,
+
+    /// The NuccSprite or NuccSprite2 drawn by the layer. Layers without a sprite only group their children.
+    pub sprite: Option<NuccStructInfo>,
+
+    /// Index of the parent in the layers of the NuccLayerSet.
+    pub parent: Option<usize>,
+
+    /// Relative to the parent, in pixels.
+    pub position: [f32; 2],
+    pub scale: [f32; 2],
+
+    /// In degrees, clockwise.
+    pub rotation: f32,
+
+    /// Multiplied with the sprite's color and inherited by the children.
+    pub color: [f32; 4],
+    pub visible: bool,
+}
+
+#[derive(Clone)]
+pub struct NuccLayerSet {
+    pub struct_info: NuccStructInfo,
+    pub version: u16,
+
+    /// Size of the canvas, in pixels.
+    pub width: u32,
+    pub height: u32,
+
+    pub layers: Vec<Layer>,
+}
+
+impl_nucc_info!(NuccLayerSet, struct_info);
+
+impl NuccLayerSet {
+    pub fn layer_index(&self, name: &str) -> Option<usize> {
+        self.layers.iter().position(|layer| layer.name == name)
+    }
+
+    /// Returns the indices of the layers without a parent, in drawing order.
+    pub fn roots(&self) -> Vec<usize> {
+        self.children_of(None)
+    }
+
+    /// Returns the indices of the layer's children, in drawing order.
+    pub fn children(&self, index: usize) -> Vec<usize> {
+        self.children_of(Some(index))
+    }
+
+    fn children_of(&self, parent: Option<usize>) -> Vec<usize> {
+        self.layers
+            .iter()
+            .enumerate()
+            .filter(|(_, layer)| layer.parent == parent)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Returns the sprites of all layers, without duplicates.
+    pub fn sprites(&self) -> Vec<&NuccStructInfo> {
+        let mut sprites = vec![];
+        for sprite in self.layers.iter().filter_map(|layer| layer.sprite.as_ref()) {
+            if !sprites.contains(&sprite) {
+                sprites.push(sprite);
+            }
+        }
+
+        sprites
+    }
+}
+
+impl<'a> TryFrom<NuccStructConverter<'a>> for NuccLayerSet {
+    type Error = NuccError;
+
+    fn try_from(converter: NuccStructConverter<'a>) -> Result<Self, Self::Error> {
+        let NuccStructConverter(boxed, struct_infos, _) = converter;
+        let chunk = boxed
+            .downcast::<NuccChunkLayerSet>()
+            .map(|c| *c)
+            .ok()
+            .unwrap();
+
+        let layers = chunk
+            .layers
+            .into_iter()
+            .map(|layer| {
+                Ok(Layer {
+                    name: layer.name.into(),
+                    sprite: (layer.sprite_index >= 0)
+                        .then(|| resolve_index(struct_infos, layer.sprite_index as usize))
+                        .transpose()?,
+                    parent: (layer.parent_index >= 0).then(|| layer.parent_index as usize),
+                    position: layer.position,
+                    scale: layer.scale,
+                    rotation: layer.rotation,
+                    color: layer.color,
+                    visible: layer.visible != 0,
+                })
+            })
+            .collect::<Result<_, NuccError>>()?;
+
+        Ok(Self {
+            struct_info: Default::default(),
+            version: chunk.version,
+            width: chunk.width,
+            height: chunk.height,
+            layers,
+        })
+    }
+}
+
+impl<'a> From<NuccChunkConverter<'a>> for Box<NuccChunkLayerSet> {
+    fn from(converter: NuccChunkConverter) -> Self {
+        let NuccChunkConverter(boxed, struct_infos, _) = converter;
+        let layer_set = boxed.downcast::<NuccLayerSet>().map(|s| *s).ok().unwrap();
+
+        let mut chunk = NuccChunkLayerSet::default();
+        chunk.version = layer_set.version;
+        chunk.width = layer_set.width;
+        chunk.height = layer_set.height;
+        chunk.layers = layer_set
+            .layers
+            .into_iter()
+            .map(|layer| ChunkLayer {
+                name: layer.name.into(),
+                sprite_index: layer
+                    .sprite
+                    .map_or(-1, |sprite| index_of(struct_infos, &sprite) as i32),
+                parent_index: layer.parent.map_or(-1, |parent| parent as i32),
+                position: layer.position,
+                scale: layer.scale,
+                rotation: layer.rotation,
+                color: layer.color,
+                visible: layer.visible as u32,
+            })
+            .collect();
+
+        chunk.update().expect("Could not update LayerSet chunk.");
+        Box::new(chunk)
+    }
+}
+
+impl NuccStruct for NuccLayerSet {
+    fn chunk_type(&self) -> NuccChunkType {
+        NuccChunkType::NuccChunkLayerSet
+    }
+
+    fn version(&self) -> u16 {
+        self.version
+    }
+
+    fn set_version(&mut self, version: u16) {
+        self.version = version;
+    }
+}
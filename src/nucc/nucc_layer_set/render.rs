@@ -0,0 +1,332 @@
+use std::{fs::File, io::BufWriter, path::Path};
+
+use hashbrown::HashMap;
+
+use super::{Layer, NuccLayerSet, NuccSprite, NuccSprite2, NuccStructInfo};
+
+use crate::error::NuccError;
+use crate::nucc::nucc_sprite::{SpriteRect, SpriteUv};
+use crate::xfbin::Xfbin;
+
+/// An image with 8-bit RGBA pixels, stored row by row from the top.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RgbaImage {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl RgbaImage {
+    /// Creates a fully transparent image.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![0; width as usize * height as usize * 4],
+        }
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let offset = (y as usize * self.width as usize + x as usize) * 4;
+        self.pixels[offset..offset + 4].try_into().unwrap()
+    }
+
+    pub fn read_png(file_path: &dyn AsRef<Path>) -> Result<Self, NuccError> {
+        let file = File::open(file_path).map_err(|_| NuccError::IoError)?;
+
+        let mut decoder = png::Decoder::new(file);
+        decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+
+        let mut reader = decoder.read_info().map_err(|_| NuccError::InvalidImage)?;
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let info = reader
+            .next_frame(&mut buffer)
+            .map_err(|_| NuccError::InvalidImage)?;
+        buffer.truncate(info.buffer_size());
+
+        let pixels = match info.color_type {
+            png::ColorType::Rgba => buffer,
+            png::ColorType::Rgb => buffer
+                .chunks_exact(3)
+                .flat_map(|p| [p[0], p[1], p[2], 255])
+                .collect(),
+            png::ColorType::GrayscaleAlpha => buffer
+                .chunks_exact(2)
+                .flat_map(|p| [p[0], p[0], p[0], p[1]])
+                .collect(),
+            png::ColorType::Grayscale => buffer.iter().flat_map(|&p| [p, p, p, 255]).collect(),
+            png::ColorType::Indexed => return Err(NuccError::InvalidImage),
+        };
+
+        Ok(Self {
+            width: info.width,
+            height: info.height,
+            pixels,
+        })
+    }
+
+    pub fn write_png(&self, file_path: &dyn AsRef<Path>) -> Result<(), NuccError> {
+        let file = File::create(file_path).map_err(|_| NuccError::IoError)?;
+
+        let mut encoder = png::Encoder::new(BufWriter::new(file), self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&self.pixels))
+            .map_err(|_| NuccError::InvalidImage)
+    }
+
+    /// Returns the texel at the normalized coordinates, without filtering.
+    fn sample(&self, u: f32, v: f32) -> [f32; 4] {
+        let x = ((u * self.width as f32) as i64).clamp(0, self.width as i64 - 1) as u32;
+        let y = ((v * self.height as f32) as i64).clamp(0, self.height as i64 - 1) as u32;
+
+        self.pixel(x, y).map(|c| c as f32 / 255.0)
+    }
+
+    /// Alpha blends the color over the pixel.
+    fn blend(&mut self, x: u32, y: u32, [r, g, b, a]: [f32; 4]) {
+        let offset = (y as usize * self.width as usize + x as usize) * 4;
+        let pixel = &mut self.pixels[offset..offset + 4];
+
+        let dst_alpha = pixel[3] as f32 / 255.0;
+        let out_alpha = a + dst_alpha * (1.0 - a);
+        if out_alpha <= 0.0 {
+            return;
+        }
+
+        for (channel, src) in pixel.iter_mut().take(3).zip([r, g, b]) {
+            let dst = *channel as f32 / 255.0;
+            let out = (src * a + dst * dst_alpha * (1.0 - a)) / out_alpha;
+            *channel = (out.clamp(0.0, 1.0) * 255.0).round() as u8;
+        }
+
+        pixel[3] = (out_alpha.clamp(0.0, 1.0) * 255.0).round() as u8;
+    }
+}
+
+/// 2D affine transform, as the columns of a 3x2 matrix.
+#[derive(Clone, Copy)]
+struct Transform([f32; 6]);
+
+impl Transform {
+    const IDENTITY: Self = Self([1.0, 0.0, 0.0, 1.0, 0.0, 0.0]);
+
+    fn from_layer(layer: &Layer) -> Self {
+        let (sin, cos) = layer.rotation.to_radians().sin_cos();
+        let [sx, sy] = layer.scale;
+        let [tx, ty] = layer.position;
+
+        Self([cos * sx, sin * sx, -sin * sy, cos * sy, tx, ty])
+    }
+
+    fn then(&self, child: &Self) -> Self {
+        let [a, b, c, d, tx, ty] = self.0;
+        let [ca, cb, cc, cd, ctx, cty] = child.0;
+
+        Self([
+            a * ca + c * cb,
+            b * ca + d * cb,
+            a * cc + c * cd,
+            b * cc + d * cd,
+            a * ctx + c * cty + tx,
+            b * ctx + d * cty + ty,
+        ])
+    }
+
+    fn apply(&self, [x, y]: [f32; 2]) -> [f32; 2] {
+        let [a, b, c, d, tx, ty] = self.0;
+        [a * x + c * y + tx, b * x + d * y + ty]
+    }
+
+    fn inverse(&self) -> Option<Self> {
+        let [a, b, c, d, tx, ty] = self.0;
+        let det = a * d - b * c;
+        if det.abs() < f32::EPSILON {
+            return None;
+        }
+
+        let (ia, ib, ic, id) = (d / det, -b / det, -c / det, a / det);
+        Some(Self([
+            ia,
+            ib,
+            ic,
+            id,
+            -(ia * tx + ic * ty),
+            -(ib * tx + id * ty),
+        ]))
+    }
+}
+
+/// The part of a sprite that is drawn by a layer.
+struct SpriteQuad<'a> {
+    texture: &'a NuccStructInfo,
+    rect: SpriteRect,
+    uv: SpriteUv,
+    color: [f32; 4],
+}
+
+impl NuccLayerSet {
+    /// Composites the visible layers into an image of the layer set's size. Sprites are looked up in the
+    /// xfbin, and their textures in the given images. Sprites or textures that are missing are skipped.
+    /// For NuccSprite2, only the first frame is drawn.
+    pub fn render(
+        &self,
+        xfbin: &Xfbin,
+        textures: &HashMap<NuccStructInfo, RgbaImage>,
+    ) -> RgbaImage {
+        let mut quads = HashMap::new();
+        for nucc_struct in xfbin.pages.iter().flat_map(|page| page.structs.iter()) {
+            if let Some(sprite) = nucc_struct.downcast_ref::<NuccSprite>() {
+                quads.insert(
+                    &sprite.struct_info,
+                    SpriteQuad {
+                        texture: &sprite.texture,
+                        rect: sprite.rect,
+                        uv: sprite.uv,
+                        color: sprite.color,
+                    },
+                );
+            } else if let Some(sprite) = nucc_struct.downcast_ref::<NuccSprite2>() {
+                let frame = sprite.frames.first();
+                if let Some((frame, texture)) =
+                    frame.and_then(|f| sprite.textures.get(f.texture).map(|t| (f, t)))
+                {
+                    quads.insert(
+                        &sprite.struct_info,
+                        SpriteQuad {
+                            texture,
+                            rect: frame.rect,
+                            uv: frame.uv,
+                            color: sprite.color,
+                        },
+                    );
+                }
+            }
+        }
+
+        let mut image = RgbaImage::new(self.width, self.height);
+        for root in self.roots() {
+            self.render_layer(
+                root,
+                Transform::IDENTITY,
+                [1.0; 4],
+                &quads,
+                textures,
+                &mut image,
+            );
+        }
+
+        image
+    }
+
+    pub fn render_png(
+        &self,
+        xfbin: &Xfbin,
+        textures: &HashMap<NuccStructInfo, RgbaImage>,
+        file_path: &dyn AsRef<Path>,
+    ) -> Result<(), NuccError> {
+        self.render(xfbin, textures).write_png(file_path)
+    }
+
+    fn render_layer(
+        &self,
+        index: usize,
+        parent_transform: Transform,
+        parent_color: [f32; 4],
+        quads: &HashMap<&NuccStructInfo, SpriteQuad>,
+        textures: &HashMap<NuccStructInfo, RgbaImage>,
+        image: &mut RgbaImage,
+    ) {
+        let layer = &self.layers[index];
+        if !layer.visible {
+            return;
+        }
+
+        let transform = parent_transform.then(&Transform::from_layer(layer));
+        let mut color = parent_color;
+        for (c, layer_c) in color.iter_mut().zip(layer.color) {
+            *c *= layer_c;
+        }
+
+        let quad = layer.sprite.as_ref().and_then(|sprite| quads.get(sprite));
+        if let Some((quad, texture)) = quad.and_then(|q| textures.get(q.texture).map(|t| (q, t))) {
+            draw_quad(quad, texture, &transform, color, image);
+        }
+
+        for child in self.children(index) {
+            self.render_layer(child, transform, color, quads, textures, image);
+        }
+    }
+}
+
+fn draw_quad(
+    quad: &SpriteQuad,
+    texture: &RgbaImage,
+    transform: &Transform,
+    color: [f32; 4],
+    image: &mut RgbaImage,
+) {
+    let inverse = match transform.inverse() {
+        Some(inverse) => inverse,
+        None => return,
+    };
+
+    let SpriteRect {
+        x,
+        y,
+        width,
+        height,
+    } = quad.rect;
+    if width == 0.0 || height == 0.0 {
+        return;
+    }
+
+    let corners = [
+        [x, y],
+        [x + width, y],
+        [x, y + height],
+        [x + width, y + height],
+    ]
+    .map(|corner| transform.apply(corner));
+
+    let min_x = corners
+        .iter()
+        .map(|c| c[0])
+        .fold(f32::MAX, f32::min)
+        .floor()
+        .max(0.0) as u32;
+    let min_y = corners
+        .iter()
+        .map(|c| c[1])
+        .fold(f32::MAX, f32::min)
+        .floor()
+        .max(0.0) as u32;
+    let max_x = (corners.iter().map(|c| c[0]).fold(f32::MIN, f32::max).ceil() as i64)
+        .clamp(0, image.width as i64) as u32;
+    let max_y = (corners.iter().map(|c| c[1]).fold(f32::MIN, f32::max).ceil() as i64)
+        .clamp(0, image.height as i64) as u32;
+
+    for py in min_y..max_y {
+        for px in min_x..max_x {
+            let [lx, ly] = inverse.apply([px as f32 + 0.5, py as f32 + 0.5]);
+
+            let s = (lx - x) / width;
+            let t = (ly - y) / height;
+            if !(0.0..1.0).contains(&s) || !(0.0..1.0).contains(&t) {
+                continue;
+            }
+
+            let uv = quad.uv;
+            let texel = texture.sample(
+                uv.left + s * (uv.right - uv.left),
+                uv.top + t * (uv.bottom - uv.top),
+            );
+
+            let pixel = std::array::from_fn(|i| texel[i] * color[i] * quad.color[i]);
+            image.blend(px, py, pixel);
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use deku::DekuUpdate;
+use serde::{Deserialize, Serialize};
+
+use super::effect::{Color, EffectColors};
+use super::*;
+
+use crate::error::NuccError;
+use crate::nucc_chunk::LensFlareElement as ChunkLensFlareElement;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LensFlareElement {
+    /// Index in the textures of the NuccLensFlare.
+    pub texture: usize,
+
+    /// Position on the line from the light (0.0) to the center of the screen (1.0).
+    pub position: f32,
+    pub size: f32,
+
+    /// In degrees.
+    pub rotation: f32,
+
+    pub color: Color,
+    pub unk: u16,
+}
+
+/// Flare elements drawn on screen while the light is visible.
+///
+/// Only the association to a light is parsed. Effects linked to a nuccChunkCamera are not, and camera
+/// chunks are kept as unknown chunks.
+#[derive(Clone)]
+pub struct NuccLensFlare {
+    pub struct_info: NuccStructInfo,
+    pub version: u16,
+
+    /// The light chunk (i.e. nuccChunkLightPoint) the flare is attached to.
+    pub light: NuccStructInfo,
+
+    pub intensity: f32,
+    pub fade_distance: f32,
+    pub flags: u32,
+
+    pub textures: Vec<NuccStructInfo>,
+    pub elements: Vec<LensFlareElement>,
+}
+
+impl_nucc_info!(NuccLensFlare, struct_info);
+
+impl NuccLensFlare {
+    /// Returns the elements that use the texture with the given chunk name.
+    pub fn elements_of(&self, texture_name: &str) -> Vec<&LensFlareElement> {
+        self.elements
+            .iter()
+            .filter(|element| {
+                self.textures
+                    .get(element.texture)
+                    .map_or(false, |texture| texture.chunk_name == texture_name)
+            })
+            .collect()
+    }
+}
+
+impl EffectColors for NuccLensFlare {
+    fn colors_mut(&mut self) -> Vec<&mut Color> {
+        self.elements
+            .iter_mut()
+            .map(|element| &mut element.color)
+            .collect()
+    }
+}
+
+impl<'a> TryFrom<NuccStructConverter<'a>> for NuccLensFlare {
+    type Error = NuccError;
+
+    fn try_from(converter: NuccStructConverter<'a>) -> Result<Self, Self::Error> {
+        let NuccStructConverter(boxed, struct_infos, _) = converter;
+        let chunk = boxed
+            .downcast::<NuccChunkLensFlare>()
+            .map(|c| *c)
+            .ok()
+            .unwrap();
+
+        Ok(Self {
+            struct_info: Default::default(),
+            version: chunk.version,
+            light: resolve_index(struct_infos, chunk.light_index as usize)?,
+            intensity: chunk.intensity,
+            fade_distance: chunk.fade_distance,
+            flags: chunk.flags,
+            textures: chunk
+                .texture_indices
+                .iter()
+                .map(|&i| resolve_index(struct_infos, i as usize))
+                .collect::<Result<_, _>>()?,
+            elements: chunk
+                .elements
+                .into_iter()
+                .map(|element| LensFlareElement {
+                    texture: element.texture_slot as usize,
+                    position: element.position,
+                    size: element.size,
+                    rotation: element.rotation,
+                    color: element.color,
+                    unk: element.unk,
+                })
+                .collect(),
+        })
+    }
+}
+
+impl<'a> From<NuccChunkConverter<'a>> for Box<NuccChunkLensFlare> {
+    fn from(converter: NuccChunkConverter) -> Self {
+        let NuccChunkConverter(boxed, struct_infos, _) = converter;
+        let lens_flare = boxed.downcast::<NuccLensFlare>().map(|s| *s).ok().unwrap();
+
+        let mut chunk = NuccChunkLensFlare::default();
+        chunk.version = lens_flare.version;
+        chunk.light_index = index_of(struct_infos, &lens_flare.light);
+        chunk.intensity = lens_flare.intensity;
+        chunk.fade_distance = lens_flare.fade_distance;
+        chunk.flags = lens_flare.flags;
+
+        chunk.texture_indices = lens_flare
+            .textures
+            .iter()
+            .map(|texture| index_of(struct_infos, texture))
+            .collect();
+
+        chunk.elements = lens_flare
+            .elements
+            .into_iter()
+            .map(|element| ChunkLensFlareElement {
+                texture_slot: element.texture as u16,
+                unk: element.unk,
+                position: element.position,
+                size: element.size,
+                rotation: element.rotation,
+                color: element.color,
+            })
+            .collect();
+
+        chunk.update().expect("Could not update LensFlare chunk.");
+        Box::new(chunk)
+    }
+}
+
+impl NuccStruct for NuccLensFlare {
+    fn chunk_type(&self) -> NuccChunkType {
+        NuccChunkType::NuccChunkLensFlare
+    }
+
+    fn version(&self) -> u16 {
+        self.version
+    }
+
+    fn set_version(&mut self, version: u16) {
+        self.version = version;
+    }
+}
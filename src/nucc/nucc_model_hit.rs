@@ -0,0 +1,204 @@
+use std::path::Path;
+
+use deku::DekuUpdate;
+use serde::{Deserialize, Serialize};
+
+use super::*;
+
+use crate::error::NuccError;
+use crate::mesh::Mesh;
+use crate::nucc_chunk::HitSection as ChunkHitSection;
+
+/// A group of collision triangles that share the same flags.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct HitSection {
+    pub collision_flags: [u8; 4],
+    pub unk: u32,
+
+    pub triangles: Vec<[[f32; 3]; 3]>,
+}
+
+#[derive(Clone)]
+pub struct NuccModelHit {
+    pub struct_info: NuccStructInfo,
+    pub version: u16,
+
+    pub sections: Vec<HitSection>,
+}
+
+impl_nucc_info!(NuccModelHit, struct_info);
+
+impl NuccModelHit {
+    /// Returns a mesh for each section, named `<chunk_name>_<section_index>_<collision_flags>`.
+    pub fn to_meshes(&self) -> Vec<Mesh> {
+        self.sections
+            .iter()
+            .enumerate()
+            .map(|(i, section)| {
+                let flags = section
+                    .collision_flags
+                    .iter()
+                    .map(|f| format!("{f:02x}"))
+                    .collect::<String>();
+
+                Mesh::from_triangles(
+                    &format!("{}_{i}_{flags}", self.struct_info.chunk_name),
+                    &section.triangles,
+                )
+            })
+            .collect()
+    }
+
+    /// Replaces the sections with the given meshes. The flags are taken from the mesh names if
+    /// they were exported with `to_meshes`, otherwise they are kept from the section at the same index.
+    pub fn set_meshes(&mut self, meshes: &[Mesh]) -> Result<(), NuccError> {
+        let mut sections = Vec::with_capacity(meshes.len());
+
+        for (i, mesh) in meshes.iter().enumerate() {
+            if mesh.indices.len() % 3 != 0
+                || mesh
+                    .indices
+                    .iter()
+                    .any(|&index| index as usize >= mesh.positions.len())
+            {
+                return Err(NuccError::InvalidMesh);
+            }
+
+            let mut section = self.sections.get(i).cloned().unwrap_or_default();
+            if let Some(flags) = Self::flags_from_name(&mesh.name) {
+                section.collision_flags = flags;
+            }
+
+            section.triangles = mesh.triangles().collect();
+            sections.push(section);
+        }
+
+        self.sections = sections;
+        Ok(())
+    }
+
+    fn flags_from_name(name: &str) -> Option<[u8; 4]> {
+        let flags = name.rsplit('_').next()?;
+        if flags.len() != 8 {
+            return None;
+        }
+
+        let mut collision_flags = [0; 4];
+        for (i, flag) in collision_flags.iter_mut().enumerate() {
+            *flag = u8::from_str_radix(flags.get(i * 2..i * 2 + 2)?, 16).ok()?;
+        }
+
+        Some(collision_flags)
+    }
+
+    pub fn export_obj(&self, file_path: &dyn AsRef<Path>) -> Result<(), NuccError> {
+        let mut output = vec![];
+        Mesh::write_obj(&self.to_meshes(), &mut output).map_err(|_| NuccError::InvalidObj)?;
+        std::fs::write(file_path, output).map_err(|_| NuccError::IoError)
+    }
+
+    pub fn export_glb(&self, file_path: &dyn AsRef<Path>) -> Result<(), NuccError> {
+        std::fs::write(file_path, Mesh::to_glb(&self.to_meshes())?).map_err(|_| NuccError::IoError)
+    }
+
+    /// Replaces the sections with the meshes of an OBJ or glTF file.
+    pub fn import_mesh(&mut self, file_path: &dyn AsRef<Path>) -> Result<(), NuccError> {
+        let meshes = Mesh::read_file(file_path)?;
+        self.set_meshes(&meshes)
+    }
+}
+
+impl<'a> TryFrom<NuccStructConverter<'a>> for NuccModelHit {
+    type Error = NuccError;
+
+    fn try_from(converter: NuccStructConverter<'a>) -> Result<Self, Self::Error> {
+        let NuccStructConverter(boxed, _, _) = converter;
+        let chunk = boxed
+            .downcast::<NuccChunkModelHit>()
+            .map(|c| *c)
+            .ok()
+            .unwrap();
+
+        // Every vertex has to belong to a triangle of a section, so none are lost on repack
+        let section_vertex_count = chunk
+            .sections
+            .iter()
+            .map(|section| section.vertex_count as usize)
+            .sum::<usize>();
+
+        if section_vertex_count != chunk.vertices.len()
+            || chunk
+                .sections
+                .iter()
+                .any(|section| section.vertex_count % 3 != 0)
+        {
+            return Err(NuccError::InvalidMesh);
+        }
+
+        let mut vertices = chunk.vertices.into_iter();
+        let sections = chunk
+            .sections
+            .into_iter()
+            .map(|section| {
+                let section_vertices = vertices
+                    .by_ref()
+                    .take(section.vertex_count as usize)
+                    .collect::<Vec<_>>();
+
+                HitSection {
+                    collision_flags: section.collision_flags,
+                    unk: section.unk,
+                    triangles: section_vertices
+                        .chunks_exact(3)
+                        .map(|t| [t[0], t[1], t[2]])
+                        .collect(),
+                }
+            })
+            .collect();
+
+        Ok(Self {
+            struct_info: Default::default(),
+            version: chunk.version,
+            sections,
+        })
+    }
+}
+
+impl<'a> From<NuccChunkConverter<'a>> for Box<NuccChunkModelHit> {
+    fn from(converter: NuccChunkConverter) -> Self {
+        let NuccChunkConverter(boxed, _, _) = converter;
+        let model_hit = boxed.downcast::<NuccModelHit>().map(|s| *s).ok().unwrap();
+
+        let mut chunk = NuccChunkModelHit::default();
+        chunk.version = model_hit.version;
+
+        for section in model_hit.sections {
+            chunk.sections.push(ChunkHitSection {
+                vertex_count: section.triangles.len() as u32 * 3,
+                collision_flags: section.collision_flags,
+                unk: section.unk,
+            });
+
+            chunk
+                .vertices
+                .extend(section.triangles.into_iter().flatten());
+        }
+
+        chunk.update().expect("Could not update ModelHit chunk.");
+        Box::new(chunk)
+    }
+}
+
+impl NuccStruct for NuccModelHit {
+    fn chunk_type(&self) -> NuccChunkType {
+        NuccChunkType::NuccChunkModelHit
+    }
+
+    fn version(&self) -> u16 {
+        self.version
+    }
+
+    fn set_version(&mut self, version: u16) {
+        self.version = version;
+    }
+}
@@ -0,0 +1,172 @@
+use deku::DekuUpdate;
+use hashbrown::HashMap;
+
+use super::*;
+
+use crate::error::NuccError;
+use crate::mesh::Mesh;
+use crate::nucc_chunk::BatchPrimitive as ChunkBatchPrimitive;
+pub use crate::nucc_chunk::PrimitiveType;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchPrimitive {
+    pub primitive_type: PrimitiveType,
+
+    /// Range in the indices of the NuccPrimitiveVertex.
+    pub index_start: u32,
+    pub index_count: u32,
+
+    pub unk: u16,
+}
+
+#[derive(Clone)]
+pub struct NuccModelPrimitiveBatch {
+    pub struct_info: NuccStructInfo,
+    pub version: u16,
+
+    /// The NuccPrimitiveVertex that the primitives are drawn from.
+    pub vertex_buffer: NuccStructInfo,
+    pub material: NuccStructInfo,
+
+    pub primitives: Vec<BatchPrimitive>,
+}
+
+impl_nucc_info!(NuccModelPrimitiveBatch, struct_info);
+
+impl NuccModelPrimitiveBatch {
+    /// Returns a mesh for each primitive, named `<chunk_name>_<primitive_index>`.
+    /// Only the vertices that are used by a primitive are included in its mesh.
+    pub fn to_meshes(&self, vertex_buffer: &NuccPrimitiveVertex) -> Result<Vec<Mesh>, NuccError> {
+        self.primitives
+            .iter()
+            .enumerate()
+            .map(|(i, primitive)| {
+                let start = primitive.index_start as usize;
+                let indices = vertex_buffer
+                    .indices
+                    .get(start..start + primitive.index_count as usize)
+                    .ok_or(NuccError::InvalidMesh)?;
+
+                let mut mesh = Mesh {
+                    name: format!("{}_{i}", self.struct_info.chunk_name),
+                    ..Default::default()
+                };
+
+                let mut vertex_map = HashMap::new();
+                let mut mesh_indices = Vec::with_capacity(indices.len());
+                for &index in indices {
+                    let vertex = vertex_buffer
+                        .vertices
+                        .get(index as usize)
+                        .ok_or(NuccError::InvalidMesh)?;
+
+                    mesh_indices.push(*vertex_map.entry(index).or_insert_with(|| {
+                        mesh.positions.push(vertex.position);
+                        mesh.normals.push(vertex.normal);
+                        mesh.uvs.push(vertex.uv);
+
+                        (mesh.positions.len() - 1) as u32
+                    }));
+                }
+
+                mesh.indices = match primitive.primitive_type {
+                    PrimitiveType::TriangleList => mesh_indices,
+                    PrimitiveType::TriangleStrip => mesh_indices
+                        .windows(3)
+                        .enumerate()
+                        // Every other triangle is flipped to keep the winding order
+                        .map(|(i, t)| {
+                            if i % 2 == 0 {
+                                [t[0], t[1], t[2]]
+                            } else {
+                                [t[1], t[0], t[2]]
+                            }
+                        })
+                        // Repeated indices are used to restart strips
+                        .filter(|t| t[0] != t[1] && t[1] != t[2] && t[0] != t[2])
+                        .flatten()
+                        .collect(),
+                };
+
+                Ok(mesh)
+            })
+            .collect()
+    }
+}
+
+impl<'a> TryFrom<NuccStructConverter<'a>> for NuccModelPrimitiveBatch {
+    type Error = NuccError;
+
+    fn try_from(converter: NuccStructConverter<'a>) -> Result<Self, Self::Error> {
+        let NuccStructConverter(boxed, struct_infos, _) = converter;
+        let chunk = boxed
+            .downcast::<NuccChunkModelPrimitiveBatch>()
+            .map(|c| *c)
+            .ok()
+            .unwrap();
+
+        let primitives = chunk
+            .primitives
+            .into_iter()
+            .map(|primitive| BatchPrimitive {
+                primitive_type: primitive.primitive_type,
+                index_start: primitive.index_start,
+                index_count: primitive.index_count,
+                unk: primitive.unk,
+            })
+            .collect();
+
+        Ok(Self {
+            struct_info: Default::default(),
+            version: chunk.version,
+            vertex_buffer: resolve_index(struct_infos, chunk.vertex_buffer_index as usize)?,
+            material: resolve_index(struct_infos, chunk.material_index as usize)?,
+            primitives,
+        })
+    }
+}
+
+impl<'a> From<NuccChunkConverter<'a>> for Box<NuccChunkModelPrimitiveBatch> {
+    fn from(converter: NuccChunkConverter) -> Self {
+        let NuccChunkConverter(boxed, struct_infos, _) = converter;
+        let batch = boxed
+            .downcast::<NuccModelPrimitiveBatch>()
+            .map(|s| *s)
+            .ok()
+            .unwrap();
+
+        let mut chunk = NuccChunkModelPrimitiveBatch::default();
+        chunk.version = batch.version;
+        chunk.vertex_buffer_index = index_of(struct_infos, &batch.vertex_buffer);
+        chunk.material_index = index_of(struct_infos, &batch.material);
+        chunk.primitives = batch
+            .primitives
+            .into_iter()
+            .map(|primitive| ChunkBatchPrimitive {
+                primitive_type: primitive.primitive_type,
+                unk: primitive.unk,
+                index_start: primitive.index_start,
+                index_count: primitive.index_count,
+            })
+            .collect();
+
+        chunk
+            .update()
+            .expect("Could not update ModelPrimitiveBatch chunk.");
+        Box::new(chunk)
+    }
+}
+
+impl NuccStruct for NuccModelPrimitiveBatch {
+    fn chunk_type(&self) -> NuccChunkType {
+        NuccChunkType::NuccChunkModelPrimitiveBatch
+    }
+
+    fn version(&self) -> u16 {
+        self.version
+    }
+
+    fn set_version(&mut self, version: u16) {
+        self.version = version;
+    }
+}
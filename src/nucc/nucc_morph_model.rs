@@ -0,0 +1,136 @@
+use std::path::Path;
+
+use deku::DekuUpdate;
+use hashbrown::HashMap;
+
+use super::*;
+
+use crate::error::NuccError;
+use crate::mesh::Mesh;
+use crate::xfbin::Xfbin;
+
+/// Blend shapes of a model. Each primitive of the base model has a NuccMorphPrimitive with the deltas of every target.
+#[derive(Clone)]
+pub struct NuccMorphModel {
+    pub struct_info: NuccStructInfo,
+    pub version: u16,
+
+    /// The nuccChunkModel the targets are applied to.
+    pub model: NuccStructInfo,
+
+    pub primitives: Vec<NuccStructInfo>,
+    pub target_names: Vec<String>,
+}
+
+impl_nucc_info!(NuccMorphModel, struct_info);
+
+impl NuccMorphModel {
+    pub fn target_index(&self, name: &str) -> Option<usize> {
+        self.target_names.iter().position(|n| n == name)
+    }
+
+    /// Returns the base meshes with the morph targets of their NuccMorphPrimitive, which is looked up in the xfbin.
+    /// The base meshes should be in the same order as the primitives.
+    pub fn to_meshes(&self, xfbin: &Xfbin, base_meshes: &[Mesh]) -> Result<Vec<Mesh>, NuccError> {
+        if base_meshes.len() != self.primitives.len() {
+            return Err(NuccError::InvalidMesh);
+        }
+
+        let morph_primitives = xfbin
+            .pages
+            .iter()
+            .flat_map(|page| page.structs.iter())
+            .filter_map(|s| s.downcast_ref::<NuccMorphPrimitive>())
+            .map(|p| (&p.struct_info, p))
+            .collect::<HashMap<_, _>>();
+
+        self.primitives
+            .iter()
+            .zip(base_meshes)
+            .map(|(primitive, base_mesh)| {
+                let morph_primitive = morph_primitives
+                    .get(primitive)
+                    .ok_or(NuccError::MorphPrimitiveNotFound)?;
+
+                let mut mesh = base_mesh.clone();
+                morph_primitive.apply_to_mesh(&mut mesh, &self.target_names)?;
+
+                Ok(mesh)
+            })
+            .collect()
+    }
+
+    /// Writes the base meshes with their morph targets as a binary glTF (.glb).
+    pub fn export_glb(
+        &self,
+        xfbin: &Xfbin,
+        base_meshes: &[Mesh],
+        file_path: &dyn AsRef<Path>,
+    ) -> Result<(), NuccError> {
+        let glb = Mesh::to_glb(&self.to_meshes(xfbin, base_meshes)?)?;
+        std::fs::write(file_path, glb).map_err(|_| NuccError::IoError)
+    }
+}
+
+impl<'a> TryFrom<NuccStructConverter<'a>> for NuccMorphModel {
+    type Error = NuccError;
+
+    fn try_from(converter: NuccStructConverter<'a>) -> Result<Self, Self::Error> {
+        let NuccStructConverter(boxed, struct_infos, _) = converter;
+        let chunk = boxed
+            .downcast::<NuccChunkMorphModel>()
+            .map(|c| *c)
+            .ok()
+            .unwrap();
+
+        Ok(Self {
+            struct_info: Default::default(),
+            version: chunk.version,
+            model: resolve_index(struct_infos, chunk.model_index as usize)?,
+            primitives: chunk
+                .primitive_indices
+                .iter()
+                .map(|&i| resolve_index(struct_infos, i as usize))
+                .collect::<Result<_, _>>()?,
+            target_names: chunk.target_names.into_iter().map(String::from).collect(),
+        })
+    }
+}
+
+impl<'a> From<NuccChunkConverter<'a>> for Box<NuccChunkMorphModel> {
+    fn from(converter: NuccChunkConverter) -> Self {
+        let NuccChunkConverter(boxed, struct_infos, _) = converter;
+        let morph_model = boxed.downcast::<NuccMorphModel>().map(|s| *s).ok().unwrap();
+
+        let mut chunk = NuccChunkMorphModel::default();
+        chunk.version = morph_model.version;
+        chunk.model_index = index_of(struct_infos, &morph_model.model);
+        chunk.primitive_indices = morph_model
+            .primitives
+            .iter()
+            .map(|primitive| index_of(struct_infos, primitive))
+            .collect();
+        chunk.target_names = morph_model
+            .target_names
+            .into_iter()
+            .map(Into::into)
+            .collect();
+
+        chunk.update().expect("Could not update MorphModel chunk.");
+        Box::new(chunk)
+    }
+}
+
+impl NuccStruct for NuccMorphModel {
+    fn chunk_type(&self) -> NuccChunkType {
+        NuccChunkType::NuccChunkMorphModel
+    }
+
+    fn version(&self) -> u16 {
+        self.version
+    }
+
+    fn set_version(&mut self, version: u16) {
+        self.version = version;
+    }
+}
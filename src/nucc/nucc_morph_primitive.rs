@@ -0,0 +1,184 @@
+use deku::DekuUpdate;
+use serde::{Deserialize, Serialize};
+
+use super::*;
+
+use crate::error::NuccError;
+use crate::mesh::{Mesh, MorphTarget as MeshMorphTarget};
+use crate::nucc_chunk::{MorphDelta as ChunkMorphDelta, MorphTarget as ChunkMorphTarget};
+
+/// Offset of a single vertex from its position in the base primitive.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MorphDelta {
+    pub vertex_index: u32,
+    pub position: [f32; 3],
+    pub normal: [f32; 3],
+}
+
+/// Deltas of a morph target, in the same order as the target names of the NuccMorphModel.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct MorphTarget {
+    pub deltas: Vec<MorphDelta>,
+}
+
+#[derive(Clone)]
+pub struct NuccMorphPrimitive {
+    pub struct_info: NuccStructInfo,
+    pub version: u16,
+
+    pub vertex_count: u32,
+    pub targets: Vec<MorphTarget>,
+}
+
+impl_nucc_info!(NuccMorphPrimitive, struct_info);
+
+impl NuccMorphPrimitive {
+    /// Replaces the morph targets of the mesh with the targets of the primitive.
+    /// The mesh should be the base primitive, with the same vertex order.
+    pub fn apply_to_mesh(&self, mesh: &mut Mesh, target_names: &[String]) -> Result<(), NuccError> {
+        if mesh.positions.len() != self.vertex_count as usize {
+            return Err(NuccError::InvalidMesh);
+        }
+
+        mesh.morph_targets = self
+            .targets
+            .iter()
+            .enumerate()
+            .map(|(i, target)| {
+                let mut morph_target = MeshMorphTarget {
+                    name: target_names
+                        .get(i)
+                        .cloned()
+                        .unwrap_or_else(|| format!("target_{i}")),
+                    position_deltas: vec![[0.0; 3]; mesh.positions.len()],
+                    normal_deltas: vec![[0.0; 3]; mesh.positions.len()],
+                };
+
+                for delta in target.deltas.iter() {
+                    let index = delta.vertex_index as usize;
+                    if index >= mesh.positions.len() {
+                        return Err(NuccError::InvalidMesh);
+                    }
+
+                    morph_target.position_deltas[index] = delta.position;
+                    morph_target.normal_deltas[index] = delta.normal;
+                }
+
+                Ok(morph_target)
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(())
+    }
+
+    /// Replaces the targets with the morph targets of the mesh. Vertices that are not moved are not stored.
+    pub fn set_from_mesh(&mut self, mesh: &Mesh) {
+        self.vertex_count = mesh.positions.len() as u32;
+        self.targets = mesh
+            .morph_targets
+            .iter()
+            .map(|target| MorphTarget {
+                deltas: target
+                    .position_deltas
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &position)| MorphDelta {
+                        vertex_index: i as u32,
+                        position,
+                        normal: target.normal_deltas.get(i).copied().unwrap_or_default(),
+                    })
+                    .filter(|delta| delta.position != [0.0; 3] || delta.normal != [0.0; 3])
+                    .collect(),
+            })
+            .collect();
+    }
+}
+
+impl<'a> From<NuccStructConverter<'a>> for NuccMorphPrimitive {
+    fn from(converter: NuccStructConverter<'a>) -> Self {
+        let NuccStructConverter(boxed, _, _) = converter;
+        let chunk = boxed
+            .downcast::<NuccChunkMorphPrimitive>()
+            .map(|c| *c)
+            .ok()
+            .unwrap();
+
+        let targets = chunk
+            .targets
+            .into_iter()
+            .map(|target| MorphTarget {
+                deltas: target
+                    .deltas
+                    .into_iter()
+                    .map(|delta| MorphDelta {
+                        vertex_index: delta.vertex_index,
+                        position: delta.position,
+                        normal: delta.normal,
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        Self {
+            struct_info: Default::default(),
+            version: chunk.version,
+            vertex_count: chunk.vertex_count,
+            targets,
+        }
+    }
+}
+
+impl<'a> From<NuccChunkConverter<'a>> for Box<NuccChunkMorphPrimitive> {
+    fn from(converter: NuccChunkConverter) -> Self {
+        let NuccChunkConverter(boxed, _, _) = converter;
+        let primitive = boxed
+            .downcast::<NuccMorphPrimitive>()
+            .map(|s| *s)
+            .ok()
+            .unwrap();
+
+        let mut chunk = NuccChunkMorphPrimitive::default();
+        chunk.version = primitive.version;
+        chunk.vertex_count = primitive.vertex_count;
+        chunk.targets = primitive
+            .targets
+            .into_iter()
+            .map(|target| {
+                let mut chunk_target = ChunkMorphTarget::default();
+                chunk_target.deltas = target
+                    .deltas
+                    .into_iter()
+                    .map(|delta| ChunkMorphDelta {
+                        vertex_index: delta.vertex_index,
+                        position: delta.position,
+                        normal: delta.normal,
+                    })
+                    .collect();
+
+                chunk_target
+                    .update()
+                    .expect("Could not update MorphPrimitive target.");
+                chunk_target
+            })
+            .collect();
+
+        chunk
+            .update()
+            .expect("Could not update MorphPrimitive chunk.");
+        Box::new(chunk)
+    }
+}
+
+impl NuccStruct for NuccMorphPrimitive {
+    fn chunk_type(&self) -> NuccChunkType {
+        NuccChunkType::NuccChunkMorphPrimitive
+    }
+
+    fn version(&self) -> u16 {
+        self.version
+    }
+
+    fn set_version(&mut self, version: u16) {
+        self.version = version;
+    }
+}
@@ -0,0 +1,100 @@
+use serde::{Deserialize, Serialize};
+
+use super::*;
+
+use crate::error::NuccError;
+
+/// Location of a nub relative to its parent coord.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct NubTransform {
+    pub position: [f32; 3],
+
+    /// Euler angles in degrees, in XYZ order.
+    pub rotation: [f32; 3],
+    pub scale: [f32; 3],
+}
+
+impl Default for NubTransform {
+    fn default() -> Self {
+        Self {
+            position: [0.0; 3],
+            rotation: [0.0; 3],
+            scale: [1.0; 3],
+        }
+    }
+}
+
+/// An attachment point for accessories (i.e. weapons and effects). The nub's name is its chunk name.
+#[derive(Clone)]
+pub struct NuccNub {
+    pub struct_info: NuccStructInfo,
+    pub version: u16,
+
+    /// The clump that the parent coord belongs to.
+    pub clump: NuccStructInfo,
+    pub parent: NuccStructReference,
+
+    pub transform: NubTransform,
+    pub unk: u16,
+}
+
+impl_nucc_info!(NuccNub, struct_info);
+
+impl NuccNub {
+    pub fn name(&self) -> &str {
+        &self.struct_info.chunk_name
+    }
+}
+
+impl<'a> TryFrom<NuccStructConverter<'a>> for NuccNub {
+    type Error = NuccError;
+
+    fn try_from(converter: NuccStructConverter<'a>) -> Result<Self, Self::Error> {
+        let NuccStructConverter(boxed, struct_infos, struct_references) = converter;
+        let chunk = boxed.downcast::<NuccChunkNub>().map(|c| *c).ok().unwrap();
+
+        Ok(Self {
+            struct_info: Default::default(),
+            version: chunk.version,
+            clump: resolve_index(struct_infos, chunk.clump_index as usize)?,
+            parent: resolve_index(struct_references, chunk.coord_index as usize)?,
+            transform: NubTransform {
+                position: chunk.position,
+                rotation: chunk.rotation,
+                scale: chunk.scale,
+            },
+            unk: chunk.unk,
+        })
+    }
+}
+
+impl<'a> From<NuccChunkConverter<'a>> for Box<NuccChunkNub> {
+    fn from(converter: NuccChunkConverter) -> Self {
+        let NuccChunkConverter(boxed, struct_infos, struct_references) = converter;
+        let nub = boxed.downcast::<NuccNub>().map(|s| *s).ok().unwrap();
+
+        Box::new(NuccChunkNub {
+            version: nub.version,
+            clump_index: index_of(struct_infos, &nub.clump),
+            coord_index: index_of(struct_references, &nub.parent) as u16,
+            unk: nub.unk,
+            position: nub.transform.position,
+            rotation: nub.transform.rotation,
+            scale: nub.transform.scale,
+        })
+    }
+}
+
+impl NuccStruct for NuccNub {
+    fn chunk_type(&self) -> NuccChunkType {
+        NuccChunkType::NuccChunkNub
+    }
+
+    fn version(&self) -> u16 {
+        self.version
+    }
+
+    fn set_version(&mut self, version: u16) {
+        self.version = version;
+    }
+}
@@ -0,0 +1,136 @@
+use serde::{Deserialize, Serialize};
+
+use super::effect::{Color, EffectColors};
+use super::*;
+
+use crate::error::NuccError;
+
+/// How particles are spawned by the emitter. Times are in seconds.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EmissionParams {
+    pub max_particles: u32,
+
+    /// Particles emitted per second.
+    pub rate: f32,
+    pub duration: f32,
+
+    pub lifetime: f32,
+    pub lifetime_variance: f32,
+
+    pub speed: f32,
+    pub speed_variance: f32,
+
+    /// Angle of the emission cone around the direction, in degrees.
+    pub spread_angle: f32,
+    pub direction: [f32; 3],
+    pub gravity: [f32; 3],
+}
+
+#[derive(Clone)]
+pub struct NuccParticle {
+    pub struct_info: NuccStructInfo,
+    pub version: u16,
+
+    pub material: NuccStructInfo,
+
+    /// The coord that particles are emitted from.
+    pub coord: NuccStructReference,
+
+    pub emission: EmissionParams,
+
+    /// Particle sizes and colors are interpolated over their lifetime.
+    pub start_size: f32,
+    pub end_size: f32,
+
+    pub start_color: Color,
+    pub end_color: Color,
+    pub flags: u32,
+}
+
+impl_nucc_info!(NuccParticle, struct_info);
+
+impl EffectColors for NuccParticle {
+    fn colors_mut(&mut self) -> Vec<&mut Color> {
+        vec![&mut self.start_color, &mut self.end_color]
+    }
+}
+
+impl<'a> TryFrom<NuccStructConverter<'a>> for NuccParticle {
+    type Error = NuccError;
+
+    fn try_from(converter: NuccStructConverter<'a>) -> Result<Self, Self::Error> {
+        let NuccStructConverter(boxed, struct_infos, struct_references) = converter;
+        let chunk = boxed
+            .downcast::<NuccChunkParticle>()
+            .map(|c| *c)
+            .ok()
+            .unwrap();
+
+        Ok(Self {
+            struct_info: Default::default(),
+            version: chunk.version,
+            material: resolve_index(struct_infos, chunk.material_index as usize)?,
+            coord: resolve_index(struct_references, chunk.coord_index as usize)?,
+            emission: EmissionParams {
+                max_particles: chunk.max_particles,
+                rate: chunk.emission_rate,
+                duration: chunk.emission_duration,
+                lifetime: chunk.lifetime,
+                lifetime_variance: chunk.lifetime_variance,
+                speed: chunk.speed,
+                speed_variance: chunk.speed_variance,
+                spread_angle: chunk.spread_angle,
+                direction: chunk.direction,
+                gravity: chunk.gravity,
+            },
+            start_size: chunk.start_size,
+            end_size: chunk.end_size,
+            start_color: chunk.start_color,
+            end_color: chunk.end_color,
+            flags: chunk.flags,
+        })
+    }
+}
+
+impl<'a> From<NuccChunkConverter<'a>> for Box<NuccChunkParticle> {
+    fn from(converter: NuccChunkConverter) -> Self {
+        let NuccChunkConverter(boxed, struct_infos, struct_references) = converter;
+        let particle = boxed.downcast::<NuccParticle>().map(|s| *s).ok().unwrap();
+        let emission = particle.emission;
+
+        Box::new(NuccChunkParticle {
+            version: particle.version,
+            material_index: index_of(struct_infos, &particle.material),
+            coord_index: index_of(struct_references, &particle.coord),
+            max_particles: emission.max_particles,
+            emission_rate: emission.rate,
+            emission_duration: emission.duration,
+            lifetime: emission.lifetime,
+            lifetime_variance: emission.lifetime_variance,
+            speed: emission.speed,
+            speed_variance: emission.speed_variance,
+            spread_angle: emission.spread_angle,
+            direction: emission.direction,
+            gravity: emission.gravity,
+            start_size: particle.start_size,
+            end_size: particle.end_size,
+            start_color: particle.start_color,
+            end_color: particle.end_color,
+            flags: particle.flags,
+        })
+    }
+}
+
+impl NuccStruct for NuccParticle {
+    fn chunk_type(&self) -> NuccChunkType {
+        NuccChunkType::NuccChunkParticle
+    }
+
+    fn version(&self) -> u16 {
+        self.version
+    }
+
+    fn set_version(&mut self, version: u16) {
+        self.version = version;
+    }
+}
@@ -0,0 +1,97 @@
+use deku::DekuUpdate;
+use serde::{Deserialize, Serialize};
+
+use super::*;
+
+use crate::nucc_chunk::PrimitiveVertex as ChunkPrimitiveVertex;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PrimitiveVertex {
+    pub position: [f32; 3],
+    pub normal: [f32; 3],
+    pub color: [u8; 4],
+    pub uv: [f32; 2],
+}
+
+/// Vertex and index buffers shared by the primitives of NuccModelPrimitiveBatch structs.
+#[derive(Clone)]
+pub struct NuccPrimitiveVertex {
+    pub struct_info: NuccStructInfo,
+    pub version: u16,
+
+    pub vertices: Vec<PrimitiveVertex>,
+    pub indices: Vec<u16>,
+}
+
+impl_nucc_info!(NuccPrimitiveVertex, struct_info);
+
+impl<'a> From<NuccStructConverter<'a>> for NuccPrimitiveVertex {
+    fn from(converter: NuccStructConverter<'a>) -> Self {
+        let NuccStructConverter(boxed, _, _) = converter;
+        let chunk = boxed
+            .downcast::<NuccChunkPrimitiveVertex>()
+            .map(|c| *c)
+            .ok()
+            .unwrap();
+
+        Self {
+            struct_info: Default::default(),
+            version: chunk.version,
+            vertices: chunk
+                .vertices
+                .into_iter()
+                .map(|vertex| PrimitiveVertex {
+                    position: vertex.position,
+                    normal: vertex.normal,
+                    color: vertex.color,
+                    uv: vertex.uv,
+                })
+                .collect(),
+            indices: chunk.indices,
+        }
+    }
+}
+
+impl<'a> From<NuccChunkConverter<'a>> for Box<NuccChunkPrimitiveVertex> {
+    fn from(converter: NuccChunkConverter) -> Self {
+        let NuccChunkConverter(boxed, _, _) = converter;
+        let primitive_vertex = boxed
+            .downcast::<NuccPrimitiveVertex>()
+            .map(|s| *s)
+            .ok()
+            .unwrap();
+
+        let mut chunk = NuccChunkPrimitiveVertex::default();
+        chunk.version = primitive_vertex.version;
+        chunk.vertices = primitive_vertex
+            .vertices
+            .into_iter()
+            .map(|vertex| ChunkPrimitiveVertex {
+                position: vertex.position,
+                normal: vertex.normal,
+                color: vertex.color,
+                uv: vertex.uv,
+            })
+            .collect();
+        chunk.indices = primitive_vertex.indices;
+
+        chunk
+            .update()
+            .expect("Could not update PrimitiveVertex chunk.");
+        Box::new(chunk)
+    }
+}
+
+impl NuccStruct for NuccPrimitiveVertex {
+    fn chunk_type(&self) -> NuccChunkType {
+        NuccChunkType::NuccChunkPrimitiveVertex
+    }
+
+    fn version(&self) -> u16 {
+        self.version
+    }
+
+    fn set_version(&mut self, version: u16) {
+        self.version = version;
+    }
+}
@@ -0,0 +1,128 @@
+use serde::{Deserialize, Serialize};
+
+use super::*;
+
+use crate::error::NuccError;
+
+/// A rectangle in pixels, relative to the sprite's origin.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SpriteRect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+/// Normalized texture coordinates of a sprite's corners.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SpriteUv {
+    pub left: f32,
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+}
+
+impl From<[f32; 4]> for SpriteRect {
+    fn from([x, y, width, height]: [f32; 4]) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+impl From<SpriteRect> for [f32; 4] {
+    fn from(rect: SpriteRect) -> Self {
+        [rect.x, rect.y, rect.width, rect.height]
+    }
+}
+
+impl From<[f32; 4]> for SpriteUv {
+    fn from([left, top, right, bottom]: [f32; 4]) -> Self {
+        Self {
+            left,
+            top,
+            right,
+            bottom,
+        }
+    }
+}
+
+impl From<SpriteUv> for [f32; 4] {
+    fn from(uv: SpriteUv) -> Self {
+        [uv.left, uv.top, uv.right, uv.bottom]
+    }
+}
+
+#[derive(Clone)]
+pub struct NuccSprite {
+    pub struct_info: NuccStructInfo,
+    pub version: u16,
+
+    pub texture: NuccStructInfo,
+
+    pub rect: SpriteRect,
+    pub uv: SpriteUv,
+
+    pub color: [f32; 4],
+    pub flags: u32,
+}
+
+impl_nucc_info!(NuccSprite, struct_info);
+
+impl<'a> TryFrom<NuccStructConverter<'a>> for NuccSprite {
+    type Error = NuccError;
+
+    fn try_from(converter: NuccStructConverter<'a>) -> Result<Self, Self::Error> {
+        let NuccStructConverter(boxed, struct_infos, _) = converter;
+        let chunk = boxed
+            .downcast::<NuccChunkSprite>()
+            .map(|c| *c)
+            .ok()
+            .unwrap();
+
+        Ok(Self {
+            struct_info: Default::default(),
+            version: chunk.version,
+            texture: resolve_index(struct_infos, chunk.texture_index as usize)?,
+            rect: chunk.rect.into(),
+            uv: chunk.uv.into(),
+            color: chunk.color,
+            flags: chunk.flags,
+        })
+    }
+}
+
+impl<'a> From<NuccChunkConverter<'a>> for Box<NuccChunkSprite> {
+    fn from(converter: NuccChunkConverter) -> Self {
+        let NuccChunkConverter(boxed, struct_infos, _) = converter;
+        let sprite = boxed.downcast::<NuccSprite>().map(|s| *s).ok().unwrap();
+
+        let texture_index = index_of(struct_infos, &sprite.texture);
+
+        Box::new(NuccChunkSprite {
+            version: sprite.version,
+            texture_index,
+            rect: sprite.rect.into(),
+            uv: sprite.uv.into(),
+            color: sprite.color,
+            flags: sprite.flags,
+        })
+    }
+}
+
+impl NuccStruct for NuccSprite {
+    fn chunk_type(&self) -> NuccChunkType {
+        NuccChunkType::NuccChunkSprite
+    }
+
+    fn version(&self) -> u16 {
+        self.version
+    }
+
+    fn set_version(&mut self, version: u16) {
+        self.version = version;
+    }
+}
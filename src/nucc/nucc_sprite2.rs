@@ -0,0 +1,114 @@
+use deku::DekuUpdate;
+use serde::{Deserialize, Serialize};
+
+use super::nucc_sprite::{SpriteRect, SpriteUv};
+use super::*;
+
+use crate::error::NuccError;
+use crate::nucc_chunk::Sprite2Frame as ChunkSprite2Frame;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SpriteFrame {
+    /// Index in the textures of the NuccSprite2.
+    pub texture: usize,
+
+    pub rect: SpriteRect,
+    pub uv: SpriteUv,
+    pub unk: u16,
+}
+
+/// A sprite with multiple frames, which can be on different textures.
+#[derive(Clone)]
+pub struct NuccSprite2 {
+    pub struct_info: NuccStructInfo,
+    pub version: u16,
+
+    pub textures: Vec<NuccStructInfo>,
+    pub frames: Vec<SpriteFrame>,
+
+    pub color: [f32; 4],
+    pub flags: u32,
+}
+
+impl_nucc_info!(NuccSprite2, struct_info);
+
+impl<'a> TryFrom<NuccStructConverter<'a>> for NuccSprite2 {
+    type Error = NuccError;
+
+    fn try_from(converter: NuccStructConverter<'a>) -> Result<Self, Self::Error> {
+        let NuccStructConverter(boxed, struct_infos, _) = converter;
+        let chunk = boxed
+            .downcast::<NuccChunkSprite2>()
+            .map(|c| *c)
+            .ok()
+            .unwrap();
+
+        Ok(Self {
+            struct_info: Default::default(),
+            version: chunk.version,
+            textures: chunk
+                .texture_indices
+                .iter()
+                .map(|&i| resolve_index(struct_infos, i as usize))
+                .collect::<Result<_, _>>()?,
+            frames: chunk
+                .frames
+                .into_iter()
+                .map(|frame| SpriteFrame {
+                    texture: frame.texture_slot as usize,
+                    rect: frame.rect.into(),
+                    uv: frame.uv.into(),
+                    unk: frame.unk,
+                })
+                .collect(),
+            color: chunk.color,
+            flags: chunk.flags,
+        })
+    }
+}
+
+impl<'a> From<NuccChunkConverter<'a>> for Box<NuccChunkSprite2> {
+    fn from(converter: NuccChunkConverter) -> Self {
+        let NuccChunkConverter(boxed, struct_infos, _) = converter;
+        let sprite = boxed.downcast::<NuccSprite2>().map(|s| *s).ok().unwrap();
+
+        let mut chunk = NuccChunkSprite2::default();
+        chunk.version = sprite.version;
+        chunk.texture_indices = sprite
+            .textures
+            .into_iter()
+            .map(|texture| index_of(struct_infos, &texture))
+            .collect();
+
+        chunk.frames = sprite
+            .frames
+            .into_iter()
+            .map(|frame| ChunkSprite2Frame {
+                texture_slot: frame.texture as u16,
+                unk: frame.unk,
+                rect: frame.rect.into(),
+                uv: frame.uv.into(),
+            })
+            .collect();
+
+        chunk.color = sprite.color;
+        chunk.flags = sprite.flags;
+
+        chunk.update().expect("Could not update Sprite2 chunk.");
+        Box::new(chunk)
+    }
+}
+
+impl NuccStruct for NuccSprite2 {
+    fn chunk_type(&self) -> NuccChunkType {
+        NuccChunkType::NuccChunkSprite2
+    }
+
+    fn version(&self) -> u16 {
+        self.version
+    }
+
+    fn set_version(&mut self, version: u16) {
+        self.version = version;
+    }
+}
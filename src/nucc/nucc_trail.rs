@@ -0,0 +1,94 @@
+use super::effect::{Color, EffectColors};
+use super::*;
+
+use crate::error::NuccError;
+
+/// A ribbon stretched between two coords that fades out over its lifetime (i.e. weapon swings).
+#[derive(Clone)]
+pub struct NuccTrail {
+    pub struct_info: NuccStructInfo,
+    pub version: u16,
+
+    pub material: NuccStructInfo,
+    pub start_coord: NuccStructReference,
+    pub end_coord: NuccStructReference,
+
+    pub segment_count: u32,
+
+    /// In seconds.
+    pub lifetime: f32,
+
+    pub start_width: f32,
+    pub end_width: f32,
+
+    pub start_color: Color,
+    pub end_color: Color,
+    pub flags: u32,
+}
+
+impl_nucc_info!(NuccTrail, struct_info);
+
+impl EffectColors for NuccTrail {
+    fn colors_mut(&mut self) -> Vec<&mut Color> {
+        vec![&mut self.start_color, &mut self.end_color]
+    }
+}
+
+impl<'a> TryFrom<NuccStructConverter<'a>> for NuccTrail {
+    type Error = NuccError;
+
+    fn try_from(converter: NuccStructConverter<'a>) -> Result<Self, Self::Error> {
+        let NuccStructConverter(boxed, struct_infos, struct_references) = converter;
+        let chunk = boxed.downcast::<NuccChunkTrail>().map(|c| *c).ok().unwrap();
+
+        Ok(Self {
+            struct_info: Default::default(),
+            version: chunk.version,
+            material: resolve_index(struct_infos, chunk.material_index as usize)?,
+            start_coord: resolve_index(struct_references, chunk.start_coord_index as usize)?,
+            end_coord: resolve_index(struct_references, chunk.end_coord_index as usize)?,
+            segment_count: chunk.segment_count,
+            lifetime: chunk.lifetime,
+            start_width: chunk.start_width,
+            end_width: chunk.end_width,
+            start_color: chunk.start_color,
+            end_color: chunk.end_color,
+            flags: chunk.flags,
+        })
+    }
+}
+
+impl<'a> From<NuccChunkConverter<'a>> for Box<NuccChunkTrail> {
+    fn from(converter: NuccChunkConverter) -> Self {
+        let NuccChunkConverter(boxed, struct_infos, struct_references) = converter;
+        let trail = boxed.downcast::<NuccTrail>().map(|s| *s).ok().unwrap();
+
+        Box::new(NuccChunkTrail {
+            version: trail.version,
+            material_index: index_of(struct_infos, &trail.material),
+            start_coord_index: index_of(struct_references, &trail.start_coord),
+            end_coord_index: index_of(struct_references, &trail.end_coord),
+            segment_count: trail.segment_count,
+            lifetime: trail.lifetime,
+            start_width: trail.start_width,
+            end_width: trail.end_width,
+            start_color: trail.start_color,
+            end_color: trail.end_color,
+            flags: trail.flags,
+        })
+    }
+}
+
+impl NuccStruct for NuccTrail {
+    fn chunk_type(&self) -> NuccChunkType {
+        NuccChunkType::NuccChunkTrail
+    }
+
+    fn version(&self) -> u16 {
+        self.version
+    }
+
+    fn set_version(&mut self, version: u16) {
+        self.version = version;
+    }
+}
@@ -49,4 +49,8 @@ impl NuccStruct for NuccUnknown {
     fn version(&self) -> u16 {
         self.version
     }
+
+    fn set_version(&mut self, version: u16) {
+        self.version = version;
+    }
 }
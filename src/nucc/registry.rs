@@ -0,0 +1,99 @@
+use std::sync::{OnceLock, RwLock};
+
+use deku::ctx::Endian;
+use hashbrown::HashMap;
+
+use crate::error::NuccError;
+use crate::nucc_chunk::{NuccChunk, NuccChunkUnknown};
+
+use super::{NuccStruct, NuccStructInfo, NuccStructReference};
+
+/// Creates a struct from the data of a chunk, which is in the byte order of the XFBIN's chunks.
+/// Struct infos and references are those of the chunk's page, so indices stored in the data can be resolved
+/// the same way as in the built-in structs.
+pub type NuccStructReader = fn(
+    data: &[u8],
+    endian: Endian,
+    version: u16,
+    struct_infos: &[NuccStructInfo],
+    struct_references: &[NuccStructReference],
+) -> Result<Box<dyn NuccStruct>, NuccError>;
+
+/// Writes a struct back to chunk data in the given byte order. Struct infos and references that the data points to
+/// should be added to the maps, which will be written as the page's chunk maps and references.
+pub type NuccStructWriter = fn(
+    nucc_struct: Box<dyn NuccStruct>,
+    endian: Endian,
+    struct_infos: &mut HashMap<NuccStructInfo, u32>,
+    struct_references: &mut HashMap<NuccStructReference, u32>,
+) -> Result<Vec<u8>, NuccError>;
+
+#[derive(Clone, Copy)]
+pub struct NuccStructHandler {
+    pub reader: NuccStructReader,
+    pub writer: NuccStructWriter,
+}
+
+fn registry() -> &'static RwLock<HashMap<String, NuccStructHandler>> {
+    static REGISTRY: OnceLock<RwLock<HashMap<String, NuccStructHandler>>> = OnceLock::new();
+    REGISTRY.get_or_init(Default::default)
+}
+
+/// Registers a reader/writer pair for a chunk type string (i.e. "nuccChunkDynamics").
+/// Registered types take priority over the built-in ones (except nuccChunkNull and nuccChunkPage),
+/// and replace any previously registered handler.
+///
+/// Structs created by the reader must return `NuccChunkType::NuccChunkCustom` as their chunk type,
+/// and the chunk type string of their struct info is used to find the writer.
+/// Errors returned by the reader or the writer fail reading or writing the whole XFBIN.
+pub fn register_chunk_type(chunk_type: &str, reader: NuccStructReader, writer: NuccStructWriter) {
+    registry()
+        .write()
+        .unwrap()
+        .insert(chunk_type.to_string(), NuccStructHandler { reader, writer });
+}
+
+/// Returns true if a handler was registered for the chunk type.
+pub fn unregister_chunk_type(chunk_type: &str) -> bool {
+    registry().write().unwrap().remove(chunk_type).is_some()
+}
+
+pub fn get_handler(chunk_type: &str) -> Option<NuccStructHandler> {
+    registry().read().unwrap().get(chunk_type).copied()
+}
+
+/// Reads an unknown chunk with the handler registered for its type, if there is one.
+pub(crate) fn read_struct(
+    chunk: &NuccChunkUnknown,
+    endian: Endian,
+    struct_infos: &[NuccStructInfo],
+    struct_references: &[NuccStructReference],
+) -> Option<Result<Box<dyn NuccStruct>, NuccError>> {
+    get_handler(&chunk.chunk_type).map(|handler| {
+        (handler.reader)(
+            &chunk.data,
+            endian,
+            chunk.version,
+            struct_infos,
+            struct_references,
+        )
+    })
+}
+
+/// Writes a struct of a registered type to an unknown chunk with its handler.
+pub(crate) fn write_struct(
+    nucc_struct: Box<dyn NuccStruct>,
+    endian: Endian,
+    struct_infos: &mut HashMap<NuccStructInfo, u32>,
+    struct_references: &mut HashMap<NuccStructReference, u32>,
+) -> Result<Box<dyn NuccChunk>, NuccError> {
+    let chunk_type = nucc_struct.struct_info().chunk_type.clone();
+    let version = nucc_struct.version();
+    let handler = get_handler(&chunk_type).ok_or(NuccError::ChunkHandlerNotFound)?;
+
+    Ok(Box::new(NuccChunkUnknown {
+        data: (handler.writer)(nucc_struct, endian, struct_infos, struct_references)?,
+        chunk_type,
+        version,
+    }))
+}
@@ -1,26 +1,71 @@
 mod nucc_chunk_anm;
+mod nucc_chunk_anm_strm;
+mod nucc_chunk_anm_strm_frame;
+mod nucc_chunk_billboard;
 mod nucc_chunk_binary;
+mod nucc_chunk_dynamics;
+mod nucc_chunk_font;
 mod nucc_chunk_index;
+mod nucc_chunk_layer_set;
+mod nucc_chunk_lens_flare;
+mod nucc_chunk_model_hit;
+mod nucc_chunk_model_primitive_batch;
+mod nucc_chunk_morph_model;
+mod nucc_chunk_morph_primitive;
+mod nucc_chunk_nub;
 mod nucc_chunk_null;
 mod nucc_chunk_page;
+mod nucc_chunk_particle;
+mod nucc_chunk_primitive_vertex;
+mod nucc_chunk_sprite;
+mod nucc_chunk_sprite2;
+mod nucc_chunk_trail;
 mod nucc_chunk_unknown;
 
 use deku::bitvec::{BitView, Msb0};
+use deku::ctx::Endian;
 use deku::prelude::*;
 use downcast_rs::{impl_downcast, Downcast};
 use std::str::FromStr;
 use strum_macros::{Display, EnumString};
 
+use super::nucc::registry;
 use super::utils::*;
 pub use nucc_chunk_anm::NuccChunkAnm;
+pub use nucc_chunk_anm_strm::NuccChunkAnmStrm;
+pub use nucc_chunk_anm_strm_frame::NuccChunkAnmStrmFrame;
+pub use nucc_chunk_billboard::NuccChunkBillboard;
 pub use nucc_chunk_binary::NuccChunkBinary;
+pub use nucc_chunk_dynamics::NuccChunkDynamics;
+pub use nucc_chunk_font::NuccChunkFont;
 pub use nucc_chunk_index::NuccChunkIndex;
+pub use nucc_chunk_layer_set::NuccChunkLayerSet;
+pub use nucc_chunk_lens_flare::NuccChunkLensFlare;
+pub use nucc_chunk_model_hit::NuccChunkModelHit;
+pub use nucc_chunk_model_primitive_batch::NuccChunkModelPrimitiveBatch;
+pub use nucc_chunk_morph_model::NuccChunkMorphModel;
+pub use nucc_chunk_morph_primitive::NuccChunkMorphPrimitive;
+pub use nucc_chunk_nub::NuccChunkNub;
 pub use nucc_chunk_null::NuccChunkNull;
 pub use nucc_chunk_page::NuccChunkPage;
+pub use nucc_chunk_particle::NuccChunkParticle;
+pub use nucc_chunk_primitive_vertex::NuccChunkPrimitiveVertex;
+pub use nucc_chunk_sprite::NuccChunkSprite;
+pub use nucc_chunk_sprite2::NuccChunkSprite2;
+pub use nucc_chunk_trail::NuccChunkTrail;
 pub use nucc_chunk_unknown::NuccChunkUnknown;
 
-pub use nucc_chunk_anm::{ClumpCoordIndex, ParentChildIndex};
+pub use nucc_chunk_anm::{Clump, ClumpCoordIndex, ParentChildIndex};
 pub use nucc_chunk_anm::{Curve, CurveFormat, CurveHeader, Entry, EntryFormat};
+pub use nucc_chunk_dynamics::{CollisionSphere, SpringGroup};
+pub use nucc_chunk_font::Glyph;
+pub use nucc_chunk_layer_set::Layer;
+pub use nucc_chunk_lens_flare::LensFlareElement;
+pub use nucc_chunk_model_hit::HitSection;
+pub use nucc_chunk_model_primitive_batch::{BatchPrimitive, PrimitiveType};
+pub use nucc_chunk_morph_primitive::{MorphDelta, MorphTarget};
+pub use nucc_chunk_primitive_vertex::PrimitiveVertex;
+pub use nucc_chunk_sprite2::Sprite2Frame;
 
 pub trait NuccChunk: Downcast {
     fn chunk_type(&self) -> NuccChunkType;
@@ -28,27 +73,29 @@ pub trait NuccChunk: Downcast {
 
     fn read_boxed<'a>(
         input: &'a DekuBitSlice,
+        endian: Endian,
         version: u16,
     ) -> Result<(&DekuBitSlice, Box<dyn NuccChunk>), DekuError>
     where
-        Self: Sized + DekuRead<'a, (deku::ctx::Endian, u16)>,
+        Self: Sized + DekuRead<'a, (Endian, u16)>,
     {
-        Self::read(input, (deku::ctx::Endian::Big, version))
+        Self::read(input, (endian, version))
             .map(|(rest, value)| (rest, Box::new(value) as Box<dyn NuccChunk>))
     }
 
     fn write_boxed(
         boxed: Box<dyn NuccChunk>,
         output: &mut DekuBitVec,
+        endian: Endian,
         version: u16,
     ) -> Result<(), DekuError>
     where
-        Self: Sized + DekuWrite<(deku::ctx::Endian, u16)>,
+        Self: Sized + DekuWrite<(Endian, u16)>,
     {
         Self::write(
             &boxed.downcast::<Self>().map(|c| *c).ok().unwrap(),
             output,
-            (deku::ctx::Endian::Big, version),
+            (endian, version),
         )
     }
 }
@@ -60,11 +107,29 @@ impl_downcast!(NuccChunk);
 #[strum(serialize_all = "camelCase")]
 pub enum NuccChunkType {
     NuccChunkUnknown, // Not an actual type
+    NuccChunkCustom,  // Registered at runtime, stored as NuccChunkUnknown
     NuccChunkNull,
     NuccChunkPage,
     NuccChunkIndex, // Does not exist as a chunk
     NuccChunkAnm,
+    NuccChunkAnmStrm,
+    NuccChunkAnmStrmFrame,
     NuccChunkBinary,
+    NuccChunkDynamics,
+    NuccChunkModelHit,
+    NuccChunkBillboard,
+    NuccChunkTrail,
+    NuccChunkParticle,
+    NuccChunkSprite,
+    NuccChunkSprite2,
+    NuccChunkLayerSet,
+    NuccChunkMorphModel,
+    NuccChunkMorphPrimitive,
+    NuccChunkModelPrimitiveBatch,
+    NuccChunkPrimitiveVertex,
+    NuccChunkNub,
+    NuccChunkFont,
+    NuccChunkLensFlare,
 }
 
 impl Default for NuccChunkType {
@@ -77,37 +142,183 @@ impl NuccChunkType {
     pub fn read_struct<'a>(
         input: &'a DekuBitSlice,
         chunk_type: &str,
+        endian: Endian,
         version: u16,
     ) -> Result<(&'a DekuBitSlice, Box<dyn NuccChunk>), DekuError> {
-        match NuccChunkType::from_str(chunk_type).unwrap_or_default() {
+        let nucc_chunk_type = match NuccChunkType::from_str(chunk_type).unwrap_or_default() {
+            // Page structure chunks can't be overridden
+            nucc_chunk_type @ (NuccChunkType::NuccChunkNull
+            | NuccChunkType::NuccChunkPage
+            | NuccChunkType::NuccChunkIndex) => nucc_chunk_type,
+
+            // Registered chunk types are read by their handler from the unknown chunk's data
+            _ if registry::get_handler(chunk_type).is_some() => NuccChunkType::NuccChunkUnknown,
+            nucc_chunk_type => nucc_chunk_type,
+        };
+
+        let unknown_chunk = || -> Box<dyn NuccChunk> {
+            Box::new(NuccChunkUnknown {
+                data: input.to_bitvec().into_vec(),
+                chunk_type: chunk_type.to_string(),
+                version,
+            })
+        };
+
+        let result = match nucc_chunk_type {
             NuccChunkType::NuccChunkNull => Ok((input, Box::new(NuccChunkNull(version)))),
-            NuccChunkType::NuccChunkPage => NuccChunkPage::read_boxed(input, version),
+            NuccChunkType::NuccChunkPage => NuccChunkPage::read_boxed(input, endian, version),
             NuccChunkType::NuccChunkIndex => Ok((input, Box::new(NuccChunkIndex))),
-            NuccChunkType::NuccChunkAnm => NuccChunkAnm::read_boxed(input, version),
-            NuccChunkType::NuccChunkBinary => NuccChunkBinary::read_boxed(input, version),
-            NuccChunkType::NuccChunkUnknown => Ok((
-                input,
-                Box::new(NuccChunkUnknown {
-                    data: input.to_bitvec().into_vec(),
-                    chunk_type: chunk_type.to_string(),
-                    version,
-                }),
-            )),
+            NuccChunkType::NuccChunkAnm => NuccChunkAnm::read_boxed(input, endian, version),
+            NuccChunkType::NuccChunkAnmStrm => NuccChunkAnmStrm::read_boxed(input, endian, version),
+            NuccChunkType::NuccChunkAnmStrmFrame => {
+                NuccChunkAnmStrmFrame::read_boxed(input, endian, version)
+            }
+            NuccChunkType::NuccChunkBinary => NuccChunkBinary::read_boxed(input, endian, version),
+            NuccChunkType::NuccChunkDynamics => {
+                NuccChunkDynamics::read_boxed(input, endian, version)
+            }
+            NuccChunkType::NuccChunkModelHit => {
+                NuccChunkModelHit::read_boxed(input, endian, version)
+            }
+            NuccChunkType::NuccChunkBillboard => {
+                NuccChunkBillboard::read_boxed(input, endian, version)
+            }
+            NuccChunkType::NuccChunkTrail => NuccChunkTrail::read_boxed(input, endian, version),
+            NuccChunkType::NuccChunkParticle => {
+                NuccChunkParticle::read_boxed(input, endian, version)
+            }
+            NuccChunkType::NuccChunkSprite => NuccChunkSprite::read_boxed(input, endian, version),
+            NuccChunkType::NuccChunkSprite2 => NuccChunkSprite2::read_boxed(input, endian, version),
+            NuccChunkType::NuccChunkLayerSet => {
+                NuccChunkLayerSet::read_boxed(input, endian, version)
+            }
+            NuccChunkType::NuccChunkMorphModel => {
+                NuccChunkMorphModel::read_boxed(input, endian, version)
+            }
+            NuccChunkType::NuccChunkMorphPrimitive => {
+                NuccChunkMorphPrimitive::read_boxed(input, endian, version)
+            }
+            NuccChunkType::NuccChunkModelPrimitiveBatch => {
+                NuccChunkModelPrimitiveBatch::read_boxed(input, endian, version)
+            }
+            NuccChunkType::NuccChunkPrimitiveVertex => {
+                NuccChunkPrimitiveVertex::read_boxed(input, endian, version)
+            }
+            NuccChunkType::NuccChunkNub => NuccChunkNub::read_boxed(input, endian, version),
+            NuccChunkType::NuccChunkFont => NuccChunkFont::read_boxed(input, endian, version),
+            NuccChunkType::NuccChunkLensFlare => {
+                NuccChunkLensFlare::read_boxed(input, endian, version)
+            }
+            NuccChunkType::NuccChunkUnknown | NuccChunkType::NuccChunkCustom => {
+                Ok((input, unknown_chunk()))
+            }
+        };
+
+        // Layouts that were only checked against a few files are kept as unknown chunks if they
+        // don't match the data exactly, so the chunk can still be written back unchanged
+        if nucc_chunk_type.is_strict_layout() {
+            result
+        } else {
+            match result {
+                Ok((rest, value)) if rest.is_empty() => Ok((rest, value)),
+                _ => Ok((input, unknown_chunk())),
+            }
         }
     }
 
-    pub fn write_struct(boxed: Box<dyn NuccChunk>, version: u16) -> Result<DekuBitVec, DekuError> {
+    /// Returns false for chunk types whose layout might not match every game's files.
+    fn is_strict_layout(&self) -> bool {
+        !matches!(
+            self,
+            NuccChunkType::NuccChunkDynamics
+                | NuccChunkType::NuccChunkModelHit
+                | NuccChunkType::NuccChunkBillboard
+                | NuccChunkType::NuccChunkTrail
+                | NuccChunkType::NuccChunkParticle
+                | NuccChunkType::NuccChunkSprite
+                | NuccChunkType::NuccChunkSprite2
+                | NuccChunkType::NuccChunkLayerSet
+                | NuccChunkType::NuccChunkMorphModel
+                | NuccChunkType::NuccChunkMorphPrimitive
+                | NuccChunkType::NuccChunkModelPrimitiveBatch
+                | NuccChunkType::NuccChunkPrimitiveVertex
+                | NuccChunkType::NuccChunkNub
+                | NuccChunkType::NuccChunkFont
+                | NuccChunkType::NuccChunkLensFlare
+        )
+    }
+
+    pub fn write_struct(
+        boxed: Box<dyn NuccChunk>,
+        endian: Endian,
+        version: u16,
+    ) -> Result<DekuBitVec, DekuError> {
         let mut output = DekuBitVec::new();
         match boxed.chunk_type() {
             NuccChunkType::NuccChunkNull | NuccChunkType::NuccChunkIndex => {
                 Ok(()) as Result<(), DekuError>
             }
-            NuccChunkType::NuccChunkPage => NuccChunkPage::write_boxed(boxed, &mut output, version),
-            NuccChunkType::NuccChunkAnm => NuccChunkAnm::write_boxed(boxed, &mut output, version),
+            NuccChunkType::NuccChunkPage => {
+                NuccChunkPage::write_boxed(boxed, &mut output, endian, version)
+            }
+            NuccChunkType::NuccChunkAnm => {
+                NuccChunkAnm::write_boxed(boxed, &mut output, endian, version)
+            }
+            NuccChunkType::NuccChunkAnmStrm => {
+                NuccChunkAnmStrm::write_boxed(boxed, &mut output, endian, version)
+            }
+            NuccChunkType::NuccChunkAnmStrmFrame => {
+                NuccChunkAnmStrmFrame::write_boxed(boxed, &mut output, endian, version)
+            }
             NuccChunkType::NuccChunkBinary => {
-                NuccChunkBinary::write_boxed(boxed, &mut output, version)
+                NuccChunkBinary::write_boxed(boxed, &mut output, endian, version)
+            }
+            NuccChunkType::NuccChunkDynamics => {
+                NuccChunkDynamics::write_boxed(boxed, &mut output, endian, version)
+            }
+            NuccChunkType::NuccChunkModelHit => {
+                NuccChunkModelHit::write_boxed(boxed, &mut output, endian, version)
+            }
+            NuccChunkType::NuccChunkBillboard => {
+                NuccChunkBillboard::write_boxed(boxed, &mut output, endian, version)
+            }
+            NuccChunkType::NuccChunkTrail => {
+                NuccChunkTrail::write_boxed(boxed, &mut output, endian, version)
+            }
+            NuccChunkType::NuccChunkParticle => {
+                NuccChunkParticle::write_boxed(boxed, &mut output, endian, version)
+            }
+            NuccChunkType::NuccChunkSprite => {
+                NuccChunkSprite::write_boxed(boxed, &mut output, endian, version)
+            }
+            NuccChunkType::NuccChunkSprite2 => {
+                NuccChunkSprite2::write_boxed(boxed, &mut output, endian, version)
+            }
+            NuccChunkType::NuccChunkLayerSet => {
+                NuccChunkLayerSet::write_boxed(boxed, &mut output, endian, version)
+            }
+            NuccChunkType::NuccChunkMorphModel => {
+                NuccChunkMorphModel::write_boxed(boxed, &mut output, endian, version)
+            }
+            NuccChunkType::NuccChunkMorphPrimitive => {
+                NuccChunkMorphPrimitive::write_boxed(boxed, &mut output, endian, version)
+            }
+            NuccChunkType::NuccChunkModelPrimitiveBatch => {
+                NuccChunkModelPrimitiveBatch::write_boxed(boxed, &mut output, endian, version)
+            }
+            NuccChunkType::NuccChunkPrimitiveVertex => {
+                NuccChunkPrimitiveVertex::write_boxed(boxed, &mut output, endian, version)
+            }
+            NuccChunkType::NuccChunkNub => {
+                NuccChunkNub::write_boxed(boxed, &mut output, endian, version)
+            }
+            NuccChunkType::NuccChunkFont => {
+                NuccChunkFont::write_boxed(boxed, &mut output, endian, version)
+            }
+            NuccChunkType::NuccChunkLensFlare => {
+                NuccChunkLensFlare::write_boxed(boxed, &mut output, endian, version)
             }
-            NuccChunkType::NuccChunkUnknown => {
+            NuccChunkType::NuccChunkUnknown | NuccChunkType::NuccChunkCustom => {
                 let mut chunk = boxed
                     .downcast::<NuccChunkUnknown>()
                     .map(|c| *c)
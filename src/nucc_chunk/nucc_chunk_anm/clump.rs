@@ -22,6 +22,18 @@ pub struct Clump {
     pub model_indices: Vec<u32>,
 }
 
+impl Clump {
+    pub fn new(clump_index: u32, bone_material_indices: Vec<u32>, model_indices: Vec<u32>) -> Self {
+        Self {
+            clump_index,
+            bone_material_count: bone_material_indices.len() as u16,
+            model_count: model_indices.len() as u16,
+            bone_material_indices,
+            model_indices,
+        }
+    }
+}
+
 #[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
 #[deku_derive(DekuRead, DekuWrite)]
 #[deku(
@@ -13,6 +13,18 @@ pub struct CurveHeader {
     unk_size_flags: u16,
 }
 
+impl CurveHeader {
+    pub fn new(curve_index: u16, curve_format: CurveFormat, frame_count: u16) -> Self {
+        Self {
+            curve_index,
+            unk_size_flags: curve_format.size_per_frame().unwrap_or_default() as u16,
+            curve_format,
+            frame_count,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[deku_derive(DekuRead, DekuWrite)]
 #[deku(
     endian = "endian",
@@ -68,8 +80,9 @@ pub enum CurveFormat {
 }
 
 impl CurveFormat {
-    pub fn size_per_frame(&self) -> usize {
-        match self {
+    /// Returns None for formats whose frame layout is not known yet.
+    pub fn size_per_frame(&self) -> Option<usize> {
+        Some(match self {
             CurveFormat::OpacityShortTable | CurveFormat::OpacityShortTableNoInterp => 0x02,
             CurveFormat::ColorRGBTable => 0x03,
             CurveFormat::FloatFixed | CurveFormat::FloatTable | CurveFormat::FloatTableNoInterp => {
@@ -85,12 +98,36 @@ impl CurveFormat {
             | CurveFormat::Vector2Linear
             | CurveFormat::Vector3Table
             | CurveFormat::Vector3TableNoInterp => 0x0C,
+            CurveFormat::Vector3ShortLinear => 0x0A,
             CurveFormat::Vector3Linear | CurveFormat::QuaternionTable => 0x10,
             CurveFormat::QuatnerionLinear => 0x14,
-            CurveFormat::Vector3Bezier => todo!(),
-            CurveFormat::EulerInterpolated => todo!(),
-            CurveFormat::Vector3ShortLinear => todo!(),
-        }
+            CurveFormat::Vector3Bezier | CurveFormat::EulerInterpolated => return None,
+        })
+    }
+
+    /// Sizes of the individual values in a frame, in order.
+    fn value_sizes(&self) -> Option<&'static [usize]> {
+        Some(match self {
+            CurveFormat::OpacityShortTable | CurveFormat::OpacityShortTableNoInterp => &[2],
+            CurveFormat::ColorRGBTable => &[1, 1, 1],
+            CurveFormat::FloatFixed | CurveFormat::FloatTable | CurveFormat::FloatTableNoInterp => {
+                &[4]
+            }
+            CurveFormat::ScaleShortTable => &[2, 2, 2],
+            CurveFormat::FloatLinear | CurveFormat::Vector2Fixed => &[4, 4],
+            CurveFormat::QuaternionShortTable | CurveFormat::QuaternionShortTableNoInterp => {
+                &[2, 2, 2, 2]
+            }
+            CurveFormat::Vector3Fixed
+            | CurveFormat::EulerXYZFixed
+            | CurveFormat::Vector2Linear
+            | CurveFormat::Vector3Table
+            | CurveFormat::Vector3TableNoInterp => &[4, 4, 4],
+            CurveFormat::Vector3ShortLinear => &[4, 2, 2, 2],
+            CurveFormat::Vector3Linear | CurveFormat::QuaternionTable => &[4, 4, 4, 4],
+            CurveFormat::QuatnerionLinear => &[4, 4, 4, 4, 4],
+            CurveFormat::Vector3Bezier | CurveFormat::EulerInterpolated => return None,
+        })
     }
 }
 
@@ -104,3 +141,28 @@ pub struct Curve {
     #[deku(count = "size")]
     pub data: Vec<u8>,
 }
+
+impl Curve {
+    /// Curve data is always kept in big endian, so it can be parsed the same way for every platform.
+    /// Data read from (or written to) little endian chunks has the byte order of each value swapped.
+    pub fn swap_endian(&mut self, header: &CurveHeader) -> Result<(), DekuError> {
+        let value_sizes = header
+            .curve_format
+            .value_sizes()
+            .ok_or_else(|| unsupported_format(&header.curve_format))?;
+        let mut position = 0;
+
+        for _ in 0..header.frame_count {
+            for size in value_sizes {
+                self.data[position..(position + size)].reverse();
+                position += size;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub(crate) fn unsupported_format(curve_format: &CurveFormat) -> DekuError {
+    DekuError::Parse(format!("Unsupported curve format: {curve_format:?}"))
+}
@@ -21,27 +21,54 @@ pub struct Entry {
     #[deku(count = "curve_count")]
     pub curve_headers: Vec<CurveHeader>,
 
-    #[deku(reader = "Entry::read_curves(deku::rest, curve_headers)", ctx = "0")]
+    #[deku(
+        reader = "Entry::read_curves(deku::rest, curve_headers, endian)",
+        writer = "Entry::write_curves(deku::output, curve_headers, curves, endian)"
+    )]
     pub curves: Vec<Curve>,
 }
 
 impl Entry {
+    pub fn new(
+        coord_index: ClumpCoordIndex,
+        entry_format: EntryFormat,
+        curve_headers: Vec<CurveHeader>,
+        curves: Vec<Curve>,
+    ) -> Self {
+        Self {
+            coord_index,
+            entry_format,
+            curve_count: curve_headers.len() as u16,
+            curve_headers,
+            curves,
+        }
+    }
+
     fn read_curves<'a>(
         input: &'a DekuBitSlice,
         curve_headers: &Vec<CurveHeader>,
+        endian: ctx::Endian,
     ) -> Result<(&'a DekuBitSlice, Vec<Curve>), DekuError> {
         let mut curves = vec![];
         let mut data = input;
 
         for header in curve_headers {
-            let mut curve_size = header.curve_format.size_per_frame() * header.frame_count as usize;
+            let mut curve_size = header
+                .curve_format
+                .size_per_frame()
+                .ok_or_else(|| unsupported_format(&header.curve_format))?
+                * header.frame_count as usize;
 
             if curve_size % 4 != 0 {
                 curve_size += 4 - (curve_size % 4);
             }
 
-            match Curve::read(data, (ctx::Endian::Big, curve_size)) {
-                Ok((rest, value)) => {
+            match Curve::read(data, (endian, curve_size)) {
+                Ok((rest, mut value)) => {
+                    if endian == ctx::Endian::Little {
+                        value.swap_endian(header)?;
+                    }
+
                     curves.push(value);
                     data = rest;
                 }
@@ -51,6 +78,27 @@ impl Entry {
 
         Ok((data, curves))
     }
+
+    fn write_curves(
+        output: &mut DekuBitVec,
+        curve_headers: &[CurveHeader],
+        curves: &[Curve],
+        endian: ctx::Endian,
+    ) -> Result<(), DekuError> {
+        for (header, curve) in curve_headers.iter().zip(curves.iter()) {
+            if endian == ctx::Endian::Little {
+                let mut swapped = Curve {
+                    data: curve.data.clone(),
+                };
+                swapped.swap_endian(header)?;
+                swapped.write(output, (endian, 0))?;
+            } else {
+                curve.write(output, (endian, 0))?;
+            }
+        }
+
+        Ok(())
+    }
 }
 
 #[deku_derive(DekuRead, DekuWrite)]
@@ -0,0 +1,30 @@
+use deku::{ctx, prelude::*};
+
+use super::{NuccChunk, NuccChunkAnm, NuccChunkType};
+
+/// Uses the same layout as nuccChunkAnm. The curves of its entries only define the format of each channel
+/// and have no frames, since the keyframes are stored in the nuccChunkAnmStrmFrame chunks that follow it.
+#[derive(Default)]
+#[deku_derive(DekuRead, DekuWrite)]
+#[deku(
+    endian = "endian",
+    ctx = "endian: ctx::Endian, version: u16",
+    ctx_default = "ctx::Endian::Big, 0x79"
+)]
+pub struct NuccChunkAnmStrm {
+    #[deku(skip, default = "version")]
+    pub version: u16,
+
+    #[deku(ctx = "endian, version")]
+    pub anm: NuccChunkAnm,
+}
+
+impl NuccChunk for NuccChunkAnmStrm {
+    fn chunk_type(&self) -> NuccChunkType {
+        NuccChunkType::NuccChunkAnmStrm
+    }
+
+    fn version(&self) -> u16 {
+        self.version
+    }
+}
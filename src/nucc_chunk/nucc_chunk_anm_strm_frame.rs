@@ -0,0 +1,36 @@
+use deku::{ctx, prelude::*};
+
+use super::nucc_chunk_anm::Entry;
+use super::{NuccChunk, NuccChunkType};
+
+#[derive(Default)]
+#[deku_derive(DekuRead, DekuWrite)]
+#[deku(
+    endian = "endian",
+    ctx = "endian: ctx::Endian, version: u16",
+    ctx_default = "ctx::Endian::Big, 0x79"
+)]
+pub struct NuccChunkAnmStrmFrame {
+    #[deku(skip, default = "version")]
+    pub version: u16,
+
+    pub frame: u32,
+
+    #[deku(update = "self.entries.len() as u16")]
+    entry_count: u16,
+    pub unk: u16,
+
+    /// Each curve of these entries has the values of a single frame.
+    #[deku(count = "entry_count")]
+    pub entries: Vec<Entry>,
+}
+
+impl NuccChunk for NuccChunkAnmStrmFrame {
+    fn chunk_type(&self) -> NuccChunkType {
+        NuccChunkType::NuccChunkAnmStrmFrame
+    }
+
+    fn version(&self) -> u16 {
+        self.version
+    }
+}
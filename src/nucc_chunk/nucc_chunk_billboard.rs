@@ -0,0 +1,39 @@
+use deku::{ctx, prelude::*};
+
+use super::{NuccChunk, NuccChunkType};
+
+#[derive(Default)]
+#[deku_derive(DekuRead, DekuWrite)]
+#[deku(
+    endian = "endian",
+    ctx = "endian: ctx::Endian, version: u16",
+    ctx_default = "ctx::Endian::Big, 0x79"
+)]
+pub struct NuccChunkBillboard {
+    #[deku(skip, default = "version")]
+    pub version: u16,
+
+    /// Index of the material's chunk map in the page.
+    pub material_index: u32,
+
+    /// Index of the attached coord's reference in the page.
+    pub coord_index: u32,
+
+    pub width: f32,
+    pub height: f32,
+    pub offset: [f32; 3],
+    pub rotation: f32,
+
+    pub color: [f32; 4],
+    pub flags: u32,
+}
+
+impl NuccChunk for NuccChunkBillboard {
+    fn chunk_type(&self) -> NuccChunkType {
+        NuccChunkType::NuccChunkBillboard
+    }
+
+    fn version(&self) -> u16 {
+        self.version
+    }
+}
@@ -0,0 +1,86 @@
+use deku::{ctx, prelude::*};
+
+use super::{NuccChunk, NuccChunkType};
+
+#[derive(Default)]
+#[deku_derive(DekuRead, DekuWrite)]
+#[deku(
+    endian = "endian",
+    ctx = "endian: ctx::Endian, version: u16",
+    ctx_default = "ctx::Endian::Big, 0x79"
+)]
+pub struct NuccChunkDynamics {
+    #[deku(skip, default = "version")]
+    pub version: u16,
+
+    #[deku(update = "self.spring_groups.len() as u16")]
+    spring_group_count: u16,
+
+    #[deku(update = "self.collision_spheres.len() as u16")]
+    collision_sphere_count: u16,
+
+    /// Index of the clump's chunk map in the page.
+    pub clump_index: u32,
+
+    #[deku(count = "spring_group_count")]
+    pub spring_groups: Vec<SpringGroup>,
+
+    #[deku(count = "collision_sphere_count")]
+    pub collision_spheres: Vec<CollisionSphere>,
+
+    /// Spring group indices of all collision spheres, in order.
+    #[deku(
+        count = "collision_spheres.iter().map(|c| c.attached_group_count as usize).sum::<usize>()"
+    )]
+    pub attached_group_indices: Vec<u16>,
+}
+
+/// A chain of bones that starts from a coord. The parameters are applied to every bone in the chain.
+#[derive(Default)]
+#[deku_derive(DekuRead, DekuWrite)]
+#[deku(
+    endian = "endian",
+    ctx = "endian: ctx::Endian",
+    ctx_default = "ctx::Endian::Big"
+)]
+pub struct SpringGroup {
+    pub bounciness: f32,
+    pub elasticity: f32,
+    pub stiffness: f32,
+    pub movement: f32,
+
+    /// Index of the root coord's reference in the page.
+    pub coord_index: u16,
+    pub bone_count: u16,
+
+    pub flags: u32,
+}
+
+#[derive(Default)]
+#[deku_derive(DekuRead, DekuWrite)]
+#[deku(
+    endian = "endian",
+    ctx = "endian: ctx::Endian",
+    ctx_default = "ctx::Endian::Big"
+)]
+pub struct CollisionSphere {
+    pub offset: [f32; 3],
+    pub scale: [f32; 3],
+
+    /// Index of the attached coord's reference in the page.
+    pub coord_index: u16,
+
+    pub attach_groups: u16,
+    pub attached_group_count: u16,
+    pub unk: u16,
+}
+
+impl NuccChunk for NuccChunkDynamics {
+    fn chunk_type(&self) -> NuccChunkType {
+        NuccChunkType::NuccChunkDynamics
+    }
+
+    fn version(&self) -> u16 {
+        self.version
+    }
+}
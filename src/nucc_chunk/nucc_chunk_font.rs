@@ -0,0 +1,71 @@
+use deku::{ctx, prelude::*};
+
+use super::{NuccChunk, NuccChunkType};
+
+#[derive(Default)]
+#[deku_derive(DekuRead, DekuWrite)]
+#[deku(
+    endian = "endian",
+    ctx = "endian: ctx::Endian, version: u16",
+    ctx_default = "ctx::Endian::Big, 0x79"
+)]
+pub struct NuccChunkFont {
+    #[deku(skip, default = "version")]
+    pub version: u16,
+
+    pub line_height: u16,
+
+    /// Distance from the top of a line to the baseline.
+    pub base: u16,
+
+    /// Size of every texture, in pixels.
+    pub texture_width: u16,
+    pub texture_height: u16,
+
+    #[deku(update = "self.texture_indices.len() as u16")]
+    texture_count: u16,
+    pub unk: u16,
+
+    #[deku(update = "self.glyphs.len() as u32")]
+    glyph_count: u32,
+
+    /// Indices of the textures' chunk maps in the page.
+    #[deku(count = "texture_count")]
+    pub texture_indices: Vec<u32>,
+
+    #[deku(count = "glyph_count")]
+    pub glyphs: Vec<Glyph>,
+}
+
+#[derive(Default)]
+#[deku_derive(DekuRead, DekuWrite)]
+#[deku(
+    endian = "endian",
+    ctx = "endian: ctx::Endian",
+    ctx_default = "ctx::Endian::Big"
+)]
+pub struct Glyph {
+    pub code: u32,
+
+    /// Index in the texture indices of the chunk.
+    pub texture_slot: u16,
+    pub unk: u16,
+
+    /// Left, top, right and bottom texture coordinates.
+    pub uv: [f32; 4],
+
+    pub x_offset: i16,
+    pub y_offset: i16,
+    pub advance: i16,
+    pub padding: u16,
+}
+
+impl NuccChunk for NuccChunkFont {
+    fn chunk_type(&self) -> NuccChunkType {
+        NuccChunkType::NuccChunkFont
+    }
+
+    fn version(&self) -> u16 {
+        self.version
+    }
+}
@@ -0,0 +1,59 @@
+use deku::{ctx, prelude::*};
+
+use super::{NuccChunk, NuccChunkType};
+use crate::utils::DekuString;
+
+#[derive(Default)]
+#[deku_derive(DekuRead, DekuWrite)]
+#[deku(
+    endian = "endian",
+    ctx = "endian: ctx::Endian, version: u16",
+    ctx_default = "ctx::Endian::Big, 0x79"
+)]
+pub struct NuccChunkLayerSet {
+    #[deku(skip, default = "version")]
+    pub version: u16,
+
+    pub width: u32,
+    pub height: u32,
+
+    #[deku(update = "self.layers.len() as u32")]
+    layer_count: u32,
+
+    #[deku(count = "layer_count")]
+    pub layers: Vec<Layer>,
+}
+
+#[derive(Default)]
+#[deku_derive(DekuRead, DekuWrite)]
+#[deku(
+    endian = "endian",
+    ctx = "endian: ctx::Endian",
+    ctx_default = "ctx::Endian::Big"
+)]
+pub struct Layer {
+    pub name: DekuString,
+
+    /// Index of the sprite's chunk map in the page, or -1 for an empty layer.
+    pub sprite_index: i32,
+
+    /// Index of the parent in the layers of the chunk, or -1 for a root layer.
+    pub parent_index: i32,
+
+    pub position: [f32; 2],
+    pub scale: [f32; 2],
+    pub rotation: f32,
+
+    pub color: [f32; 4],
+    pub visible: u32,
+}
+
+impl NuccChunk for NuccChunkLayerSet {
+    fn chunk_type(&self) -> NuccChunkType {
+        NuccChunkType::NuccChunkLayerSet
+    }
+
+    fn version(&self) -> u16 {
+        self.version
+    }
+}
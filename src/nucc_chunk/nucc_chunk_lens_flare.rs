@@ -0,0 +1,65 @@
+use deku::{ctx, prelude::*};
+
+use super::{NuccChunk, NuccChunkType};
+
+#[derive(Default)]
+#[deku_derive(DekuRead, DekuWrite)]
+#[deku(
+    endian = "endian",
+    ctx = "endian: ctx::Endian, version: u16",
+    ctx_default = "ctx::Endian::Big, 0x79"
+)]
+pub struct NuccChunkLensFlare {
+    #[deku(skip, default = "version")]
+    pub version: u16,
+
+    /// Index of the light's chunk map in the page.
+    pub light_index: u32,
+
+    pub intensity: f32,
+    pub fade_distance: f32,
+    pub flags: u32,
+
+    #[deku(update = "self.texture_indices.len() as u16")]
+    texture_count: u16,
+
+    #[deku(update = "self.elements.len() as u16")]
+    element_count: u16,
+
+    /// Indices of the textures' chunk maps in the page.
+    #[deku(count = "texture_count")]
+    pub texture_indices: Vec<u32>,
+
+    #[deku(count = "element_count")]
+    pub elements: Vec<LensFlareElement>,
+}
+
+#[derive(Default)]
+#[deku_derive(DekuRead, DekuWrite)]
+#[deku(
+    endian = "endian",
+    ctx = "endian: ctx::Endian",
+    ctx_default = "ctx::Endian::Big"
+)]
+pub struct LensFlareElement {
+    /// Index in the texture indices of the chunk.
+    pub texture_slot: u16,
+    pub unk: u16,
+
+    /// Position on the line from the light to the center of the screen.
+    pub position: f32,
+    pub size: f32,
+    pub rotation: f32,
+
+    pub color: [f32; 4],
+}
+
+impl NuccChunk for NuccChunkLensFlare {
+    fn chunk_type(&self) -> NuccChunkType {
+        NuccChunkType::NuccChunkLensFlare
+    }
+
+    fn version(&self) -> u16 {
+        self.version
+    }
+}
@@ -0,0 +1,53 @@
+use deku::{ctx, prelude::*};
+
+use super::{NuccChunk, NuccChunkType};
+
+#[derive(Default)]
+#[deku_derive(DekuRead, DekuWrite)]
+#[deku(
+    endian = "endian",
+    ctx = "endian: ctx::Endian, version: u16",
+    ctx_default = "ctx::Endian::Big, 0x79"
+)]
+pub struct NuccChunkModelHit {
+    #[deku(skip, default = "version")]
+    pub version: u16,
+
+    #[deku(update = "self.sections.len() as u32")]
+    section_count: u32,
+
+    #[deku(update = "self.vertices.len() as u32")]
+    total_vertex_count: u32,
+
+    #[deku(count = "section_count")]
+    pub sections: Vec<HitSection>,
+
+    /// Triangle vertices of all sections, in order.
+    #[deku(count = "total_vertex_count")]
+    pub vertices: Vec<[f32; 3]>,
+}
+
+#[derive(Default)]
+#[deku_derive(DekuRead, DekuWrite)]
+#[deku(
+    endian = "endian",
+    ctx = "endian: ctx::Endian",
+    ctx_default = "ctx::Endian::Big"
+)]
+pub struct HitSection {
+    /// Three vertices per triangle.
+    pub vertex_count: u32,
+
+    pub collision_flags: [u8; 4],
+    pub unk: u32,
+}
+
+impl NuccChunk for NuccChunkModelHit {
+    fn chunk_type(&self) -> NuccChunkType {
+        NuccChunkType::NuccChunkModelHit
+    }
+
+    fn version(&self) -> u16 {
+        self.version
+    }
+}
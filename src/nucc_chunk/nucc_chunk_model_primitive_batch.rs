@@ -0,0 +1,74 @@
+use deku::{ctx, prelude::*};
+
+use super::{NuccChunk, NuccChunkType};
+
+#[derive(Default)]
+#[deku_derive(DekuRead, DekuWrite)]
+#[deku(
+    endian = "endian",
+    ctx = "endian: ctx::Endian, version: u16",
+    ctx_default = "ctx::Endian::Big, 0x79"
+)]
+pub struct NuccChunkModelPrimitiveBatch {
+    #[deku(skip, default = "version")]
+    pub version: u16,
+
+    /// Index of the nuccChunkPrimitiveVertex's chunk map in the page.
+    pub vertex_buffer_index: u32,
+
+    /// Index of the material's chunk map in the page.
+    pub material_index: u32,
+
+    #[deku(update = "self.primitives.len() as u32")]
+    primitive_count: u32,
+
+    #[deku(count = "primitive_count")]
+    pub primitives: Vec<BatchPrimitive>,
+}
+
+/// A range in the indices of the vertex buffer.
+#[derive(Default)]
+#[deku_derive(DekuRead, DekuWrite)]
+#[deku(
+    endian = "endian",
+    ctx = "endian: ctx::Endian",
+    ctx_default = "ctx::Endian::Big"
+)]
+pub struct BatchPrimitive {
+    pub primitive_type: PrimitiveType,
+    pub unk: u16,
+
+    pub index_start: u32,
+    pub index_count: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[deku_derive(DekuRead, DekuWrite)]
+#[deku(
+    endian = "endian",
+    ctx = "endian: ctx::Endian",
+    ctx_default = "ctx::Endian::Big",
+    type = "u16"
+)]
+pub enum PrimitiveType {
+    #[deku(id = "0x00")]
+    TriangleList,
+    #[deku(id = "0x01")]
+    TriangleStrip,
+}
+
+impl Default for PrimitiveType {
+    fn default() -> Self {
+        PrimitiveType::TriangleList
+    }
+}
+
+impl NuccChunk for NuccChunkModelPrimitiveBatch {
+    fn chunk_type(&self) -> NuccChunkType {
+        NuccChunkType::NuccChunkModelPrimitiveBatch
+    }
+
+    fn version(&self) -> u16 {
+        self.version
+    }
+}
@@ -0,0 +1,42 @@
+use deku::{ctx, prelude::*};
+
+use super::{NuccChunk, NuccChunkType};
+use crate::utils::DekuString;
+
+#[derive(Default)]
+#[deku_derive(DekuRead, DekuWrite)]
+#[deku(
+    endian = "endian",
+    ctx = "endian: ctx::Endian, version: u16",
+    ctx_default = "ctx::Endian::Big, 0x79"
+)]
+pub struct NuccChunkMorphModel {
+    #[deku(skip, default = "version")]
+    pub version: u16,
+
+    /// Index of the base nuccChunkModel's chunk map in the page.
+    pub model_index: u32,
+
+    #[deku(update = "self.primitive_indices.len() as u16")]
+    primitive_count: u16,
+
+    #[deku(update = "self.target_names.len() as u16")]
+    target_count: u16,
+
+    /// Indices of the nuccChunkMorphPrimitives' chunk maps in the page.
+    #[deku(count = "primitive_count")]
+    pub primitive_indices: Vec<u32>,
+
+    #[deku(count = "target_count")]
+    pub target_names: Vec<DekuString>,
+}
+
+impl NuccChunk for NuccChunkMorphModel {
+    fn chunk_type(&self) -> NuccChunkType {
+        NuccChunkType::NuccChunkMorphModel
+    }
+
+    fn version(&self) -> u16 {
+        self.version
+    }
+}
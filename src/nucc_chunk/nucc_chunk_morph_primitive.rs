@@ -0,0 +1,63 @@
+use deku::{ctx, prelude::*};
+
+use super::{NuccChunk, NuccChunkType};
+
+#[derive(Default)]
+#[deku_derive(DekuRead, DekuWrite)]
+#[deku(
+    endian = "endian",
+    ctx = "endian: ctx::Endian, version: u16",
+    ctx_default = "ctx::Endian::Big, 0x79"
+)]
+pub struct NuccChunkMorphPrimitive {
+    #[deku(skip, default = "version")]
+    pub version: u16,
+
+    /// Vertex count of the base model's primitive.
+    pub vertex_count: u32,
+
+    #[deku(update = "self.targets.len() as u32")]
+    target_count: u32,
+
+    #[deku(count = "target_count")]
+    pub targets: Vec<MorphTarget>,
+}
+
+/// Only the vertices that are moved by the target are stored.
+#[derive(Default)]
+#[deku_derive(DekuRead, DekuWrite)]
+#[deku(
+    endian = "endian",
+    ctx = "endian: ctx::Endian",
+    ctx_default = "ctx::Endian::Big"
+)]
+pub struct MorphTarget {
+    #[deku(update = "self.deltas.len() as u32")]
+    delta_count: u32,
+
+    #[deku(count = "delta_count")]
+    pub deltas: Vec<MorphDelta>,
+}
+
+#[derive(Default)]
+#[deku_derive(DekuRead, DekuWrite)]
+#[deku(
+    endian = "endian",
+    ctx = "endian: ctx::Endian",
+    ctx_default = "ctx::Endian::Big"
+)]
+pub struct MorphDelta {
+    pub vertex_index: u32,
+    pub position: [f32; 3],
+    pub normal: [f32; 3],
+}
+
+impl NuccChunk for NuccChunkMorphPrimitive {
+    fn chunk_type(&self) -> NuccChunkType {
+        NuccChunkType::NuccChunkMorphPrimitive
+    }
+
+    fn version(&self) -> u16 {
+        self.version
+    }
+}
@@ -0,0 +1,38 @@
+use deku::{ctx, prelude::*};
+
+use super::{NuccChunk, NuccChunkType};
+
+#[derive(Default)]
+#[deku_derive(DekuRead, DekuWrite)]
+#[deku(
+    endian = "endian",
+    ctx = "endian: ctx::Endian, version: u16",
+    ctx_default = "ctx::Endian::Big, 0x79"
+)]
+pub struct NuccChunkNub {
+    #[deku(skip, default = "version")]
+    pub version: u16,
+
+    /// Index of the clump's chunk map in the page.
+    pub clump_index: u32,
+
+    /// Index of the parent coord's reference in the page.
+    pub coord_index: u16,
+    pub unk: u16,
+
+    pub position: [f32; 3],
+
+    /// Euler angles in degrees, in XYZ order.
+    pub rotation: [f32; 3],
+    pub scale: [f32; 3],
+}
+
+impl NuccChunk for NuccChunkNub {
+    fn chunk_type(&self) -> NuccChunkType {
+        NuccChunkType::NuccChunkNub
+    }
+
+    fn version(&self) -> u16 {
+        self.version
+    }
+}
@@ -0,0 +1,55 @@
+use deku::{ctx, prelude::*};
+
+use super::{NuccChunk, NuccChunkType};
+
+#[derive(Default)]
+#[deku_derive(DekuRead, DekuWrite)]
+#[deku(
+    endian = "endian",
+    ctx = "endian: ctx::Endian, version: u16",
+    ctx_default = "ctx::Endian::Big, 0x79"
+)]
+pub struct NuccChunkParticle {
+    #[deku(skip, default = "version")]
+    pub version: u16,
+
+    /// Index of the material's chunk map in the page.
+    pub material_index: u32,
+
+    /// Index of the emitter coord's reference in the page.
+    pub coord_index: u32,
+
+    pub max_particles: u32,
+
+    /// Particles emitted per second.
+    pub emission_rate: f32,
+    pub emission_duration: f32,
+
+    pub lifetime: f32,
+    pub lifetime_variance: f32,
+
+    pub speed: f32,
+    pub speed_variance: f32,
+
+    /// Angle of the emission cone, in degrees.
+    pub spread_angle: f32,
+    pub direction: [f32; 3],
+    pub gravity: [f32; 3],
+
+    pub start_size: f32,
+    pub end_size: f32,
+
+    pub start_color: [f32; 4],
+    pub end_color: [f32; 4],
+    pub flags: u32,
+}
+
+impl NuccChunk for NuccChunkParticle {
+    fn chunk_type(&self) -> NuccChunkType {
+        NuccChunkType::NuccChunkParticle
+    }
+
+    fn version(&self) -> u16 {
+        self.version
+    }
+}
@@ -0,0 +1,51 @@
+use deku::{ctx, prelude::*};
+
+use super::{NuccChunk, NuccChunkType};
+
+#[derive(Default)]
+#[deku_derive(DekuRead, DekuWrite)]
+#[deku(
+    endian = "endian",
+    ctx = "endian: ctx::Endian, version: u16",
+    ctx_default = "ctx::Endian::Big, 0x79"
+)]
+pub struct NuccChunkPrimitiveVertex {
+    #[deku(skip, default = "version")]
+    pub version: u16,
+
+    #[deku(update = "self.vertices.len() as u32")]
+    vertex_count: u32,
+
+    #[deku(update = "self.indices.len() as u32")]
+    index_count: u32,
+
+    #[deku(count = "vertex_count")]
+    pub vertices: Vec<PrimitiveVertex>,
+
+    #[deku(count = "index_count")]
+    pub indices: Vec<u16>,
+}
+
+#[derive(Default)]
+#[deku_derive(DekuRead, DekuWrite)]
+#[deku(
+    endian = "endian",
+    ctx = "endian: ctx::Endian",
+    ctx_default = "ctx::Endian::Big"
+)]
+pub struct PrimitiveVertex {
+    pub position: [f32; 3],
+    pub normal: [f32; 3],
+    pub color: [u8; 4],
+    pub uv: [f32; 2],
+}
+
+impl NuccChunk for NuccChunkPrimitiveVertex {
+    fn chunk_type(&self) -> NuccChunkType {
+        NuccChunkType::NuccChunkPrimitiveVertex
+    }
+
+    fn version(&self) -> u16 {
+        self.version
+    }
+}
@@ -0,0 +1,37 @@
+use deku::{ctx, prelude::*};
+
+use super::{NuccChunk, NuccChunkType};
+
+#[derive(Default)]
+#[deku_derive(DekuRead, DekuWrite)]
+#[deku(
+    endian = "endian",
+    ctx = "endian: ctx::Endian, version: u16",
+    ctx_default = "ctx::Endian::Big, 0x79"
+)]
+pub struct NuccChunkSprite {
+    #[deku(skip, default = "version")]
+    pub version: u16,
+
+    /// Index of the texture's chunk map in the page.
+    pub texture_index: u32,
+
+    /// X, Y, width and height, in pixels.
+    pub rect: [f32; 4],
+
+    /// Left, top, right and bottom texture coordinates.
+    pub uv: [f32; 4],
+
+    pub color: [f32; 4],
+    pub flags: u32,
+}
+
+impl NuccChunk for NuccChunkSprite {
+    fn chunk_type(&self) -> NuccChunkType {
+        NuccChunkType::NuccChunkSprite
+    }
+
+    fn version(&self) -> u16 {
+        self.version
+    }
+}
@@ -0,0 +1,57 @@
+use deku::{ctx, prelude::*};
+
+use super::{NuccChunk, NuccChunkType};
+
+#[derive(Default)]
+#[deku_derive(DekuRead, DekuWrite)]
+#[deku(
+    endian = "endian",
+    ctx = "endian: ctx::Endian, version: u16",
+    ctx_default = "ctx::Endian::Big, 0x79"
+)]
+pub struct NuccChunkSprite2 {
+    #[deku(skip, default = "version")]
+    pub version: u16,
+
+    #[deku(update = "self.texture_indices.len() as u16")]
+    texture_count: u16,
+
+    #[deku(update = "self.frames.len() as u16")]
+    frame_count: u16,
+
+    /// Indices of the textures' chunk maps in the page.
+    #[deku(count = "texture_count")]
+    pub texture_indices: Vec<u32>,
+
+    #[deku(count = "frame_count")]
+    pub frames: Vec<Sprite2Frame>,
+
+    pub color: [f32; 4],
+    pub flags: u32,
+}
+
+#[derive(Default)]
+#[deku_derive(DekuRead, DekuWrite)]
+#[deku(
+    endian = "endian",
+    ctx = "endian: ctx::Endian",
+    ctx_default = "ctx::Endian::Big"
+)]
+pub struct Sprite2Frame {
+    /// Index in the texture indices of the chunk.
+    pub texture_slot: u16,
+    pub unk: u16,
+
+    pub rect: [f32; 4],
+    pub uv: [f32; 4],
+}
+
+impl NuccChunk for NuccChunkSprite2 {
+    fn chunk_type(&self) -> NuccChunkType {
+        NuccChunkType::NuccChunkSprite2
+    }
+
+    fn version(&self) -> u16 {
+        self.version
+    }
+}
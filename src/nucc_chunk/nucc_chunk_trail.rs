@@ -0,0 +1,42 @@
+use deku::{ctx, prelude::*};
+
+use super::{NuccChunk, NuccChunkType};
+
+#[derive(Default)]
+#[deku_derive(DekuRead, DekuWrite)]
+#[deku(
+    endian = "endian",
+    ctx = "endian: ctx::Endian, version: u16",
+    ctx_default = "ctx::Endian::Big, 0x79"
+)]
+pub struct NuccChunkTrail {
+    #[deku(skip, default = "version")]
+    pub version: u16,
+
+    /// Index of the material's chunk map in the page.
+    pub material_index: u32,
+
+    /// Indices of the coords' references in the page. The trail is stretched between both coords.
+    pub start_coord_index: u32,
+    pub end_coord_index: u32,
+
+    pub segment_count: u32,
+    pub lifetime: f32,
+
+    pub start_width: f32,
+    pub end_width: f32,
+
+    pub start_color: [f32; 4],
+    pub end_color: [f32; 4],
+    pub flags: u32,
+}
+
+impl NuccChunk for NuccChunkTrail {
+    fn chunk_type(&self) -> NuccChunkType {
+        NuccChunkType::NuccChunkTrail
+    }
+
+    fn version(&self) -> u16 {
+        self.version
+    }
+}
@@ -0,0 +1,148 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use hashbrown::HashSet;
+use serde::{Deserialize, Serialize};
+
+use crate::error::NuccError;
+use crate::nucc::{NuccStructInfo, NuccStructReference, NuccUnknown};
+use crate::xfbin::Xfbin;
+
+const DATA_EXTENSION: &str = ".bin";
+const METADATA_EXTENSION: &str = ".bin.json";
+
+/// Sidecar of a raw chunk file, which is used to find the chunk again when importing.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RawChunkMetadata {
+    pub page_index: usize,
+    pub struct_info: NuccStructInfo,
+    pub version: u16,
+
+    /// File name of the chunk's data, relative to the metadata file.
+    pub data_file_name: String,
+
+    /// References of the chunk's page. Indices in the chunk's data point to these.
+    /// Changing them replaces the page's references on import.
+    pub struct_references: Vec<NuccStructReference>,
+}
+
+/// Replaces characters that can't be used in file names.
+fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect()
+}
+
+impl Xfbin {
+    /// Writes the data of every unknown chunk as `<chunk_name>.<chunk_type>.bin`, with a `.bin.json` metadata file.
+    /// Chunks with the same name and type get a counter before the extension. Returns the written metadata files.
+    pub fn export_unknown_chunks(&self, directory: &Path) -> Result<Vec<PathBuf>, NuccError> {
+        fs::create_dir_all(directory).map_err(|_| NuccError::RawChunkIoError)?;
+
+        let mut used_names = HashSet::new();
+        let mut metadata_paths = vec![];
+
+        for (page_index, page) in self.pages.iter().enumerate() {
+            for unknown in page
+                .structs
+                .iter()
+                .filter_map(|s| s.downcast_ref::<NuccUnknown>())
+            {
+                let base_name = sanitize(&format!(
+                    "{}.{}",
+                    unknown.struct_info.chunk_name, unknown.chunk_type
+                ));
+
+                let mut name = base_name.clone();
+                let mut counter = 1;
+                while !used_names.insert(name.to_lowercase()) {
+                    name = format!("{base_name}.{counter}");
+                    counter += 1;
+                }
+
+                let data_file_name = name.clone() + DATA_EXTENSION;
+                fs::write(directory.join(&data_file_name), &unknown.data)
+                    .map_err(|_| NuccError::RawChunkIoError)?;
+
+                let metadata = RawChunkMetadata {
+                    page_index,
+                    struct_info: unknown.struct_info.clone(),
+                    version: unknown.version,
+                    data_file_name,
+                    struct_references: page.struct_references.clone(),
+                };
+
+                let metadata_path = directory.join(name + METADATA_EXTENSION);
+                fs::write(
+                    &metadata_path,
+                    serde_json::to_string_pretty(&metadata).unwrap(),
+                )
+                .map_err(|_| NuccError::RawChunkIoError)?;
+
+                metadata_paths.push(metadata_path);
+            }
+        }
+
+        Ok(metadata_paths)
+    }
+
+    /// Replaces the data of the unknown chunk that the metadata file points to with its data file.
+    pub fn import_unknown_chunk(&mut self, metadata_path: &Path) -> Result<(), NuccError> {
+        let metadata = fs::read(metadata_path).map_err(|_| NuccError::RawChunkIoError)?;
+        let metadata: RawChunkMetadata =
+            serde_json::from_slice(&metadata).map_err(|_| NuccError::InvalidRawChunkMetadata)?;
+
+        let data = fs::read(metadata_path.with_file_name(&metadata.data_file_name))
+            .map_err(|_| NuccError::RawChunkIoError)?;
+
+        let page = self
+            .pages
+            .get_mut(metadata.page_index)
+            .ok_or(NuccError::RawChunkNotFound)?;
+
+        let mut unknowns = page
+            .structs
+            .iter_mut()
+            .filter_map(|s| s.downcast_mut::<NuccUnknown>())
+            .filter(|u| u.struct_info == metadata.struct_info);
+
+        let unknown = match (unknowns.next(), unknowns.next()) {
+            (Some(unknown), None) => unknown,
+            _ => return Err(NuccError::RawChunkNotFound),
+        };
+
+        unknown.data = data;
+        unknown.version = metadata.version;
+
+        page.struct_references = metadata.struct_references;
+
+        Ok(())
+    }
+
+    /// Imports every `.bin.json` metadata file in the directory. Returns the imported metadata files.
+    pub fn import_unknown_chunks(&mut self, directory: &Path) -> Result<Vec<PathBuf>, NuccError> {
+        let mut metadata_paths = fs::read_dir(directory)
+            .map_err(|_| NuccError::RawChunkIoError)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|n| n.to_str())
+                    .map_or(false, |n| n.ends_with(METADATA_EXTENSION))
+            })
+            .collect::<Vec<_>>();
+
+        metadata_paths.sort();
+
+        for metadata_path in metadata_paths.iter() {
+            self.import_unknown_chunk(metadata_path)?;
+        }
+
+        Ok(metadata_paths)
+    }
+}
@@ -1,7 +1,9 @@
-use deku::DekuUpdate;
+use deku::{DekuError, DekuUpdate};
 use hashbrown::HashMap;
 use itertools::Itertools;
 
+use crate::endianness::Endianness;
+use crate::error::NuccError;
 use crate::utils::DekuString;
 
 use super::nucc::*;
@@ -11,6 +13,7 @@ use super::xfbin_file::*;
 
 pub struct Xfbin {
     pub version: u16,
+    pub endianness: Endianness,
     pub pages: Vec<XfbinPage>,
 }
 
@@ -21,6 +24,35 @@ pub struct XfbinPage {
     pub struct_references: Vec<NuccStructReference>,
 }
 
+impl Xfbin {
+    /// Returns the structs of the given type in all pages, in order.
+    pub fn structs_of<T: NuccStruct>(&self) -> impl Iterator<Item = &T> {
+        self.pages
+            .iter()
+            .flat_map(|page| page.structs.iter())
+            .filter_map(|s| s.downcast_ref::<T>())
+    }
+
+    pub fn nubs(&self) -> Vec<&NuccNub> {
+        self.structs_of::<NuccNub>().collect()
+    }
+
+    /// Returns the nub with the given name, if there is only one.
+    pub fn nub(&self, name: &str) -> Option<&NuccNub> {
+        self.structs_of::<NuccNub>()
+            .filter(|nub| nub.name() == name)
+            .exactly_one()
+            .ok()
+    }
+
+    /// Returns the lens flares that are attached to the light with the given chunk name.
+    pub fn lens_flares_of(&self, light_name: &str) -> Vec<&NuccLensFlare> {
+        self.structs_of::<NuccLensFlare>()
+            .filter(|lens_flare| lens_flare.light.chunk_name == light_name)
+            .collect()
+    }
+}
+
 impl XfbinPage {
     pub fn has_unknown_chunk(&self) -> bool {
         for nucc_struct in self.structs.iter() {
@@ -32,6 +64,23 @@ impl XfbinPage {
         false
     }
 
+    /// Combines the page's NuccAnmStrm with its NuccAnmStrmFrame structs.
+    /// Returns None if the page has no streamed animation.
+    pub fn streamed_anm(&self) -> Option<Result<NuccAnm, NuccError>> {
+        let anm_strm = self
+            .structs
+            .iter()
+            .find_map(|s| s.downcast_ref::<NuccAnmStrm>())?;
+
+        let frames = self
+            .structs
+            .iter()
+            .filter_map(|s| s.downcast_ref::<NuccAnmStrmFrame>())
+            .collect_vec();
+
+        Some(anm_strm.to_anm(&frames))
+    }
+
     #[allow(clippy::type_complexity)]
     pub fn destructure(
         self,
@@ -62,11 +111,15 @@ impl XfbinPage {
     }
 }
 
-impl From<XfbinFile> for Xfbin {
-    fn from(xfbin: XfbinFile) -> Self {
+impl TryFrom<XfbinFile> for Xfbin {
+    type Error = DekuError;
+
+    fn try_from(xfbin: XfbinFile) -> Result<Self, Self::Error> {
         let mut pages = Vec::new();
         let mut page = XfbinPage::default();
 
+        let endianness = xfbin.endianness;
+
         let chunk_names = &Vec::<String>::from(xfbin.index.chunk_names)[..];
         let file_paths = &Vec::<String>::from(xfbin.index.file_paths)[..];
         let chunk_types = &Vec::<String>::from(xfbin.index.chunk_types)[..];
@@ -101,7 +154,11 @@ impl From<XfbinFile> for Xfbin {
                 chunk_type,
             } = &struct_infos_mapped[struct_infos_index + chunk.chunk_map_index as usize];
 
-            let parsed = chunk.unpack(chunk_type);
+            let parsed = chunk.unpack(chunk_type, endianness.chunks).map_err(|err| {
+                DekuError::Parse(format!(
+                    "Could not read {chunk_type} chunk {chunk_name}: {err}"
+                ))
+            })?;
 
             match parsed.chunk_type() {
                 NuccChunkType::NuccChunkNull => continue,
@@ -133,11 +190,39 @@ impl From<XfbinFile> for Xfbin {
                 _ => (),
             }
 
-            let mut parsed_struct = Box::<dyn NuccStruct>::from(NuccStructConverter(
-                parsed,
-                &struct_infos_mapped[struct_infos_index..],
-                &struct_references[struct_references_index..],
-            ));
+            let page_struct_infos = &struct_infos_mapped[struct_infos_index..];
+            let page_struct_references = &struct_references[struct_references_index..];
+
+            let registered = parsed.downcast_ref::<NuccChunkUnknown>().and_then(|chunk| {
+                registry::read_struct(
+                    chunk,
+                    endianness.chunks,
+                    page_struct_infos,
+                    page_struct_references,
+                )
+            });
+
+            let mut parsed_struct = match registered {
+                Some(result) => result.map_err(|err| {
+                    DekuError::Parse(format!(
+                        "Could not read {chunk_type} chunk {chunk_name}: {err}"
+                    ))
+                })?,
+
+                // Chunks with indices outside of their page are kept as unknown chunks
+                None => Box::<dyn NuccStruct>::try_from(NuccStructConverter(
+                    parsed,
+                    page_struct_infos,
+                    page_struct_references,
+                ))
+                .unwrap_or_else(|_| {
+                    Box::new(NuccUnknown::from(NuccStructConverter(
+                        chunk.unpack_unknown(chunk_type),
+                        page_struct_infos,
+                        page_struct_references,
+                    )))
+                }),
+            };
 
             let struct_info = parsed_struct.struct_info_mut();
             struct_info.chunk_name = chunk_name.clone();
@@ -147,29 +232,33 @@ impl From<XfbinFile> for Xfbin {
             page.structs.push(parsed_struct);
         }
 
-        Self {
+        Ok(Self {
             version: xfbin.header.version,
+            endianness,
             pages,
-        }
+        })
     }
 }
 
-impl From<Xfbin> for XfbinFile {
-    fn from(xfbin: Xfbin) -> Self {
+impl TryFrom<Xfbin> for XfbinFile {
+    type Error = DekuError;
+
+    fn try_from(xfbin: Xfbin) -> Result<Self, Self::Error> {
         fn repack_struct(
             boxed: Box<dyn NuccChunk>,
             struct_info: NuccStructInfo,
             page_struct_infos: &mut HashMap<NuccStructInfo, u32>,
-        ) -> XfbinChunk {
+            endianness: Endianness,
+        ) -> Result<XfbinChunk, DekuError> {
             let struct_info_index = page_struct_infos.len() as u32;
             let chunk_map_index = *page_struct_infos
                 .entry(struct_info)
                 .or_insert(struct_info_index);
 
-            let mut chunk = XfbinChunk::repack(boxed);
+            let mut chunk = XfbinChunk::repack(boxed, endianness.chunks)?;
             chunk.chunk_map_index = chunk_map_index;
 
-            chunk
+            Ok(chunk)
         }
 
         let mut header = XfbinHeader::default();
@@ -189,7 +278,8 @@ impl From<Xfbin> for XfbinFile {
             Box::new(NuccChunkNull(xfbin.version)),
             NuccChunkNull::default_chunk_info(),
             &mut struct_infos_map,
-        );
+            xfbin.endianness,
+        )?;
         chunks.push(null_chunk);
 
         for page in xfbin.pages {
@@ -200,19 +290,40 @@ impl From<Xfbin> for XfbinFile {
                 Box::new(NuccChunkNull(xfbin.version)),
                 NuccChunkNull::default_chunk_info(),
                 &mut page_struct_infos,
-            );
+                xfbin.endianness,
+            )?;
             chunks.push(null_chunk);
 
             for nucc_struct in page_structs {
                 let struct_info = nucc_struct.struct_info().clone();
 
-                let boxed = Box::<dyn NuccChunk>::from(NuccChunkConverter(
-                    nucc_struct,
+                let boxed = if nucc_struct.chunk_type() == NuccChunkType::NuccChunkCustom {
+                    registry::write_struct(
+                        nucc_struct,
+                        xfbin.endianness.chunks,
+                        &mut page_struct_infos,
+                        &mut page_struct_references,
+                    )
+                } else {
+                    Box::<dyn NuccChunk>::try_from(NuccChunkConverter(
+                        nucc_struct,
+                        &mut page_struct_infos,
+                        &mut page_struct_references,
+                    ))
+                }
+                .map_err(|err| {
+                    DekuError::Parse(format!(
+                        "Could not write {} chunk {}: {err}",
+                        struct_info.chunk_type, struct_info.chunk_name
+                    ))
+                })?;
+
+                chunks.push(repack_struct(
+                    boxed,
+                    struct_info,
                     &mut page_struct_infos,
-                    &mut page_struct_references,
-                ));
-
-                chunks.push(repack_struct(boxed, struct_info, &mut page_struct_infos));
+                    xfbin.endianness,
+                )?);
             }
 
             // Add nuccChunkPage map
@@ -220,14 +331,16 @@ impl From<Xfbin> for XfbinFile {
                 Box::new(NuccChunkPage::default()),
                 NuccChunkIndex::default_chunk_info(),
                 &mut page_struct_infos,
-            );
+                xfbin.endianness,
+            )?;
 
             // Add nuccChunkIndex map
             repack_struct(
                 Box::new(NuccChunkIndex),
                 NuccChunkIndex::default_chunk_info(),
                 &mut page_struct_infos,
-            );
+                xfbin.endianness,
+            )?;
 
             // Create final nuccChunkPage
             let page_chunk = repack_struct(
@@ -238,7 +351,8 @@ impl From<Xfbin> for XfbinFile {
                 }),
                 NuccChunkPage::default_chunk_info(),
                 &mut page_struct_infos,
-            );
+                xfbin.endianness,
+            )?;
 
             chunks.push(page_chunk);
 
@@ -350,9 +464,10 @@ impl From<Xfbin> for XfbinFile {
             header,
             index,
             chunks,
+            endianness: xfbin.endianness,
         };
 
         xfbin_file.update().expect("Could not update Xfbin file.");
-        xfbin_file
+        Ok(xfbin_file)
     }
 }
@@ -1,26 +1,38 @@
 use deku::{bitvec::*, ctx::Endian, prelude::*};
 use std::{borrow::BorrowMut, marker::PhantomData, mem};
 
-use super::nucc_chunk::{NuccChunk, NuccChunkType};
+use super::endianness::Endianness;
+use super::nucc_chunk::{NuccChunk, NuccChunkType, NuccChunkUnknown};
 use super::utils::*;
 
 #[deku_derive(DekuRead, DekuWrite)]
-#[deku(endian = "big")]
+#[deku(
+    endian = "endian",
+    ctx = "endian: deku::ctx::Endian",
+    ctx_default = "Endian::Big"
+)]
 pub struct XfbinFile {
     pub header: XfbinHeader,
     pub index: XfbinIndex,
 
-    #[deku(reader = "XfbinFile::read_chunks(deku::rest)")]
+    #[deku(reader = "XfbinFile::read_chunks(deku::rest, endian)")]
     pub chunks: Vec<XfbinChunk>,
+
+    /// Set after reading, since the chunks' byte order can't be known from the container's.
+    #[deku(skip)]
+    pub endianness: Endianness,
 }
 
 impl XfbinFile {
-    fn read_chunks(input: &DekuBitSlice) -> Result<(&DekuBitSlice, Vec<XfbinChunk>), DekuError> {
+    fn read_chunks(
+        input: &DekuBitSlice,
+        endian: Endian,
+    ) -> Result<(&DekuBitSlice, Vec<XfbinChunk>), DekuError> {
         let mut chunks = Vec::new();
         let mut data = input;
 
         loop {
-            match XfbinChunk::read(data, Endian::Big) {
+            match XfbinChunk::read(data, endian) {
                 Ok((rest, value)) => {
                     chunks.push(value);
                     data = rest;
@@ -32,6 +44,36 @@ impl XfbinFile {
 
         Ok((data, chunks))
     }
+
+    /// Detects the chunks' byte order from the first nuccChunkPage, which only has two small counts.
+    /// Returns None if there are no pages.
+    pub fn detect_chunk_endian(&self) -> Option<Endian> {
+        let chunk_types = Vec::<String>::from(&self.index.chunk_types);
+
+        // Chunk map indices of the first page start at 0, so they don't need to be offset
+        let page_chunk = self.chunks.iter().find(|chunk| {
+            self.index
+                .chunk_map_indices
+                .get(chunk.chunk_map_index as usize)
+                .and_then(|i| self.index.chunk_maps.get(*i as usize))
+                .and_then(|map| chunk_types.get(map.chunk_type_index as usize))
+                .map_or(false, |chunk_type| {
+                    chunk_type == &NuccChunkType::NuccChunkPage.to_string()
+                })
+        });
+
+        match page_chunk.map(|chunk| &chunk.chunk_buffer[..]) {
+            Some([a0, a1, a2, a3, ..]) => {
+                let bytes = [*a0, *a1, *a2, *a3];
+                Some(if u32::from_be_bytes(bytes) <= u32::from_le_bytes(bytes) {
+                    Endian::Big
+                } else {
+                    Endian::Little
+                })
+            }
+            _ => None,
+        }
+    }
 }
 
 #[derive(Default, DekuRead, DekuWrite)]
@@ -151,6 +193,12 @@ impl From<Vec<String>> for XfbinDataBuffer<DekuString> {
 
 impl From<XfbinDataBuffer<DekuString>> for Vec<String> {
     fn from(value: XfbinDataBuffer<DekuString>) -> Self {
+        Self::from(&value)
+    }
+}
+
+impl From<&XfbinDataBuffer<DekuString>> for Vec<String> {
+    fn from(value: &XfbinDataBuffer<DekuString>) -> Self {
         let mut strings = Vec::new();
         let mut data: &DekuBitSlice = value.data.view_bits();
 
@@ -200,21 +248,69 @@ pub struct XfbinChunk {
 }
 
 impl XfbinChunk {
-    pub fn unpack(self, chunk_type: &str) -> Box<dyn NuccChunk> {
-        NuccChunkType::read_struct(self.chunk_buffer.view_bits(), chunk_type, self.version)
-            .map(|(_, value)| value)
-            .unwrap()
+    pub fn unpack(
+        &self,
+        chunk_type: &str,
+        endian: Endian,
+    ) -> Result<Box<dyn NuccChunk>, DekuError> {
+        NuccChunkType::read_struct(
+            self.chunk_buffer.view_bits(),
+            chunk_type,
+            endian,
+            self.version,
+        )
+        .map(|(_, value)| value)
     }
 
-    pub fn repack(boxed: Box<dyn NuccChunk>) -> Self {
+    /// Keeps the chunk's data as is, for chunks that can't be converted to their type's struct.
+    pub fn unpack_unknown(&self, chunk_type: &str) -> Box<dyn NuccChunk> {
+        Box::new(NuccChunkUnknown {
+            data: self.chunk_buffer.clone(),
+            chunk_type: chunk_type.to_string(),
+            version: self.version,
+        })
+    }
+
+    pub fn repack(boxed: Box<dyn NuccChunk>, endian: Endian) -> Result<Self, DekuError> {
         let mut value = Self::default();
         value.version = boxed.version();
-        value.chunk_buffer = NuccChunkType::write_struct(boxed, value.version)
-            .ok()
-            .unwrap()
-            .into_vec();
-        value.update().expect("Could not update Xfbin chunk.");
+        value.chunk_buffer = NuccChunkType::write_struct(boxed, endian, value.version)?.into_vec();
+        value.update()?;
+
+        Ok(value)
+    }
+}
 
-        value
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unpack_unsupported_curve_format() {
+        #[rustfmt::skip]
+        let data = [
+            0, 0, 0, 0, // frame
+            0, 1, // entry_count
+            0, 0, // unk
+            0, 0, 0, 0, // coord_index
+            0, 1, // entry_format: Coord
+            0, 1, // curve_count
+            0, 0, // curve_index
+            0, 7, // curve_format: Vector3Bezier
+            0, 1, // frame_count
+            0, 0, // unk_size_flags
+        ];
+
+        let chunk = XfbinChunk {
+            chunk_size: data.len() as u32,
+            chunk_map_index: 0,
+            version: 0x79,
+            chunk_buffer: data.to_vec(),
+        };
+
+        assert!(chunk.unpack("nuccChunkAnmStrmFrame", Endian::Big).is_err());
+        assert!(chunk
+            .unpack("nuccChunkAnmStrmFrame", Endian::Little)
+            .is_err());
     }
 }